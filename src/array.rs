@@ -0,0 +1,236 @@
+// array.rs
+
+use crate::expression::Expr;
+
+// 数组：元素按行优先顺序存放在一个 Vec 里。
+// 一维数组就是向量，可以带填充指针：length 和打印只看填充指针之前的元素，vector-push-extend 在填充指针处追加。
+#[derive(Clone, Debug)]
+pub struct Array {
+    dimensions: Vec<usize>,
+    elements: Vec<Expr>,
+    fill_pointer: Option<usize>,
+}
+
+// 数组元素总数的上限，即 Lisp 中的 array-total-size-limit；超过它的数组一次性分配会耗尽内存
+pub const TOTAL_SIZE_LIMIT: usize = 1 << 22;
+
+impl Array {
+    pub fn new(dimensions: Vec<usize>, initial_element: Expr) -> Result<Self, String> {
+        let size = Array::total_size(&dimensions)?;
+        Ok(Array { dimensions, elements: vec![initial_element; size], fill_pointer: None })
+    }
+
+    // 各维之积；乘法溢出或超过上限时报错
+    pub fn total_size(dimensions: &[usize]) -> Result<usize, String> {
+        dimensions
+            .iter()
+            .try_fold(1usize, |size, &dimension| size.checked_mul(dimension))
+            .filter(|&size| size <= TOTAL_SIZE_LIMIT)
+            .ok_or_else(|| {
+                let dimensions: Vec<String> = dimensions.iter().map(|d| d.to_string()).collect();
+                format!("dimensions ({}) exceed array-total-size-limit {}", dimensions.join(" "), TOTAL_SIZE_LIMIT)
+            })
+    }
+
+    pub fn vector(elements: Vec<Expr>) -> Self {
+        Array { dimensions: vec![elements.len()], elements, fill_pointer: None }
+    }
+
+    // 按嵌套列表构造 rank 维数组，用于 #2A((1 2) (3 4)) 和 :initial-contents
+    pub fn from_nested(rank: usize, contents: &Expr) -> Result<Self, String> {
+        let mut dimensions = Vec::with_capacity(rank);
+        let mut level = contents.clone();
+        for _ in 0..rank {
+            let items = Array::sequence_items(&level)?;
+            dimensions.push(items.len());
+            match items.into_iter().next() {
+                Some(first) => level = first,
+                None => break,
+            }
+        }
+        // 某一维为 0 时，更深的维度无从得知，按 0 处理
+        dimensions.resize(rank, 0);
+        let mut elements = Vec::with_capacity(dimensions.iter().product());
+        Array::flatten(contents, &dimensions, &mut elements)?;
+        Ok(Array { dimensions, elements, fill_pointer: None })
+    }
+
+    fn sequence_items(contents: &Expr) -> Result<Vec<Expr>, String> {
+        match contents {
+            Expr::List(items) => Ok(items.clone()),
+            Expr::Array(array) if array.borrow().rank() == 1 => Ok(array.borrow().active_elements().to_vec()),
+            Expr::Str(s) => Ok(s.chars().map(Expr::Char).collect()),
+            other => Err(format!("{} is not a sequence", other)),
+        }
+    }
+
+    fn flatten(contents: &Expr, dimensions: &[usize], elements: &mut Vec<Expr>) -> Result<(), String> {
+        let Some((&length, rest)) = dimensions.split_first() else {
+            elements.push(contents.clone());
+            return Ok(());
+        };
+        let items = Array::sequence_items(contents)?;
+        if items.len() != length {
+            return Err(format!("initial contents {} do not match the dimension {}", contents, length));
+        }
+        for item in &items {
+            Array::flatten(item, rest, elements)?;
+        }
+        Ok(())
+    }
+
+    pub fn with_fill_pointer(mut self, fill_pointer: usize) -> Self {
+        self.fill_pointer = Some(fill_pointer);
+        self
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    pub fn rank(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    pub fn fill_pointer(&self) -> Option<usize> {
+        self.fill_pointer
+    }
+
+    pub fn set_fill_pointer(&mut self, fill_pointer: usize) -> Result<(), String> {
+        if self.fill_pointer.is_none() {
+            return Err("array has no fill pointer".to_string());
+        }
+        if fill_pointer > self.elements.len() {
+            return Err(format!("fill pointer {} exceeds the array size {}", fill_pointer, self.elements.len()));
+        }
+        self.fill_pointer = Some(fill_pointer);
+        Ok(())
+    }
+
+    // 作为序列时可见的元素：有填充指针时只到填充指针为止
+    pub fn active_elements(&self) -> &[Expr] {
+        &self.elements[..self.fill_pointer.unwrap_or(self.elements.len())]
+    }
+
+    pub fn row_major_index(&self, subscripts: &[usize]) -> Result<usize, String> {
+        if subscripts.len() != self.rank() {
+            return Err(format!("expected {} subscripts, got {}", self.rank(), subscripts.len()));
+        }
+        let mut index = 0;
+        for (&subscript, &dimension) in subscripts.iter().zip(&self.dimensions) {
+            if subscript >= dimension {
+                return Err(format!("index {} is out of bounds for dimension {}", subscript, dimension));
+            }
+            index = index * dimension + subscript;
+        }
+        Ok(index)
+    }
+
+    pub fn get(&self, index: usize) -> &Expr {
+        &self.elements[index]
+    }
+
+    pub fn set(&mut self, index: usize, value: Expr) {
+        self.elements[index] = value;
+    }
+
+    // 把 value 放到填充指针处并返回它的下标；空间不够时按 extension 扩大，extension 为 0 时不扩大而返回 None
+    pub fn push(&mut self, value: Expr, extension: usize) -> Result<Option<usize>, String> {
+        let fill_pointer = self.fill_pointer.ok_or("vector has no fill pointer")?;
+        if fill_pointer == self.elements.len() {
+            if extension == 0 {
+                return Ok(None);
+            }
+            let size = fill_pointer.saturating_add(extension);
+            if size > TOTAL_SIZE_LIMIT {
+                return Err(format!("extending the vector to {} elements exceeds array-total-size-limit {}", size, TOTAL_SIZE_LIMIT));
+            }
+            self.elements.resize(size, Expr::nil());
+            self.dimensions[0] = self.elements.len();
+        }
+        self.elements[fill_pointer] = value;
+        self.fill_pointer = Some(fill_pointer + 1);
+        Ok(Some(fill_pointer))
+    }
+
+    // 去掉填充指针前的最后一个元素并返回它
+    pub fn pop(&mut self) -> Result<Expr, String> {
+        match self.fill_pointer.ok_or("vector has no fill pointer")? {
+            0 => Err("vector is empty".to_string()),
+            fill_pointer => {
+                self.fill_pointer = Some(fill_pointer - 1);
+                Ok(self.elements[fill_pointer - 1].clone())
+            }
+        }
+    }
+
+    // 向量打印为 #(1 2 3)，多维数组打印为 #2A((1 2) (3 4))，元素的打印方式由 item 决定
+    pub fn render(&self, item: &dyn Fn(&Expr) -> String) -> String {
+        if self.rank() == 1 {
+            let items: Vec<String> = self.active_elements().iter().map(item).collect();
+            return format!("#({})", items.join(" "));
+        }
+        format!("#{}A{}", self.rank(), self.render_level(0, 0, item))
+    }
+
+    fn render_level(&self, depth: usize, offset: usize, item: &dyn Fn(&Expr) -> String) -> String {
+        if depth == self.rank() {
+            return item(&self.elements[offset]);
+        }
+        let stride: usize = self.dimensions[depth + 1..].iter().product();
+        let items: Vec<String> = (0..self.dimensions[depth])
+            .map(|i| self.render_level(depth + 1, offset + i * stride, item))
+            .collect();
+        format!("({})", items.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(values: &[i64]) -> Expr {
+        Expr::List(values.iter().map(|&n| Expr::Number(n)).collect())
+    }
+
+    #[test]
+    fn test_row_major_layout_and_rendering() {
+        let contents = Expr::List(vec![numbers(&[1, 2, 3]), numbers(&[4, 5, 6])]);
+        let array = Array::from_nested(2, &contents).unwrap();
+        assert_eq!(array.dimensions(), &[2, 3]);
+        assert_eq!(array.row_major_index(&[1, 2]), Ok(5));
+        assert_eq!(array.get(4), &Expr::Number(5));
+        assert!(array.row_major_index(&[2, 0]).is_err());
+        assert!(array.row_major_index(&[0]).is_err());
+        assert_eq!(array.render(&|e| e.to_string()), "#2A((1 2 3) (4 5 6))");
+        assert!(Array::from_nested(2, &Expr::List(vec![numbers(&[1]), numbers(&[1, 2])])).is_err());
+    }
+
+    #[test]
+    fn test_fill_pointer_push_and_pop() {
+        let mut vector = Array::new(vec![2], Expr::Number(0)).unwrap().with_fill_pointer(0);
+        assert_eq!(vector.render(&|e| e.to_string()), "#()");
+        assert_eq!(vector.push(Expr::Number(7), 0), Ok(Some(0)));
+        assert_eq!(vector.push(Expr::Number(8), 0), Ok(Some(1)));
+        assert_eq!(vector.push(Expr::Number(9), 0), Ok(None));
+        assert_eq!(vector.push(Expr::Number(9), 4), Ok(Some(2)));
+        assert_eq!(vector.dimensions(), &[6]);
+        assert_eq!(vector.render(&|e| e.to_string()), "#(7 8 9)");
+        assert_eq!(vector.pop(), Ok(Expr::Number(9)));
+        assert_eq!(vector.active_elements().len(), 2);
+        assert!(Array::vector(vec![]).push(Expr::Number(1), 1).is_err());
+        let mut full = Array::new(vec![1], Expr::Number(0)).unwrap().with_fill_pointer(1);
+        assert!(full.push(Expr::Number(1), usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_total_size_limit() {
+        assert_eq!(Array::total_size(&[2, 3, 4]), Ok(24));
+        assert_eq!(Array::total_size(&[]), Ok(1));
+        assert_eq!(Array::total_size(&[TOTAL_SIZE_LIMIT]), Ok(TOTAL_SIZE_LIMIT));
+        assert!(Array::total_size(&[TOTAL_SIZE_LIMIT + 1]).is_err());
+        assert!(Array::total_size(&[100000, 100000]).is_err());
+        assert!(Array::total_size(&[usize::MAX, 2]).is_err());
+        assert!(Array::new(vec![100000, 100000], Expr::nil()).is_err());
+    }
+}
@@ -0,0 +1,553 @@
+// bignum.rs
+
+use std::cmp::Ordering;
+use std::fmt;
+
+// 任意精度整数：符号 + 以 2^32 为基数的小端序数位。
+// 数位末尾没有多余的 0；零表示为空数位且非负。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+const BASE: u64 = 1 << 32;
+
+impl BigInt {
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        BigInt { negative, magnitude }
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        BigInt::from_i128(value as i128)
+    }
+
+    pub fn from_i128(value: i128) -> Self {
+        let negative = value < 0;
+        let mut rest = value.unsigned_abs();
+        let mut magnitude = Vec::new();
+        while rest > 0 {
+            magnitude.push(rest as u32);
+            rest >>= 32;
+        }
+        BigInt::from_parts(negative, magnitude)
+    }
+
+    // 向零截断浮点数；非有限值返回 None
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let value = value.trunc();
+        if value.abs() < 9.0e18 {
+            return Some(BigInt::from_i64(value as i64));
+        }
+        // |value| >= 2^63，此时它是 尾数 * 2^指数 且指数为正
+        let bits = value.abs().to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64 - 1075;
+        let mut magnitude = BigInt::from_i64(((bits & ((1 << 52) - 1)) | (1 << 52)) as i64).magnitude;
+        for _ in 0..exponent {
+            BigInt::mul_small_add(&mut magnitude, 2, 0);
+        }
+        Some(BigInt::from_parts(value < 0.0, magnitude))
+    }
+
+    // 能放进 i64 时返回 Some
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let mut value: i128 = 0;
+        for &digit in self.magnitude.iter().rev() {
+            value = (value << 32) | digit as i128;
+        }
+        if self.negative {
+            value = -value;
+        }
+        i64::try_from(value).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let mut value = 0.0;
+        for &digit in self.magnitude.iter().rev() {
+            value = value * BASE as f64 + digit as f64;
+        }
+        if self.negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.magnitude.first().is_none_or(|digit| digit % 2 == 0)
+    }
+
+    pub fn is_one(&self) -> bool {
+        !self.negative && self.magnitude == [1]
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+
+    // 解析指定进制的整数字符串，允许前导 + 或 -
+    pub fn parse(text: &str, radix: u32) -> Option<BigInt> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        if digits.is_empty() {
+            return None;
+        }
+        let mut magnitude = Vec::new();
+        for ch in digits.chars() {
+            let digit = ch.to_digit(radix)?;
+            BigInt::mul_small_add(&mut magnitude, radix, digit);
+        }
+        Some(BigInt::from_parts(negative, magnitude))
+    }
+
+    // 用大写字母表示大于 9 的数字
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let mut digits = Vec::new();
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let remainder = BigInt::div_small(&mut rest, radix);
+            digits.push(std::char::from_digit(remainder, radix).unwrap().to_ascii_uppercase());
+        }
+        if self.negative {
+            digits.push('-');
+        }
+        digits.iter().rev().collect()
+    }
+
+    fn mul_small_add(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for digit in magnitude.iter_mut() {
+            let value = *digit as u64 * factor as u64 + carry;
+            *digit = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            magnitude.push(carry as u32);
+        }
+    }
+
+    // 原地除以一个小整数，返回余数
+    fn div_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for digit in magnitude.iter_mut().rev() {
+            let value = (remainder << 32) | *digit as u64;
+            *digit = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        remainder as u32
+    }
+
+    fn cmp_magnitude(left: &[u32], right: &[u32]) -> Ordering {
+        left.len()
+            .cmp(&right.len())
+            .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+    }
+
+    fn add_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(left.len().max(right.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..left.len().max(right.len()) {
+            let value = *left.get(i).unwrap_or(&0) as u64 + *right.get(i).unwrap_or(&0) as u64 + carry;
+            result.push(value as u32);
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            result.push(carry as u32);
+        }
+        result
+    }
+
+    // 要求 left >= right
+    fn sub_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(left.len());
+        let mut borrow = 0i64;
+        for (i, &digit) in left.iter().enumerate() {
+            let mut value = digit as i64 - *right.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if value < 0 {
+                value += BASE as i64;
+                borrow = 1;
+            }
+            result.push(value as u32);
+        }
+        result
+    }
+
+    fn mul_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+        let mut result = vec![0u32; left.len() + right.len()];
+        for (i, &l) in left.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &r) in right.iter().enumerate() {
+                let value = result[i + j] as u64 + l as u64 * r as u64 + carry;
+                result[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            result[i + right.len()] = carry as u32;
+        }
+        result
+    }
+
+    fn bit_length(magnitude: &[u32]) -> usize {
+        match magnitude.last() {
+            Some(top) => (magnitude.len() - 1) * 32 + (32 - top.leading_zeros() as usize),
+            None => 0,
+        }
+    }
+
+    // 逐位长除法，返回 (商, 余数) 的数位
+    fn divrem_magnitude(left: &[u32], right: &[u32]) -> (Vec<u32>, Vec<u32>) {
+        if BigInt::cmp_magnitude(left, right) == Ordering::Less {
+            return (Vec::new(), left.to_vec());
+        }
+        if right.len() == 1 {
+            let mut quotient = left.to_vec();
+            let remainder = BigInt::div_small(&mut quotient, right[0]);
+            return (quotient, vec![remainder]);
+        }
+        let mut quotient = vec![0u32; left.len()];
+        let mut remainder: Vec<u32> = Vec::new();
+        for bit in (0..BigInt::bit_length(left)).rev() {
+            // remainder = remainder * 2 + 当前位
+            BigInt::mul_small_add(&mut remainder, 2, (left[bit / 32] >> (bit % 32)) & 1);
+            if BigInt::cmp_magnitude(&remainder, right) != Ordering::Less {
+                remainder = BigInt::sub_magnitude(&remainder, right);
+                while remainder.last() == Some(&0) {
+                    remainder.pop();
+                }
+                quotient[bit / 32] |= 1 << (bit % 32);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, BigInt::add_magnitude(&self.magnitude, &other.magnitude));
+        }
+        match BigInt::cmp_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(other.negative, BigInt::sub_magnitude(&other.magnitude, &self.magnitude)),
+            _ => BigInt::from_parts(self.negative, BigInt::sub_magnitude(&self.magnitude, &other.magnitude)),
+        }
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    // 最大公约数，结果非负；gcd(0, 0) = 0
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while let Some((_, remainder)) = a.div_rem(&b) {
+            a = b;
+            b = remainder;
+        }
+        a
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, BigInt::mul_magnitude(&self.magnitude, &other.magnitude))
+    }
+
+    // 向零截断的除法，余数与被除数同号；除数为零时返回 None
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = BigInt::divrem_magnitude(&self.magnitude, &other.magnitude);
+        Some((
+            BigInt::from_parts(self.negative != other.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    // 以补码表示的 len 个数位（len 必须足以容纳符号位）
+    fn to_twos_complement(&self, len: usize) -> Vec<u32> {
+        let mut digits = self.magnitude.clone();
+        digits.resize(len, 0);
+        if self.negative {
+            let mut carry = 1u64;
+            for digit in digits.iter_mut() {
+                let value = (!*digit) as u64 + carry;
+                *digit = value as u32;
+                carry = value >> 32;
+            }
+        }
+        digits
+    }
+
+    fn from_twos_complement(mut digits: Vec<u32>) -> BigInt {
+        let negative = digits.last().is_some_and(|top| top >> 31 == 1);
+        if negative {
+            let mut carry = 1u64;
+            for digit in digits.iter_mut() {
+                let value = (!*digit) as u64 + carry;
+                *digit = value as u32;
+                carry = value >> 32;
+            }
+        }
+        BigInt::from_parts(negative, digits)
+    }
+
+    fn bitwise(&self, other: &BigInt, op: fn(u32, u32) -> u32) -> BigInt {
+        let len = self.magnitude.len().max(other.magnitude.len()) + 1;
+        let left = self.to_twos_complement(len);
+        let right = other.to_twos_complement(len);
+        BigInt::from_twos_complement(left.iter().zip(&right).map(|(l, r)| op(*l, *r)).collect())
+    }
+
+    pub fn and(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |l, r| l & r)
+    }
+
+    pub fn or(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |l, r| l | r)
+    }
+
+    pub fn xor(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |l, r| l ^ r)
+    }
+
+    // 按补码取反：-x - 1
+    pub fn not(&self) -> BigInt {
+        self.neg().sub(&BigInt::from_i64(1))
+    }
+
+    pub fn shift_left(&self, bits: usize) -> BigInt {
+        let mut magnitude = vec![0u32; bits / 32];
+        let mut carry = 0u32;
+        for &digit in &self.magnitude {
+            let value = ((digit as u64) << (bits % 32)) | carry as u64;
+            magnitude.push(value as u32);
+            carry = (value >> 32) as u32;
+        }
+        magnitude.push(carry);
+        BigInt::from_parts(self.negative, magnitude)
+    }
+
+    // 算术右移，即向负无穷取整地除以 2^bits
+    pub fn shift_right(&self, bits: usize) -> BigInt {
+        if self.negative {
+            // -x >> n = -((x - 1) >> n) - 1
+            return self.abs().sub(&BigInt::from_i64(1)).shift_right(bits).not();
+        }
+        let skip = bits / 32;
+        if skip >= self.magnitude.len() {
+            return BigInt::from_i64(0);
+        }
+        let shift = bits % 32;
+        let digits = &self.magnitude[skip..];
+        let magnitude = (0..digits.len())
+            .map(|i| {
+                let high = *digits.get(i + 1).unwrap_or(&0) as u64;
+                (((high << 32) | digits[i] as u64) >> shift) as u32
+            })
+            .collect();
+        BigInt::from_parts(false, magnitude)
+    }
+
+    // 补码表示下第 index 位是否为 1
+    pub fn bit(&self, index: usize) -> bool {
+        !self.shift_right(index).is_even()
+    }
+
+    // 非负数统计 1 的个数，负数统计补码中 0 的个数
+    pub fn count_ones(&self) -> usize {
+        if self.negative {
+            return self.not().count_ones();
+        }
+        self.magnitude.iter().map(|digit| digit.count_ones() as usize).sum()
+    }
+
+    // 不含符号位的补码位数
+    pub fn integer_length(&self) -> usize {
+        if self.negative {
+            return self.not().integer_length();
+        }
+        BigInt::bit_length(&self.magnitude)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => BigInt::cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => BigInt::cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string_radix(10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(text: &str) -> BigInt {
+        BigInt::parse(text, 10).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("+42").to_string(), "42");
+        assert_eq!(big("123456789012345678901234567890").to_string(), "123456789012345678901234567890");
+        assert_eq!(big("-98765432109876543210").to_string(), "-98765432109876543210");
+        assert_eq!(BigInt::parse("ff", 16), Some(BigInt::from_i64(255)));
+        assert_eq!(BigInt::from_i64(-255).to_string_radix(16), "-FF");
+        assert_eq!(BigInt::parse("12a", 10), None);
+        assert_eq!(BigInt::parse("-", 10), None);
+    }
+
+    #[test]
+    fn test_i64_round_trip() {
+        for value in [0, 1, -1, i64::MAX, i64::MIN, 4294967296, -4294967295] {
+            assert_eq!(BigInt::from_i64(value).to_i64(), Some(value));
+        }
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+        assert_eq!(big("-9223372036854775809").to_i64(), None);
+    }
+
+    #[test]
+    fn test_add_and_sub() {
+        let max = BigInt::from_i64(i64::MAX);
+        assert_eq!(max.add(&BigInt::from_i64(1)).to_string(), "9223372036854775808");
+        assert_eq!(big("-5").add(&big("3")).to_string(), "-2");
+        assert_eq!(big("5").sub(&big("8")).to_string(), "-3");
+        assert_eq!(big("18446744073709551616").sub(&big("1")).to_string(), "18446744073709551615");
+        assert_eq!(big("7").sub(&big("7")), BigInt::from_i64(0));
+    }
+
+    #[test]
+    fn test_mul() {
+        let value = big("123456789012345678901234567890");
+        assert_eq!(value.mul(&value).to_string(), "15241578753238836750495351562536198787501905199875019052100");
+        assert_eq!(big("-3").mul(&big("4")).to_string(), "-12");
+        assert_eq!(big("-3").mul(&BigInt::from_i64(0)), BigInt::from_i64(0));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let (q, r) = big("15241578753238836750495351562536198787501905199875019052101")
+            .div_rem(&big("123456789012345678901234567890"))
+            .unwrap();
+        assert_eq!(q.to_string(), "123456789012345678901234567890");
+        assert_eq!(r.to_string(), "1");
+
+        let (q, r) = big("-7").div_rem(&big("2")).unwrap();
+        assert_eq!((q.to_string(), r.to_string()), ("-3".to_string(), "-1".to_string()));
+        assert_eq!(big("1").div_rem(&BigInt::from_i64(0)), None);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(big("-100000000000000000000") < big("-1"));
+        assert!(big("100000000000000000000") > big("99999999999999999999"));
+        assert!(big("-2") < big("1"));
+        assert_eq!(big("10").cmp(&big("10")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(big("18446744073709551616").to_f64(), 18446744073709551616.0);
+        assert_eq!(big("-3").to_f64(), -3.0);
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(big("12").gcd(&big("-18")), big("6"));
+        assert_eq!(big("0").gcd(&big("-5")), big("5"));
+        assert_eq!(big("0").gcd(&big("0")), big("0"));
+        assert_eq!(
+            big("36893488147419103232").gcd(&big("55340232221128654848")),
+            big("18446744073709551616")
+        );
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(BigInt::from_f64(-3.7), Some(big("-3")));
+        assert_eq!(BigInt::from_f64(1e20), Some(big("100000000000000000000")));
+        assert_eq!(BigInt::from_f64(-1e20), Some(big("-100000000000000000000")));
+        assert_eq!(BigInt::from_f64(f64::NAN), None);
+        assert_eq!(BigInt::from_f64(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn test_bitwise_twos_complement() {
+        assert_eq!(big("12").and(&big("10")), big("8"));
+        assert_eq!(big("12").or(&big("10")), big("14"));
+        assert_eq!(big("12").xor(&big("10")), big("6"));
+        assert_eq!(big("-1").and(&big("255")), big("255"));
+        assert_eq!(big("-256").or(&big("15")), big("-241"));
+        assert_eq!(big("-18446744073709551616").and(&big("-1")), big("-18446744073709551616"));
+        assert_eq!(big("-18446744073709551617").and(&big("18446744073709551615")), big("18446744073709551615"));
+        assert_eq!(big("0").not(), big("-1"));
+        assert_eq!(big("-18446744073709551616").not(), big("18446744073709551615"));
+    }
+
+    #[test]
+    fn test_shifts() {
+        assert_eq!(big("1").shift_left(64), big("18446744073709551616"));
+        assert_eq!(big("-3").shift_left(33), big("-25769803776"));
+        assert_eq!(big("18446744073709551616").shift_right(63), big("2"));
+        assert_eq!(big("-1").shift_right(100), big("-1"));
+        assert_eq!(big("-5").shift_right(1), big("-3"));
+        assert_eq!(big("5").shift_right(100), big("0"));
+    }
+
+    #[test]
+    fn test_bit_queries() {
+        assert!(big("5").bit(2));
+        assert!(!big("5").bit(1));
+        assert!(big("-1").bit(1000));
+        assert_eq!(big("255").count_ones(), 8);
+        assert_eq!(big("-256").count_ones(), 8);
+        assert_eq!(big("18446744073709551616").integer_length(), 65);
+        assert_eq!(big("-1").integer_length(), 0);
+        assert_eq!(big("-257").integer_length(), 9);
+    }
+}
@@ -0,0 +1,59 @@
+// character.rs
+
+// 有名字的字符。读入时名字不区分大小写；输出时使用表中第一个对应该字符的名字。
+const NAMED_CHARACTERS: &[(&str, char)] = &[
+    ("Space", ' '),
+    ("Newline", '\n'),
+    ("Tab", '\t'),
+    ("Return", '\r'),
+    ("Page", '\x0C'),
+    ("Backspace", '\x08'),
+    ("Rubout", '\x7F'),
+    ("Escape", '\x1B'),
+    ("Null", '\0'),
+    ("Linefeed", '\n'),
+    ("Nul", '\0'),
+    ("Delete", '\x7F'),
+];
+
+// #\ 之后的名字：Space、Newline 等，或者 U+XXXX 形式的码点
+pub fn from_name(name: &str) -> Option<char> {
+    if let Some(&(_, ch)) = NAMED_CHARACTERS.iter().find(|(known, _)| known.eq_ignore_ascii_case(name)) {
+        return Some(ch);
+    }
+    let hex = name.strip_prefix("U+").or_else(|| name.strip_prefix("u+"))?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+// 字符的可读形式，去掉 #\ 前缀；读回来得到同一个字符
+pub fn name_of(ch: char) -> String {
+    match NAMED_CHARACTERS.iter().find(|(_, known)| *known == ch) {
+        Some((name, _)) => name.to_string(),
+        None if ch.is_control() => format!("U+{:04X}", ch as u32),
+        None => ch.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        assert_eq!(from_name("space"), Some(' '));
+        assert_eq!(from_name("NEWLINE"), Some('\n'));
+        assert_eq!(from_name("Linefeed"), Some('\n'));
+        assert_eq!(from_name("U+3BB"), Some('λ'));
+        assert_eq!(from_name("Bogus"), None);
+        assert_eq!(name_of(' '), "Space");
+        assert_eq!(name_of('\n'), "Newline");
+        assert_eq!(name_of('\u{1}'), "U+0001");
+        assert_eq!(name_of('λ'), "λ");
+        for ch in [' ', '\n', '\t', '\0', '\u{7F}', '\u{85}', 'a', '字'] {
+            let name = name_of(ch);
+            if name.chars().count() > 1 {
+                assert_eq!(from_name(&name), Some(ch));
+            }
+        }
+    }
+}
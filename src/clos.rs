@@ -0,0 +1,215 @@
+// clos.rs
+
+use crate::expression::Expr;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// defclass 定义的类。precedence_list 是类优先级列表：自身在前，之后是全部超类，最后是 standard-object 和 t
+#[derive(Debug)]
+pub struct Class {
+    pub name: String,
+    pub precedence_list: Vec<String>,
+    // 合并了超类之后的有效槽，超类的槽排在前面
+    pub slots: Vec<SlotDefinition>,
+    // defclass 直接声明的槽，子类合并槽时使用
+    pub direct_slots: Vec<SlotDefinition>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SlotDefinition {
+    pub name: String,
+    // 初始化参数，例如 :radius
+    pub initargs: Vec<String>,
+    // 没有对应的初始化参数时求值的形式；没有时槽保持未绑定
+    pub initform: Option<Expr>,
+}
+
+impl Class {
+    pub fn slot_index(&self, name: &str) -> Option<usize> {
+        self.slots.iter().position(|slot| slot.name == name)
+    }
+}
+
+// 类的实例；未绑定的槽为 None
+#[derive(Debug)]
+pub struct Instance {
+    class: Rc<Class>,
+    slots: Vec<Option<Expr>>,
+}
+
+impl Instance {
+    pub fn new(class: Rc<Class>, slots: Vec<Option<Expr>>) -> Self {
+        Instance { class, slots }
+    }
+
+    pub fn class(&self) -> &Rc<Class> {
+        &self.class
+    }
+
+    pub fn slot(&self, name: &str) -> Option<&Option<Expr>> {
+        self.class.slot_index(name).map(|index| &self.slots[index])
+    }
+
+    // 槽存在时写入并返回 true
+    pub fn set_slot(&mut self, name: &str, value: Expr) -> bool {
+        match self.class.slot_index(name) {
+            Some(index) => {
+                self.slots[index] = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Qualifier {
+    Primary,
+    Before,
+    After,
+    Around,
+}
+
+impl Qualifier {
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            ":before" => Some(Qualifier::Before),
+            ":after" => Some(Qualifier::After),
+            ":around" => Some(Qualifier::Around),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Method {
+    pub qualifier: Qualifier,
+    // 每个必需形参的特化类名，没有特化的形参为 t
+    pub specializers: Vec<String>,
+    // 去掉特化之后的普通 lambda 列表
+    pub params: Vec<Expr>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct GenericFunction {
+    pub required: usize,
+    pub methods: Vec<Rc<Method>>,
+}
+
+impl GenericFunction {
+    pub fn new(required: usize) -> Self {
+        GenericFunction { required, methods: Vec::new() }
+    }
+
+    // 限定符和特化类都相同的方法会被替换，其余情况追加
+    pub fn add_method(&mut self, method: Method) {
+        let existing = self
+            .methods
+            .iter()
+            .position(|m| m.qualifier == method.qualifier && m.specializers == method.specializers);
+        match existing {
+            Some(index) => self.methods[index] = Rc::new(method),
+            None => self.methods.push(Rc::new(method)),
+        }
+    }
+}
+
+// 内置类型对应的类，以及它们的优先级列表
+pub const BUILTIN_CLASSES: &[&str] = &[
+    "t", "number", "real", "rational", "integer", "ratio", "float", "complex", "character", "string", "vector", "array",
+    "sequence", "symbol", "null", "list", "cons", "hash-table", "stream", "random-state", "function", "standard-object",
+    "structure-object",
+];
+
+pub fn builtin_precedence_list(value: &Expr) -> &'static [&'static str] {
+    match value {
+        Expr::Number(_) | Expr::BigInt(_) => &["integer", "rational", "real", "number", "t"],
+        Expr::Ratio(_) => &["ratio", "rational", "real", "number", "t"],
+        Expr::Float(_) => &["float", "real", "number", "t"],
+        Expr::Complex(_, _) => &["complex", "number", "t"],
+        Expr::Char(_) => &["character", "t"],
+        Expr::Str(_) => &["string", "vector", "array", "sequence", "t"],
+        Expr::List(items) if items.is_empty() => &["null", "symbol", "list", "sequence", "t"],
+        Expr::Symbol(_) if !value.is_truthy() => &["null", "symbol", "list", "sequence", "t"],
+        Expr::Symbol(_) => &["symbol", "t"],
+        // lambda 表达式就是函数对象
+        Expr::List(items) if items.first() == Some(&Expr::Symbol("lambda".to_string())) => &["function", "t"],
+        Expr::List(_) | Expr::DottedPair(_, _) => &["cons", "list", "sequence", "t"],
+        Expr::Array(array) if array.borrow().rank() == 1 => &["vector", "array", "sequence", "t"],
+        Expr::Array(_) => &["array", "t"],
+        Expr::HashTable(_) => &["hash-table", "t"],
+        Expr::Stream(_) => &["stream", "t"],
+        Expr::RandomState(_) => &["random-state", "t"],
+        Expr::Struct(_) => &["structure-object", "t"],
+        Expr::Instance(_) => &["standard-object", "t"],
+        Expr::Macro(_, _) => &["t"],
+    }
+}
+
+// 任意值的类优先级列表：实例用它的类，结构实例用结构类型及其祖先，其余用内置类
+pub fn precedence_list(value: &Expr) -> Vec<String> {
+    let mut list = match value {
+        Expr::Instance(instance) => return instance.borrow().class().precedence_list.clone(),
+        Expr::Struct(instance) => instance.borrow().structure().includes.clone(),
+        _ => Vec::new(),
+    };
+    list.extend(builtin_precedence_list(value).iter().map(|name| name.to_string()));
+    list
+}
+
+// C3 线性化：每个类排在它的超类之前，并保持每个类的直接超类的声明顺序。
+// supers 是新类的直接超类名，lists 是它们各自的优先级列表（不含末尾的 standard-object 和 t）
+pub fn linearize(name: &str, supers: &[String], lists: &HashMap<String, Vec<String>>) -> Result<Vec<String>, String> {
+    let mut sequences: Vec<Vec<String>> = supers.iter().map(|s| lists[s].clone()).collect();
+    sequences.push(supers.to_vec());
+    let mut result = vec![name.to_string()];
+    loop {
+        sequences.retain(|sequence| !sequence.is_empty());
+        if sequences.is_empty() {
+            return Ok(result);
+        }
+        // 选第一个不出现在任何序列尾部的候选
+        let candidate = sequences
+            .iter()
+            .map(|sequence| &sequence[0])
+            .find(|candidate| !sequences.iter().any(|sequence| sequence[1..].contains(candidate)))
+            .cloned()
+            .ok_or_else(|| format!("inconsistent superclass order for {}", name))?;
+        for sequence in sequences.iter_mut() {
+            if sequence[0] == candidate {
+                sequence.remove(0);
+            }
+        }
+        result.push(candidate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_c3_linearization() {
+        let mut lists = HashMap::new();
+        lists.insert("a".to_string(), names(&["a"]));
+        lists.insert("b".to_string(), names(&["b", "a"]));
+        lists.insert("c".to_string(), names(&["c", "a"]));
+        assert_eq!(linearize("d", &names(&["b", "c"]), &lists), Ok(names(&["d", "b", "c", "a"])));
+        lists.insert("x".to_string(), names(&["x", "b", "c", "a"]));
+        lists.insert("y".to_string(), names(&["y", "c", "b", "a"]));
+        assert!(linearize("z", &names(&["x", "y"]), &lists).is_err());
+    }
+
+    #[test]
+    fn test_builtin_precedence() {
+        assert_eq!(precedence_list(&Expr::Number(1)), names(&["integer", "rational", "real", "number", "t"]));
+        assert_eq!(precedence_list(&Expr::nil())[0], "null");
+        assert_eq!(precedence_list(&Expr::List(vec![Expr::Number(1)]))[0], "cons");
+        assert_eq!(precedence_list(&Expr::Str(String::new()))[0], "string");
+    }
+}
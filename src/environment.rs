@@ -2,12 +2,39 @@
 use crate::operator::initialize as operator_initialize;
 use std::collections::HashMap;
 use crate::expression::Expr;
+use crate::exception::LispError;
+use crate::source::{SourceMap, SourcePosition};
+use crate::values::MultipleValues;
+use crate::operator::random::Random;
+use crate::random::RandomState;
+use crate::stream::OutputStream;
+use crate::array::TOTAL_SIZE_LIMIT;
+use crate::structure::StructType;
+use crate::clos::{Class, GenericFunction};
+use crate::package::Packages;
+use std::cell::{RefCell, RefMut};
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct Environment {
     symbols: HashMap<String, Expr>,
     functions: HashMap<String, Expr>,
     macros: HashMap<String, Expr>,
+    // 文档字符串，以 (名称, 文档类型) 为键，例如 ("fib", "function")
+    documentation: HashMap<(String, String), String>,
+    // defstruct 定义的结构类型
+    structures: HashMap<String, Rc<StructType>>,
+    // defclass 定义的类和泛型函数；泛型函数的方法表在所有环境副本之间共享
+    classes: HashMap<String, Rc<Class>>,
+    generics: HashMap<String, Rc<RefCell<GenericFunction>>>,
+    // 符号的属性表，依次存放指示符和值；属性属于符号本身，在所有环境副本之间共享
+    plists: Rc<RefCell<HashMap<String, Vec<Expr>>>>,
+    // 包系统和当前包，读取器据此解析符号，在所有环境副本之间共享
+    packages: Rc<RefCell<Packages>>,
+    // 源码位置表在所有环境副本之间共享
+    source_map: Rc<RefCell<SourceMap>>,
+    // 多值通道同样在所有环境副本之间共享
+    values: Rc<RefCell<MultipleValues>>,
 }
 
 impl Environment {
@@ -17,12 +44,29 @@ impl Environment {
             symbols: HashMap::new(),
             functions: HashMap::new(),
             macros: HashMap::new(),
+            documentation: HashMap::new(),
+            structures: HashMap::new(),
+            classes: HashMap::new(),
+            generics: HashMap::new(),
+            plists: Rc::new(RefCell::new(HashMap::new())),
+            packages: Rc::new(RefCell::new(Packages::new(std::iter::empty()))),
+            source_map: Rc::new(RefCell::new(SourceMap::default())),
+            values: Rc::new(RefCell::new(MultipleValues::default())),
         };
         // 预定义一些 Lisp 常用符号
-        env.set_symbol("T".to_string(), Expr::Symbol("T".to_string()));
-        env.set_symbol("t".to_string(), Expr::Symbol("T".to_string())); // t 也表示真
-        env.set_symbol("NIL".to_string(), Expr::List(vec![])); // NIL 表示空列表
-        env.set_symbol("nil".to_string(), Expr::List(vec![])); // nil 也表示空列表
+        env.set_symbol("T".to_string(), Expr::t()); // T 和 t 是同一个规范真值
+        env.set_symbol("t".to_string(), Expr::t());
+        env.set_symbol("NIL".to_string(), Expr::nil()); // NIL 表示空列表
+        env.set_symbol("nil".to_string(), Expr::nil()); // nil 也表示空列表
+        env.set_symbol("*print-base*".to_string(), Expr::Number(10)); // 有理数的输出进制
+        env.set_symbol("*print-radix*".to_string(), Expr::nil()); // 是否输出进制前缀
+        env.set_symbol("array-total-size-limit".to_string(), Expr::Number(TOTAL_SIZE_LIMIT as i64)); // 数组元素总数的上限
+        // 默认随机状态使用固定种子，同一个脚本每次运行得到同样的结果
+        env.set_symbol("*random-state*".to_string(), Random::make_state(RandomState::from_seed(0)));
+        // format 等输出操作的默认目的地
+        env.set_symbol("*standard-output*".to_string(), Expr::Stream(Rc::new(RefCell::new(OutputStream::stdout()))));
+        // 预定义的变量都属于 common-lisp 包
+        env.packages = Rc::new(RefCell::new(Packages::new(env.symbols.keys())));
         env
     }
 
@@ -34,7 +78,12 @@ impl Environment {
         self.symbols.insert(symbol, value);
     }
 
+    pub fn remove_symbol(&mut self, symbol: &str) -> Option<Expr> {
+        self.symbols.remove(symbol)
+    }
+
     pub fn set_function(&mut self, name: String, func: Expr) {
+        self.source_map.borrow_mut().define_function(&name);
         self.functions.insert(name, func);
     }
 
@@ -49,4 +98,120 @@ impl Environment {
     pub fn get_macro(&self, name: &str) -> Option<&Expr> {
         self.macros.get(name)
     }
+
+    pub fn set_documentation(&mut self, name: &str, doc_type: &str, doc: String) {
+        self.documentation.insert((name.to_string(), doc_type.to_string()), doc);
+    }
+
+    pub fn remove_documentation(&mut self, name: &str, doc_type: &str) {
+        self.documentation.remove(&(name.to_string(), doc_type.to_string()));
+    }
+
+    pub fn get_documentation(&self, name: &str, doc_type: &str) -> Option<&String> {
+        self.documentation.get(&(name.to_string(), doc_type.to_string()))
+    }
+
+    pub fn set_structure(&mut self, name: String, structure: Rc<StructType>) {
+        self.structures.insert(name, structure);
+    }
+
+    pub fn get_structure(&self, name: &str) -> Option<&Rc<StructType>> {
+        self.structures.get(name)
+    }
+
+    pub fn set_class(&mut self, name: String, class: Rc<Class>) {
+        self.classes.insert(name, class);
+    }
+
+    pub fn get_class(&self, name: &str) -> Option<&Rc<Class>> {
+        self.classes.get(name)
+    }
+
+    pub fn set_generic(&mut self, name: String, generic: Rc<RefCell<GenericFunction>>) {
+        self.generics.insert(name, generic);
+    }
+
+    pub fn get_generic(&self, name: &str) -> Option<&Rc<RefCell<GenericFunction>>> {
+        self.generics.get(name)
+    }
+
+    pub fn get_plist(&self, symbol: &str) -> Vec<Expr> {
+        self.plists.borrow().get(symbol).cloned().unwrap_or_default()
+    }
+
+    pub fn set_plist(&self, symbol: &str, plist: Vec<Expr>) {
+        let mut plists = self.plists.borrow_mut();
+        if plist.is_empty() {
+            plists.remove(symbol);
+        } else {
+            plists.insert(symbol.to_string(), plist);
+        }
+    }
+
+    pub fn packages(&self) -> RefMut<'_, Packages> {
+        self.packages.borrow_mut()
+    }
+
+    // 打印时按当前包决定符号的写法，打印选项持有同一个包系统
+    pub fn shared_packages(&self) -> Rc<RefCell<Packages>> {
+        self.packages.clone()
+    }
+
+    pub fn begin_read(&self) {
+        self.source_map.borrow_mut().begin_read();
+    }
+
+    pub fn record_source(&self, form: &Expr, position: SourcePosition) {
+        self.source_map.borrow_mut().record(form, position);
+    }
+
+    pub fn finish_read(&self) {
+        self.source_map.borrow_mut().finish_read();
+    }
+
+    pub fn enter_function(&self, name: &str) -> bool {
+        self.source_map.borrow_mut().enter_function(name)
+    }
+
+    pub fn leave_function(&self) {
+        self.source_map.borrow_mut().leave_function();
+    }
+
+    // 为求值 form 时产生的错误补上源码位置
+    pub fn locate_error(&self, form: &Expr, err: LispError) -> LispError {
+        if err.position().is_some() {
+            return err;
+        }
+        match self.source_map.borrow().lookup(form) {
+            Some(position) => err.with_position(position.clone()),
+            None => err,
+        }
+    }
+
+    pub fn clear_values(&self) {
+        self.values.borrow_mut().clear();
+    }
+
+    pub fn enter_form(&self) -> usize {
+        self.values.borrow_mut().enter()
+    }
+
+    pub fn leave_form(&self, depth: usize, passes_through: bool) {
+        self.values.borrow_mut().leave(depth, passes_through);
+    }
+
+    pub fn form_depth(&self) -> usize {
+        self.values.borrow().depth()
+    }
+
+    // 返回多个值的操作符调用：登记全部值，并返回主值（没有值时为 nil）
+    pub fn set_values(&self, values: Vec<Expr>) -> Expr {
+        let primary = values.first().cloned().unwrap_or_else(Expr::nil);
+        self.values.borrow_mut().set(values);
+        primary
+    }
+
+    pub fn take_values(&self, depth: usize) -> Option<Vec<Expr>> {
+        self.values.borrow_mut().take(depth)
+    }
 }
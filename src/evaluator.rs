@@ -7,49 +7,80 @@ use crate::operator::lambda::Lambda;
 
 pub struct Evaluator;
 
+const PASS_THROUGH_OPERATORS: &[&str] = &[
+    "progn",
+    "cond",
+    "multiple-value-bind",
+    "%call-generic",
+    "call-next-method",
+    "typecase",
+    "etypecase",
+    "check-type",
+    "destructuring-bind",
+];
+
 impl Evaluator {
     pub fn eval(ast: &Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        env.clear_values();
         match ast {
+            Expr::Symbol(_) if ast.is_keyword() => Ok(ast.clone()),
             Expr::Symbol(symbol) => {
                 env.get_symbol(symbol)
                     .cloned()
                     .ok_or_else(|| LispError::new(&format!("Undefined symbol: {}", symbol)))
             }
-            Expr::Number(_) | Expr::Float(_) | Expr::Str(_) => Ok(ast.clone()),
+            Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_) | Expr::Complex(_, _) | Expr::Char(_) | Expr::Str(_) | Expr::RandomState(_) | Expr::Stream(_) | Expr::Array(_) | Expr::HashTable(_) | Expr::Struct(_) | Expr::Instance(_) => Ok(ast.clone()),
+            // 点对和不正规列表是数据，不是函数调用
+            Expr::DottedPair(_, _) => Ok(ast.clone()),
             Expr::List(list) => {
                 if list.is_empty() {
                     return Ok(Expr::List(vec![]));
                 }
-                let first = &list[0];
-                match first {
-                    Expr::Symbol(s) => {
-                        if let Some(operator_fn) = OperatorRegistry::get(s) {
-                            operator_fn(&list[1..], env)
-                        } else {
-                            Lambda::eval_function_call(s, &list[1..], env)
-                        }
-                    }
-                    Expr::List(_) => {
-                        let func = Evaluator::eval(&list[0], env)?;
-                        if let Expr::List(func_list) = func {
-                            if func_list.len() >= 3 && func_list[0] == Expr::Symbol("lambda".to_string()) {
-                                Lambda::eval_lambda_call(&func_list[1..], &list[1..], env)
-                            } else {
-                                Err(LispError::new("Invalid lambda"))
-                            }
-                        } else {
-                            Err(LispError::new("Invalid expression"))
-                        }
+                let depth = env.enter_form();
+                // 运行时错误带上该形式在源码中的位置
+                let result = Evaluator::eval_list(list, env).map_err(|err| env.locate_error(ast, err));
+                env.leave_form(depth, Evaluator::passes_values_through(&list[0]));
+                result
+            }
+            Expr::Macro(_, _) => Err(LispError::new("Macros should be expanded before evaluation")),
+        }
+    }
+
+    // 这些形式的值就是它们最后求值的子形式的值，因此子形式的多值可以原样传出；
+    // 函数调用同样如此（函数体是一个 progn）
+    fn passes_values_through(operator: &Expr) -> bool {
+        match operator {
+            Expr::Symbol(s) => match OperatorRegistry::get(s) {
+                Some(_) => PASS_THROUGH_OPERATORS.contains(&s.as_str()),
+                None => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn eval_list(list: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let first = &list[0];
+        match first {
+            Expr::Symbol(s) => {
+                if let Some(operator_fn) = OperatorRegistry::get(s) {
+                    operator_fn(&list[1..], env)
+                } else {
+                    Lambda::eval_function_call(s, &list[1..], env)
+                }
+            }
+            Expr::List(_) => {
+                let func = Evaluator::eval(&list[0], env)?;
+                if let Expr::List(func_list) = func {
+                    if func_list.len() >= 3 && func_list[0] == Expr::Symbol("lambda".to_string()) {
+                        Lambda::eval_lambda_call(&func_list[1..], &list[1..], env)
+                    } else {
+                        Err(LispError::new("Invalid lambda"))
                     }
-                    _ => Err(LispError::new("Cannot evaluate a list without a valid operator")),
+                } else {
+                    Err(LispError::new("Invalid expression"))
                 }
             }
-            Expr::DottedPair(car, cdr) => {
-                let car_expr = Evaluator::eval(car, env)?;
-                let cdr_expr = Evaluator::eval(cdr, env)?;
-                Ok(Expr::DottedPair(Box::new(car_expr), Box::new(cdr_expr)))
-            },
-            Expr::Macro(_, _) => Err(LispError::new("Macros should be expanded before evaluation")),
+            _ => Err(LispError::new("Cannot evaluate a list without a valid operator")),
         }
     }
 }
@@ -153,6 +184,18 @@ mod tests {
         assert_eq!(result, Err(LispError::new("Undefined symbol: undefined")));
     }
 
+    // 测试关键字求值为自身
+    #[test]
+    fn test_eval_keyword_is_self_evaluating() {
+        let mut env = setup_environment();
+        let keyword = Expr::Symbol(":size".to_string());
+        assert_eq!(Evaluator::eval(&keyword, &mut env), Ok(keyword.clone()));
+        assert_eq!(keyword.to_string(), ":size");
+        // 单独的冒号不是关键字
+        let colon = Expr::Symbol(":".to_string());
+        assert_eq!(Evaluator::eval(&colon, &mut env), Err(LispError::new("Undefined symbol: :")));
+    }
+
     // 测试嵌套表达式
     #[test]
     fn test_eval_nested_expression() {
@@ -303,4 +346,46 @@ mod tests {
         let result = Evaluator::eval(&invalid_expression, &mut env);
         assert_eq!(result, Err(LispError::new("Cannot evaluate a list without a valid operator")));
     }
+
+    #[test]
+    fn test_eval_dotted_pair_is_data() {
+        let mut env = setup_environment();
+        // 两半都不会被求值，即使它们看起来像函数调用或变量
+        let pair = Expr::DottedPair(
+            Box::new(Expr::List(vec![Expr::Symbol("car".to_string()), Expr::Number(5)])),
+            Box::new(Expr::Symbol("undefined".to_string())),
+        );
+        assert_eq!(Evaluator::eval(&pair, &mut env), Ok(pair.clone()));
+    }
+
+    #[test]
+    fn test_eval_error_reports_source_position() {
+        let mut env = setup_environment();
+        let input = "(defun broken (x)\n  (+ x (car 5)))";
+        let defun = crate::parser::Parser::read_from(input, "broken.lisp", 1, &mut env).unwrap();
+        Evaluator::eval(&defun, &mut env).unwrap();
+
+        // 函数体被克隆后，错误仍然指向最内层出错形式的位置
+        let call = crate::parser::Parser::read_from("(broken 1)", "main.lisp", 7, &mut env).unwrap();
+        let err = Evaluator::eval(&call, &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("car: argument must be a list"));
+        assert_eq!(err.to_string(), "car: argument must be a list at broken.lisp:2:8");
+    }
+
+    #[test]
+    fn test_similar_forms_report_their_own_positions() {
+        let mut env = setup_environment();
+        let input = "(progn\n  (car 1.5)\n  (car 2.5))";
+        let form = crate::parser::Parser::read_from(input, "floats.lisp", 1, &mut env).unwrap();
+        let Expr::List(items) = &form else { panic!("expected a list") };
+        let err = Evaluator::eval(&items[2], &mut env).unwrap_err();
+        assert_eq!(err.to_string(), "car: argument must be a list at floats.lisp:3:3");
+
+        let input = "(progn\n  (car (+ (* 1 1)))\n  (car (+ (* 1 2))))";
+        let form = crate::parser::Parser::read_from(input, "deep.lisp", 1, &mut env).unwrap();
+        let Expr::List(items) = &form else { panic!("expected a list") };
+        let err = Evaluator::eval(&items[2], &mut env).unwrap_err();
+        assert_eq!(err.to_string(), "car: argument must be a list at deep.lisp:3:3");
+    }
 }
+
@@ -1,21 +1,47 @@
 // exception.rs
 
-#[derive(Debug, PartialEq)]
+use crate::source::SourcePosition;
+
+#[derive(Debug)]
 pub struct LispError {
     message: String,
+    position: Option<SourcePosition>,
 }
 
 impl LispError {
     pub fn new(message: &str) -> Self {
         LispError {
             message: message.to_string(),
+            position: None,
         }
     }
+
+    // 附加源码位置；已有位置时保留最内层（最先附加）的那个
+    pub fn with_position(mut self, position: SourcePosition) -> Self {
+        if self.position.is_none() {
+            self.position = Some(position);
+        }
+        self
+    }
+
+    pub fn position(&self) -> Option<&SourcePosition> {
+        self.position.as_ref()
+    }
+}
+
+// 错误只按消息比较，位置信息不影响相等性
+impl PartialEq for LispError {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+    }
 }
 
 impl std::fmt::Display for LispError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+        match &self.position {
+            Some(position) => write!(f, "{} at {}", self.message, position),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 
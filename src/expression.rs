@@ -1,31 +1,75 @@
 // expression.rs
 
+use crate::array::Array;
+use crate::bignum::BigInt;
+use crate::character;
+use crate::clos::Instance;
+use crate::hash_table::HashTable;
+use crate::package;
+use crate::ratio::Ratio;
+use crate::random::RandomState;
+use crate::stream::OutputStream;
+use crate::structure::StructInstance;
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
 pub enum Expr {
     Symbol(String),
     Number(i64),
+    // 超出 i64 范围的整数；能放进 i64 的值总是用 Number 表示
+    BigInt(BigInt),
+    // 分母不为 1 的精确有理数
+    Ratio(Ratio),
     Float(f64),
+    // 复数：实部和虚部都是实数，且要么都是浮点数，要么都是有理数（此时虚部不为 0）
+    Complex(Box<Expr>, Box<Expr>),
+    Char(char),
     Str(String),
     List(Vec<Expr>),
     DottedPair(Box<Expr>, Box<Expr>),
     Macro(Vec<Expr>, Box<Expr>),
+    // 随机数状态是可变对象：random 推进的是共享的同一个状态
+    RandomState(Rc<RefCell<RandomState>>),
+    // 输出流同样是共享的可变对象
+    Stream(Rc<RefCell<OutputStream>>),
+    // 数组和向量可以原地修改，所有引用看到的是同一个数组
+    Array(Rc<RefCell<Array>>),
+    HashTable(Rc<RefCell<HashTable>>),
+    // defstruct 定义的结构实例，槽可以原地修改
+    Struct(Rc<RefCell<StructInstance>>),
+    // defclass 定义的类的实例
+    Instance(Rc<RefCell<Instance>>),
 }
 
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expr::Symbol(s) => write!(f, "{}", s),
+            Expr::Symbol(s) => match package::uninterned_name(s) {
+                Some(name) => write!(f, "#:{}", name),
+                None => write!(f, "{}", s),
+            },
             Expr::Number(n) => write!(f, "{}", n),
-            Expr::Float(n) => write!(f, "{}", n),
+            Expr::BigInt(n) => write!(f, "{}", n),
+            Expr::Ratio(r) => write!(f, "{}", r),
+            // Debug 格式总是带小数点或指数，读回来仍然是浮点数
+            Expr::Float(n) => write!(f, "{:?}", n),
+            Expr::Complex(re, im) => write!(f, "#C({} {})", re, im),
+            Expr::Char(c) => write!(f, "#\\{}", character::name_of(*c)),
             Expr::Str(s) => write!(f, "\"{}\"", s.replace("\"", "\\\"")), // 正确处理引号的转义
             Expr::List(list) => {
                 let list_str: Vec<String> = list.iter().map(|expr| format!("{}", expr)).collect();
                 write!(f, "({})", list_str.join(" "))
             },
-            Expr::DottedPair(car, cdr) => write!(f, "({} . {})", car, cdr),
+            Expr::DottedPair(car, cdr) => write!(f, "{}", Expr::render_dotted(car, cdr, &|item| item.to_string())),
             Expr::Macro(_, _) => write!(f, "<macro>"),
+            Expr::RandomState(_) => write!(f, "#<random-state>"),
+            Expr::Stream(stream) => write!(f, "{}", Expr::describe_stream(&stream.borrow())),
+            Expr::Array(array) => write!(f, "{}", array.borrow().render(&|item| format!("{}", item))),
+            Expr::HashTable(table) => write!(f, "{}", Expr::describe_hash_table(&table.borrow())),
+            Expr::Struct(instance) => write!(f, "{}", instance.borrow().render(&|item| format!("{}", item))),
+            Expr::Instance(instance) => write!(f, "#<{}>", instance.borrow().class().name),
         }
     }
 }
@@ -35,11 +79,21 @@ impl PartialEq for Expr {
         match (self, other) {
             (Expr::Symbol(a), Expr::Symbol(b)) => a == b,
             (Expr::Number(a), Expr::Number(b)) => a == b,
+            (Expr::BigInt(a), Expr::BigInt(b)) => a == b,
+            (Expr::Ratio(a), Expr::Ratio(b)) => a == b,
             (Expr::Float(a), Expr::Float(b)) => a == b,
+            (Expr::Complex(a1, a2), Expr::Complex(b1, b2)) => a1 == b1 && a2 == b2,
+            (Expr::Char(a), Expr::Char(b)) => a == b,
             (Expr::Str(a), Expr::Str(b)) => a == b,
             (Expr::List(a), Expr::List(b)) => a == b,
             (Expr::DottedPair(a1, a2), Expr::DottedPair(b1, b2)) => a1 == b1 && a2 == b2,
             (Expr::Macro(_, _), Expr::Macro(_, _)) => false, // 宏不应该被比较
+            (Expr::RandomState(a), Expr::RandomState(b)) => Rc::ptr_eq(a, b),
+            (Expr::Stream(a), Expr::Stream(b)) => Rc::ptr_eq(a, b),
+            (Expr::Array(a), Expr::Array(b)) => Rc::ptr_eq(a, b),
+            (Expr::HashTable(a), Expr::HashTable(b)) => Rc::ptr_eq(a, b),
+            (Expr::Struct(a), Expr::Struct(b)) => Rc::ptr_eq(a, b),
+            (Expr::Instance(a), Expr::Instance(b)) => Rc::ptr_eq(a, b),
             _ => false,
         }
     }
@@ -48,54 +102,167 @@ impl PartialEq for Expr {
 impl Eq for Expr {}
 
 impl Expr {
-    #[allow(dead_code)]
+    // 规范的真值 t
+    pub fn t() -> Expr {
+        Expr::Symbol("t".to_string())
+    }
+
+    // 规范的假值 nil，即空列表 ()
+    pub fn nil() -> Expr {
+        Expr::List(vec![])
+    }
+
+    pub fn from_bool(value: bool) -> Expr {
+        if value {
+            Expr::t()
+        } else {
+            Expr::nil()
+        }
+    }
+
+    // 在 cdr 前面加上 car：cdr 是列表（包括 nil）时得到列表，否则得到点对。
+    // 不正规列表 (a b . c) 因此表示为 (a . (b . c)) 这样嵌套的点对
+    pub fn cons(car: Expr, cdr: Expr) -> Expr {
+        match cdr {
+            Expr::List(mut list) => {
+                list.insert(0, car);
+                Expr::List(list)
+            }
+            cdr if cdr.is_null() => Expr::List(vec![car]),
+            cdr => Expr::DottedPair(Box::new(car), Box::new(cdr)),
+        }
+    }
+
+    // 点对和不正规列表的打印形式 (a b . c)，元素的打印方式由 item 决定
+    pub fn render_dotted(car: &Expr, cdr: &Expr, item: &dyn Fn(&Expr) -> String) -> String {
+        let mut parts = vec![item(car)];
+        let mut rest = cdr;
+        loop {
+            match rest {
+                Expr::DottedPair(car, cdr) => {
+                    parts.push(item(car));
+                    rest = cdr;
+                }
+                Expr::List(items) => {
+                    parts.extend(items.iter().map(item));
+                    break;
+                }
+                tail => {
+                    parts.push(".".to_string());
+                    parts.push(item(tail));
+                    break;
+                }
+            }
+        }
+        format!("({})", parts.join(" "))
+    }
+
+    // 把任意精度整数规范化：能放进 i64 时降级为 Number
+    pub fn from_bigint(value: BigInt) -> Expr {
+        match value.to_i64() {
+            Some(n) => Expr::Number(n),
+            None => Expr::BigInt(value),
+        }
+    }
+
+    // 把有理数规范化：分母为 1 时降级为整数
+    pub fn from_ratio(value: Ratio) -> Expr {
+        if value.is_integer() {
+            Expr::from_bigint(value.numerator().clone())
+        } else {
+            Expr::Ratio(value)
+        }
+    }
+
+    fn describe_stream(stream: &OutputStream) -> &'static str {
+        if stream.is_string_stream() {
+            "#<string-output-stream>"
+        } else {
+            "#<stdout-stream>"
+        }
+    }
+
+    fn describe_hash_table(table: &HashTable) -> String {
+        format!("#<hash-table :test {} :count {}>", table.test().name(), table.len())
+    }
+
+    // 唯一的真假判定规则：只有 nil（空列表，或者被引用的符号 nil）为假，其余一切（包括 0）为真
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Expr::List(list) => !list.is_empty(),
+            Expr::Symbol(s) => s != "nil" && s != "NIL",
+            _ => true,
+        }
+    }
+
+    // 关键字是以冒号开头的符号，例如 :test；它们求值为自身，不能被重新绑定
+    pub fn is_keyword(&self) -> bool {
+        matches!(self, Expr::Symbol(s) if s.len() > 1 && s.starts_with(':'))
+    }
+
+    // 任意数值类型：整数、分数、浮点数和复数
     pub fn is_number(&self) -> bool {
-        matches!(self, Expr::Number(_))
+        matches!(self, Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_) | Expr::Complex(_, _))
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Expr::Number(_) | Expr::BigInt(_))
     }
 
-    #[allow(dead_code)]
     pub fn is_symbol(&self) -> bool {
         matches!(self, Expr::Symbol(_))
     }
 
-    #[allow(dead_code)]
     pub fn is_string(&self) -> bool {
         matches!(self, Expr::Str(_))
     }
 
-    #[allow(dead_code)]
     pub fn is_list(&self) -> bool {
         matches!(self, Expr::List(_))
     }
 
-    #[allow(dead_code)]
     pub fn is_float(&self) -> bool {
         matches!(self, Expr::Float(_))
     }
 
-    #[allow(dead_code)]
     pub fn is_dotted_pair(&self) -> bool {
         matches!(self, Expr::DottedPair(_, _))
     }
 
-    #[allow(dead_code)]
+    // nil：空列表或者符号 nil
+    pub fn is_null(&self) -> bool {
+        !self.is_truthy()
+    }
+
+    #[allow(dead_code, clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         match self {
             Expr::Number(n) => n.to_string(),
-            Expr::Float(n) => n.to_string(),
-            Expr::Symbol(s) => s.clone(),
+            Expr::BigInt(n) => n.to_string(),
+            Expr::Ratio(r) => r.to_string(),
+            Expr::Float(n) => format!("{:?}", n),
+            Expr::Complex(re, im) => format!("#C({} {})", re, im),
+            Expr::Symbol(_) => format!("{}", self),
+            Expr::Char(c) => format!("#\\{}", character::name_of(*c)),
             Expr::Str(s) => format!("\"{}\"", s.replace("\\", "\\\\").replace("\"", "\\\"")),
             Expr::List(list) => {
                 let contents: Vec<String> = list.iter().map(|e| e.to_string()).collect();
                 format!("({})", contents.join(" "))
             }
-            Expr::DottedPair(car, cdr) => format!("({} . {})", car, cdr),
+            Expr::DottedPair(car, cdr) => Expr::render_dotted(car, cdr, &|item| item.to_string()),
             Expr::Macro(_, _) => "<macro>".to_string(),
+            Expr::RandomState(_) => "#<random-state>".to_string(),
+            Expr::Stream(stream) => Expr::describe_stream(&stream.borrow()).to_string(),
+            Expr::Array(array) => array.borrow().render(&|item| item.to_string()),
+            Expr::HashTable(table) => Expr::describe_hash_table(&table.borrow()),
+            Expr::Struct(instance) => instance.borrow().render(&|item| item.to_string()),
+            Expr::Instance(instance) => format!("#<{}>", instance.borrow().class().name),
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -529,4 +696,24 @@ mod tests {
         assert_ne!(expr_number, expr_string);
         assert_ne!(expr_number, expr_list);
     }
+
+    #[test]
+    fn test_is_truthy() {
+        assert!(!Expr::nil().is_truthy());
+        assert!(!Expr::Symbol("nil".to_string()).is_truthy());
+        assert!(!Expr::Symbol("NIL".to_string()).is_truthy());
+        assert!(Expr::t().is_truthy());
+        assert!(Expr::Number(0).is_truthy());
+        assert!(Expr::Float(0.0).is_truthy());
+        assert!(Expr::Str("".to_string()).is_truthy());
+        assert!(Expr::Symbol("foo".to_string()).is_truthy());
+        assert!(Expr::List(vec![Expr::nil()]).is_truthy());
+    }
+
+    #[test]
+    fn test_from_bool() {
+        assert_eq!(Expr::from_bool(true), Expr::Symbol("t".to_string()));
+        assert_eq!(Expr::from_bool(false), Expr::List(vec![]));
+    }
 }
+
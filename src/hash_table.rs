@@ -0,0 +1,310 @@
+// hash_table.rs
+
+use crate::expression::Expr;
+use crate::operator::arithmetic::Arithmetic;
+use crate::operator::comparison::Comparison;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+// 哈希表的键比较方式，从严到宽排列
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HashTest {
+    Eq,
+    Eql,
+    Equal,
+    Equalp,
+}
+
+impl HashTest {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "eq" => Some(HashTest::Eq),
+            "eql" => Some(HashTest::Eql),
+            "equal" => Some(HashTest::Equal),
+            "equalp" => Some(HashTest::Equalp),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HashTest::Eq => "eq",
+            HashTest::Eql => "eql",
+            HashTest::Equal => "equal",
+            HashTest::Equalp => "equalp",
+        }
+    }
+
+    pub fn matches(self, left: &Expr, right: &Expr) -> bool {
+        match self {
+            // eq 对数值的结果由实现决定；数值在求值时会被复制，这里按 eql 比较，使保存的条目总能找回
+            HashTest::Eq if left.is_number() => Comparison::is_eql(left, right),
+            HashTest::Eq => Comparison::is_eq(left, right),
+            HashTest::Eql => Comparison::is_eql(left, right),
+            HashTest::Equal => Comparison::is_equal(left, right),
+            HashTest::Equalp => Comparison::is_equalp(left, right),
+        }
+    }
+}
+
+// 哈希表：条目按插入顺序保存，maphash 和 hash-table-keys 按这个顺序遍历；
+// index 把键的哈希值映射到条目下标。哈希值只由比较方式认为相同的那部分信息决定，
+// 所以按 test 相等的两个键一定落在同一个桶里。
+// 删除的条目留下空位，其余条目的顺序和下标不变；空位超过一半时再整理并重建索引。
+#[derive(Clone, Debug)]
+pub struct HashTable {
+    test: HashTest,
+    entries: Vec<Option<(Expr, Expr)>>,
+    count: usize,
+    index: HashMap<u64, Vec<usize>>,
+}
+
+impl HashTable {
+    pub fn new(test: HashTest) -> Self {
+        HashTable { test, entries: Vec::new(), count: 0, index: HashMap::new() }
+    }
+
+    pub fn test(&self) -> HashTest {
+        self.test
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &(Expr, Expr)> {
+        self.entries.iter().flatten()
+    }
+
+    // 索引只指向仍然存在的条目
+    fn entry(&self, i: usize) -> &(Expr, Expr) {
+        self.entries[i].as_ref().expect("index points at a live entry")
+    }
+
+    fn position(&self, key: &Expr) -> Option<usize> {
+        self.index
+            .get(&self.hash(key))?
+            .iter()
+            .copied()
+            .find(|&i| self.test.matches(&self.entry(i).0, key))
+    }
+
+    pub fn get(&self, key: &Expr) -> Option<&Expr> {
+        self.position(key).map(|i| &self.entry(i).1)
+    }
+
+    pub fn insert(&mut self, key: Expr, value: Expr) {
+        match self.position(&key) {
+            Some(i) => {
+                if let Some(entry) = &mut self.entries[i] {
+                    entry.1 = value;
+                }
+            }
+            None => {
+                let hash = self.hash(&key);
+                self.index.entry(hash).or_default().push(self.entries.len());
+                self.entries.push(Some((key, value)));
+                self.count += 1;
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &Expr) -> bool {
+        let i = match self.position(key) {
+            Some(i) => i,
+            None => return false,
+        };
+        let hash = self.hash(key);
+        if let Some(bucket) = self.index.get_mut(&hash) {
+            bucket.retain(|&j| j != i);
+            if bucket.is_empty() {
+                self.index.remove(&hash);
+            }
+        }
+        self.entries[i] = None;
+        self.count -= 1;
+        if self.entries.len() > 2 * self.count {
+            self.entries.retain(Option::is_some);
+            self.rebuild_index();
+        }
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.count = 0;
+        self.index.clear();
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for i in 0..self.entries.len() {
+            let hash = self.hash(&self.entry(i).0);
+            self.index.entry(hash).or_default().push(i);
+        }
+    }
+
+    fn hash(&self, key: &Expr) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        HashTable::feed(self.test, key, &mut hasher);
+        hasher.finish()
+    }
+
+    fn feed(test: HashTest, key: &Expr, hasher: &mut DefaultHasher) {
+        // 空列表和符号 nil 是同一个对象
+        if matches!(key, Expr::List(_) | Expr::Symbol(_)) && !key.is_truthy() {
+            0u8.hash(hasher);
+            return;
+        }
+        // equalp 按 = 比较数值，不同类型的相等数值要有同样的哈希值，因此只用数值本身
+        if test == HashTest::Equalp {
+            if let Some((re, im)) = Arithmetic::complex_parts(key) {
+                1u8.hash(hasher);
+                for part in [re, im] {
+                    // 0.0 和 -0.0 按 = 相等
+                    let value = Arithmetic::to_f64(&part).unwrap_or(0.0) + 0.0;
+                    value.to_bits().hash(hasher);
+                }
+                return;
+            }
+        }
+        std::mem::discriminant(key).hash(hasher);
+        match key {
+            Expr::Symbol(s) => s.hash(hasher),
+            Expr::Char(c) if test == HashTest::Equalp => c.to_lowercase().for_each(|c| c.hash(hasher)),
+            Expr::Char(c) => c.hash(hasher),
+            Expr::Number(n) => n.hash(hasher),
+            Expr::BigInt(n) => n.to_string().hash(hasher),
+            Expr::Ratio(r) => r.to_string().hash(hasher),
+            Expr::Float(x) => x.to_bits().hash(hasher),
+            Expr::Complex(re, im) => {
+                HashTable::feed(test, re, hasher);
+                HashTable::feed(test, im, hasher);
+            }
+            // eq 和 eql 下字符串、列表和点对互不相同，只有 equal 和 equalp 才会按内容找到它们；这里统一按内容计算哈希值
+            Expr::Str(s) if test == HashTest::Equalp => s.to_lowercase().hash(hasher),
+            Expr::Str(s) => s.hash(hasher),
+            Expr::List(items) => {
+                items.len().hash(hasher);
+                for item in items {
+                    HashTable::feed(test.max(HashTest::Equal), item, hasher);
+                }
+            }
+            Expr::DottedPair(car, cdr) => {
+                HashTable::feed(test.max(HashTest::Equal), car, hasher);
+                HashTable::feed(test.max(HashTest::Equal), cdr, hasher);
+            }
+            // 数组正在被修改时读不到内容，只能按同一性处理
+            Expr::Array(array) if test == HashTest::Equalp => match array.try_borrow() {
+                Ok(array) => {
+                    array.dimensions().hash(hasher);
+                    for item in array.active_elements() {
+                        HashTable::feed(test, item, hasher);
+                    }
+                }
+                Err(_) => (Rc::as_ptr(array) as *const () as usize).hash(hasher),
+            },
+            // 可变对象按同一性比较
+            Expr::Array(array) => (Rc::as_ptr(array) as *const () as usize).hash(hasher),
+            // equalp 下的哈希表只按类型区分：哈希表可以是自己的键，插入时它正被修改，读不到条目数
+            Expr::HashTable(_) if test == HashTest::Equalp => {}
+            Expr::HashTable(table) => (Rc::as_ptr(table) as *const () as usize).hash(hasher),
+            Expr::Struct(instance) if test == HashTest::Equalp => instance.borrow().structure().name.hash(hasher),
+            Expr::Struct(instance) => (Rc::as_ptr(instance) as *const () as usize).hash(hasher),
+            Expr::Instance(instance) => (Rc::as_ptr(instance) as *const () as usize).hash(hasher),
+            Expr::RandomState(state) => (Rc::as_ptr(state) as *const () as usize).hash(hasher),
+            Expr::Stream(stream) => (Rc::as_ptr(stream) as *const () as usize).hash(hasher),
+            // 宏等其余的键只按类型区分
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Expr {
+        Expr::Str(s.to_string())
+    }
+
+    #[test]
+    fn test_hashing_follows_the_test() {
+        let mut equal = HashTable::new(HashTest::Equal);
+        equal.insert(string("a"), Expr::Number(1));
+        equal.insert(string("a"), Expr::Number(2));
+        equal.insert(Expr::List(vec![Expr::Number(1), string("x")]), Expr::Number(3));
+        assert_eq!(equal.len(), 2);
+        assert_eq!(equal.get(&string("a")), Some(&Expr::Number(2)));
+        assert_eq!(equal.get(&Expr::List(vec![Expr::Number(1), string("x")])), Some(&Expr::Number(3)));
+        assert_eq!(equal.get(&string("A")), None);
+
+        let mut eql = HashTable::new(HashTest::Eql);
+        eql.insert(string("a"), Expr::Number(1));
+        assert_eq!(eql.get(&string("a")), None);
+        eql.insert(Expr::Float(1.0), Expr::Number(1));
+        assert_eq!(eql.get(&Expr::Float(1.0)), Some(&Expr::Number(1)));
+        assert_eq!(eql.get(&Expr::Number(1)), None);
+
+        let mut equalp = HashTable::new(HashTest::Equalp);
+        equalp.insert(Expr::Number(1), string("one"));
+        equalp.insert(string("Key"), string("key"));
+        assert_eq!(equalp.get(&Expr::Float(1.0)), Some(&string("one")));
+        assert_eq!(equalp.get(&string("KEY")), Some(&string("key")));
+        assert_eq!(equalp.get(&Expr::Char('k')), None);
+    }
+
+    #[test]
+    fn test_remove_keeps_order_and_index() {
+        let mut table = HashTable::new(HashTest::Eql);
+        for n in 0..5 {
+            table.insert(Expr::Number(n), Expr::Number(n * 10));
+        }
+        assert!(table.remove(&Expr::Number(1)));
+        assert!(!table.remove(&Expr::Number(1)));
+        let keys: Vec<&Expr> = table.entries().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![&Expr::Number(0), &Expr::Number(2), &Expr::Number(3), &Expr::Number(4)]);
+        assert_eq!(table.get(&Expr::Number(4)), Some(&Expr::Number(40)));
+        table.clear();
+        assert_eq!(table.len(), 0);
+        assert_eq!(table.get(&Expr::Number(0)), None);
+    }
+
+    #[test]
+    fn test_remove_compacts_tombstones() {
+        let mut table = HashTable::new(HashTest::Eql);
+        for n in 0..100 {
+            table.insert(Expr::Number(n), Expr::Number(n));
+        }
+        for n in (0..100).filter(|n| n % 10 != 0) {
+            assert!(table.remove(&Expr::Number(n)));
+        }
+        assert_eq!(table.len(), 10);
+        assert!(table.entries.len() <= 2 * table.len());
+        let keys: Vec<Expr> = table.entries().map(|(k, _)| k.clone()).collect();
+        assert_eq!(keys, (0..10).map(|n| Expr::Number(n * 10)).collect::<Vec<_>>());
+        for n in 0..100 {
+            let expected = if n % 10 == 0 { Some(&Expr::Number(n)) } else { None };
+            assert_eq!(table.get(&Expr::Number(n)), expected);
+        }
+    }
+
+    #[test]
+    fn test_eq_table_keeps_copied_keys_apart() {
+        // 字符串和列表在求值时会被复制，eq 表里内容相同的两个键也是不同的键
+        let mut table = HashTable::new(HashTest::Eq);
+        let key = Expr::List(vec![Expr::Number(1), Expr::Number(2)]);
+        table.insert(key.clone(), Expr::Number(1));
+        table.insert(string("k"), Expr::Number(2));
+        table.insert(string("k"), Expr::Number(3));
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get(&key), None);
+        assert_eq!(table.get(&string("k")), None);
+        table.insert(Expr::Symbol("k".to_string()), Expr::Number(4));
+        table.insert(Expr::Symbol("k".to_string()), Expr::Number(5));
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.get(&Expr::Symbol("k".to_string())), Some(&Expr::Number(5)));
+    }
+}
@@ -6,10 +6,24 @@ mod exception;
 mod expression;
 mod operator;
 mod macro_expander;
+mod source;
+mod bignum;
+mod ratio;
+mod character;
+mod values;
+mod random;
+mod stream;
+mod array;
+mod hash_table;
+mod structure;
+mod clos;
+mod package;
+mod printer;
 
 use crate::environment::Environment;
 use crate::parser::Parser;
 use crate::evaluator::Evaluator;
+use crate::printer::{PrintOptions, Printer};
 use std::io::{self, Write};
 
 // 定义一个IO trait用于后续测试时模拟输入和输出操作
@@ -45,28 +59,52 @@ impl Lisp {
         Environment::initialize()
     }
 
+    // 累积的输入是否已经构成完整的形式：括号配平且字符串已经结束。
+    // 字符串、行注释和 #\( 这样的字符字面量中的括号不计入
+    fn form_complete(input: &str) -> bool {
+        let mut depth = 0isize;
+        let mut chars = input.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                '"' if !chars.any(|ch| ch == '"') => return false,
+                ';' => {
+                    chars.find(|&ch| ch == '\n');
+                }
+                '#' if chars.clone().next() == Some('\\') => {
+                    chars.nth(1);
+                }
+                _ => {}
+            }
+        }
+        depth <= 0
+    }
+
     fn repl<T: IO>(env: &mut Environment, io: &mut T) {
         let mut input_accumulated = String::new();
-        let mut open_parens = 0;
+        let mut line_number = 0;
+        let mut form_start_line = 1;
 
         loop {
-            let prompt = if open_parens > 0 { "> " } else { "lisp:> " };
+            let prompt = if input_accumulated.is_empty() { "lisp:> " } else { "> " };
             match io.readline(prompt) {
                 Ok(input) => {
                     if input.trim() == "exit" {
                         break;
                     }
+                    line_number += 1;
+                    if input_accumulated.is_empty() {
+                        form_start_line = line_number;
+                    }
                     input_accumulated.push_str(&input);
-                    
-                    open_parens += input.chars().filter(|&ch| ch == '(').count();
-                    open_parens = open_parens.saturating_sub(input.chars().filter(|&ch| ch == ')').count());
 
-                    if open_parens == 0 {
-                        match Parser::read(&input_accumulated, env) {
+                    if Lisp::form_complete(&input_accumulated) {
+                        match Parser::read_from(&input_accumulated, "<repl>", form_start_line, env) {
                             Ok(ast) => {
                                 let result = Evaluator::eval(&ast, env);
                                 match result {
-                                    Ok(value) => io.write(format!("{}\n", value)).unwrap(),
+                                    Ok(value) => io.write(format!("{}\n", Printer::print(&value, &PrintOptions::from_env(env)))).unwrap(),
                                     Err(err) => io.write(format!("Error: {}\n", err)).unwrap(),
                                 }
                             }
@@ -86,19 +124,21 @@ impl Lisp {
         match std::fs::read_to_string(file) {
             Ok(contents) => {
                 let mut input_accumulated = String::new();
-                let mut open_parens = 0;
+                let mut form_start_line = 1;
     
-                for line in contents.lines() {
+                for (index, line) in contents.lines().enumerate() {
+                    if input_accumulated.is_empty() {
+                        form_start_line = index + 1;
+                    }
                     input_accumulated.push_str(line);
-                    open_parens += line.chars().filter(|&ch| ch == '(').count();
-                    open_parens -= line.chars().filter(|&ch| ch == ')').count();
+                    input_accumulated.push('\n'); // 保留换行，使行号和行注释保持正确
     
-                    if open_parens == 0 {
-                        match Parser::read(&input_accumulated, env) {
+                    if Lisp::form_complete(&input_accumulated) {
+                        match Parser::read_from(&input_accumulated, file, form_start_line, env) {
                             Ok(ast) => {
                                 let result = Evaluator::eval(&ast, env);
                                 match result {
-                                    Ok(value) => io.write(format!("{}\n", value)).unwrap(),
+                                    Ok(value) => io.write(format!("{}\n", Printer::print(&value, &PrintOptions::from_env(env)))).unwrap(),
                                     Err(err) => io.write(format!("Error: {}\n", err)).unwrap(),
                                 }
                             }
@@ -124,7 +164,7 @@ fn main() {
 mod tests {
     use super::*;
     use crate::exception::LispError;
-    use std::io::{self, Write, Cursor, BufReader, BufRead};
+    use std::io::{self, Write, Cursor, BufRead};
 
     struct MockIO<'a> {
         input: Cursor<&'a [u8]>,
@@ -148,7 +188,7 @@ mod tests {
         fn readline(&mut self, prompt: &str) -> io::Result<String> {
             self.write(prompt.to_string())?;
             let mut line = String::new();
-            let bytes_read = BufReader::new(&mut self.input).read_line(&mut line)?;
+            let bytes_read = self.input.read_line(&mut line)?;
     
             if bytes_read == 0 {
                 return Ok("exit".to_string()); // 在EOF时返回"exit"以退出REPL
@@ -463,4 +503,97 @@ mod tests {
         assert!(output.contains("Parse Error")); // 确保捕获解析错误
     }
 
+    #[test]
+    fn test_repl_error_reports_line() {
+        let input = b"(+ 1 2)\n(+ 1\n   (car 5))\nexit\n";
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(input);
+
+        Lisp::repl(&mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("Error: car: argument must be a list at <repl>:3:4"));
+    }
+
+    #[test]
+    fn test_repl_prints_in_print_base() {
+        let input = b"(setf *print-base* 16)\n#xff\n(setf *print-radix* t)\n255\nexit\n";
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(input);
+
+        Lisp::repl(&mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("FF\n"));
+        assert!(output.contains("#xFF\n")); // 带进制前缀的输出可以原样读回
+    }
+
+    #[test]
+    fn test_repl_error_position_belongs_to_the_called_function() {
+        // 两个函数体相同，错误要指向被调用的那一个
+        let input = b"(defun f1 (x) (car x))\n(defun f2 (x) (car x))\n(f1 5)\n(f2 5)\nexit\n";
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(input);
+
+        Lisp::repl(&mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("car: argument must be a list at <repl>:1:15\n"));
+        assert!(output.contains("car: argument must be a list at <repl>:2:15\n"));
+    }
+
+    #[test]
+    fn test_repl_skips_parens_in_characters_strings_and_comments() {
+        let input = b"(char-code #\\()\n(length \"(((\")\n(cons #\\) ; )\n  \"a\n)b\")\nexit\n";
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(input);
+
+        Lisp::repl(&mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("lisp:> 40\n"));
+        assert!(output.contains("lisp:> 3\n"));
+        // 跨行的字符串结束之前一直提示继续输入
+        assert!(output.contains("lisp:> > > (#\\) . \"a\n)b\")\n"));
+    }
+
+    #[test]
+    fn test_repl_reports_dotted_list_errors_once() {
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(b"(a . b c)\nexit\n");
+
+        Lisp::repl(&mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("lisp:> Parse Error: expected ) after the form following . at <repl>:1:"));
+        assert_eq!(output.matches("Parse Error").count(), 1);
+    }
+
+    #[test]
+    fn test_interpreter_skips_parens_in_characters_and_strings() {
+        let file_content = "(char-code #\\))\n(length \")\")\n(+ 1\n   (char-code #\\())";
+        let file_path = "test_file_with_paren_characters.lisp";
+        std::fs::write(file_path, file_content).unwrap();
+
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(b"");
+
+        Lisp::interpreter(file_path, &mut env, &mut io);
+        let output = io.get_output();
+        assert_eq!(output, "41\n1\n41\n");
+
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_interpreter_error_reports_file_position() {
+        let file_content = "(+ 1 2 3)\n; comment\n(+ 4\n   undefined-var)";
+        let file_path = "test_file_with_error_position.lisp";
+        std::fs::write(file_path, file_content).unwrap();
+
+        let mut env = Lisp::initialize();
+        let mut io = MockIO::new(b"");
+
+        Lisp::interpreter(file_path, &mut env, &mut io);
+        let output = io.get_output();
+        assert!(output.contains("Undefined symbol: undefined-var at test_file_with_error_position.lisp:3:1"));
+
+        std::fs::remove_file(file_path).unwrap();
+    }
 }
+
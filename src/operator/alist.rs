@@ -0,0 +1,268 @@
+// operator/alist.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::comparison::Comparison;
+use crate::operator::lambda::{eval_keyword_arguments, Lambda};
+use crate::operator::set::SetOps;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+
+// 关联表和属性表。关联表的条目是 cons：(a . 1) 是点对，(a 1) 的 cdr 是 (1)；
+// 属性表是交替出现指示符和值的正规列表，指示符用 eq 比较
+pub struct AlistOps;
+
+impl AlistOps {
+    fn eval_args(args: &[Expr], env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        args.iter().map(|arg| Evaluator::eval(arg, env)).collect()
+    }
+
+    fn list_argument(name: &str, value: &Expr) -> Result<Vec<Expr>, LispError> {
+        match value {
+            Expr::List(items) => Ok(items.clone()),
+            other if other.is_null() => Ok(Vec::new()),
+            other => Err(LispError::new(&format!("{}: {} is not a list", name, other))),
+        }
+    }
+
+    // 条目的 car 和 cdr；关联表中的 nil 条目被跳过
+    fn entry_parts(name: &str, entry: &Expr) -> Result<Option<(Expr, Expr)>, LispError> {
+        match entry {
+            Expr::DottedPair(car, cdr) => Ok(Some(((**car).clone(), (**cdr).clone()))),
+            Expr::List(items) if !items.is_empty() => Ok(Some((items[0].clone(), Expr::List(items[1..].to_vec())))),
+            other if other.is_null() => Ok(None),
+            other => Err(LispError::new(&format!("{}: {} is not a cons", name, other))),
+        }
+    }
+
+    // assoc 和 rassoc 共用：按 :test（默认 eql）比较 item 和条目的 car 或 cdr（先经过 :key 函数）
+    fn find_entry(name: &str, args: &[Expr], env: &mut Environment, use_cdr: bool) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new(&format!("{} requires an item and an association list", name)));
+        }
+        let item = Evaluator::eval(&args[0], env)?;
+        let alist = AlistOps::list_argument(name, &Evaluator::eval(&args[1], env)?)?;
+        let mut test = None;
+        let mut key = None;
+        for (keyword, value) in eval_keyword_arguments(name, &args[2..], env, &["test", "key"])? {
+            match keyword.as_str() {
+                "test" => test = Some(value),
+                _ => key = Some(value),
+            }
+        }
+        for entry in alist {
+            let (car, cdr) = match AlistOps::entry_parts(name, &entry)? {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let mut candidate = if use_cdr { cdr } else { car };
+            if let Some(key) = &key {
+                candidate = Lambda::funcall(key, vec![candidate], env)?;
+            }
+            let matched = match &test {
+                Some(test) => Lambda::funcall(test, vec![item.clone(), candidate], env)?.is_truthy(),
+                None => Comparison::is_eql(&item, &candidate),
+            };
+            if matched {
+                return Ok(entry);
+            }
+        }
+        Ok(Expr::nil())
+    }
+
+    // (assoc item alist &key :test :key)：返回第一个 car 与 item 匹配的条目
+    pub fn eval_assoc(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        AlistOps::find_entry("assoc", args, env, false)
+    }
+
+    // (rassoc item alist &key :test :key)：返回第一个 cdr 与 item 匹配的条目
+    pub fn eval_rassoc(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        AlistOps::find_entry("rassoc", args, env, true)
+    }
+
+    // (acons key datum alist)：把 (key . datum) 加在关联表前面
+    pub fn eval_acons(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("acons", args, 3, 3)?;
+        let values = AlistOps::eval_args(args, env)?;
+        AlistOps::list_argument("acons", &values[2])?;
+        Ok(Expr::cons(Expr::cons(values[0].clone(), values[1].clone()), values[2].clone()))
+    }
+
+    // (pairlis keys data [alist])：按顺序把键和值配成条目，加在 alist 前面
+    pub fn eval_pairlis(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("pairlis", args, 2, 3)?;
+        let values = AlistOps::eval_args(args, env)?;
+        let keys = AlistOps::list_argument("pairlis", &values[0])?;
+        let data = AlistOps::list_argument("pairlis", &values[1])?;
+        if keys.len() != data.len() {
+            return Err(LispError::new("pairlis: keys and data have different lengths"));
+        }
+        let mut result = AlistOps::list_argument("pairlis", values.get(2).unwrap_or(&Expr::nil()))?;
+        let entries = keys.into_iter().zip(data).map(|(key, datum)| Expr::cons(key, datum));
+        result.splice(0..0, entries);
+        Ok(Expr::List(result))
+    }
+
+    fn plist_argument(name: &str, value: &Expr) -> Result<Vec<Expr>, LispError> {
+        match AlistOps::list_argument(name, value) {
+            Ok(plist) if plist.len() % 2 == 0 => Ok(plist),
+            _ => Err(LispError::new(&format!("{}: {} is not a property list", name, value))),
+        }
+    }
+
+    fn find_property(plist: &[Expr], indicator: &Expr) -> Option<usize> {
+        plist.chunks(2).position(|pair| Comparison::is_eq(&pair[0], indicator)).map(|i| i * 2)
+    }
+
+    // 把新的属性表写回已经解析过的位置
+    fn store_plist(place: &Expr, plist: Vec<Expr>, env: &mut Environment) -> Result<(), LispError> {
+        let quoted = Expr::List(vec![Expr::Symbol("quote".to_string()), Expr::List(plist)]);
+        SetOps::eval_setf(&[place.clone(), quoted], env)?;
+        Ok(())
+    }
+
+    // (getf plist indicator [default])
+    pub fn eval_getf(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("getf", args, 2, 3)?;
+        let values = AlistOps::eval_args(args, env)?;
+        let plist = AlistOps::plist_argument("getf", &values[0])?;
+        match AlistOps::find_property(&plist, &values[1]) {
+            Some(index) => Ok(plist[index + 1].clone()),
+            None => Ok(values.get(2).cloned().unwrap_or_else(Expr::nil)),
+        }
+    }
+
+    // (setf (getf place indicator [default]) value)：已有的属性原地修改，新属性加在表头，再写回 place
+    pub fn setf_getf(place_args: &[Expr], value: &Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("setf getf", place_args, 2, 3)?;
+        let place = SetOps::resolve_place(&place_args[0], env)?;
+        let mut plist = AlistOps::plist_argument("getf", &Evaluator::eval(&place, env)?)?;
+        let indicator = Evaluator::eval(&place_args[1], env)?;
+        if let Some(default) = place_args.get(2) {
+            Evaluator::eval(default, env)?;
+        }
+        let value = Evaluator::eval(value, env)?;
+        match AlistOps::find_property(&plist, &indicator) {
+            Some(index) => plist[index + 1] = value.clone(),
+            None => {
+                plist.insert(0, value.clone());
+                plist.insert(0, indicator);
+            }
+        }
+        AlistOps::store_plist(&place, plist, env)?;
+        Ok(value)
+    }
+
+    // (remf place indicator)：删除属性并写回 place，删除了返回 t，否则返回 nil
+    pub fn eval_remf(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("remf", args, 2, 2)?;
+        let place = SetOps::resolve_place(&args[0], env)?;
+        let mut plist = AlistOps::plist_argument("remf", &Evaluator::eval(&place, env)?)?;
+        let indicator = Evaluator::eval(&args[1], env)?;
+        match AlistOps::find_property(&plist, &indicator) {
+            Some(index) => {
+                plist.drain(index..index + 2);
+                AlistOps::store_plist(&place, plist, env)?;
+                Ok(Expr::t())
+            }
+            None => Ok(Expr::nil()),
+        }
+    }
+}
+
+pub fn register_alist_operators() {
+    OperatorRegistry::register("assoc", AlistOps::eval_assoc);
+    OperatorRegistry::register("rassoc", AlistOps::eval_rassoc);
+    OperatorRegistry::register("acons", AlistOps::eval_acons);
+    OperatorRegistry::register("pairlis", AlistOps::eval_pairlis);
+    OperatorRegistry::register("getf", AlistOps::eval_getf);
+    OperatorRegistry::register_setf_form("getf", AlistOps::setf_getf);
+    OperatorRegistry::register("remf", AlistOps::eval_remf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    #[test]
+    fn test_assoc_and_rassoc() {
+        let mut env = Environment::initialize();
+        eval_str("(setf colors '((red . 1) (green . 2) nil (blue 3)))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(assoc 'green colors)", &mut env), "(green . 2)");
+        assert_eq!(eval_to_string("(assoc 'blue colors)", &mut env), "(blue 3)");
+        assert_eq!(eval_to_string("(cdr (assoc 'red colors))", &mut env), "1");
+        assert_eq!(eval_to_string("(assoc 'white colors)", &mut env), "()");
+        assert_eq!(eval_to_string("(rassoc 2 colors)", &mut env), "(green . 2)");
+
+        eval_str("(setf names '((\"ann\" . 30) (\"bob\" . 25)))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(assoc \"bob\" names)", &mut env), "()");
+        assert_eq!(eval_to_string("(assoc \"bob\" names :test 'equal)", &mut env), "(\"bob\" . 25)");
+        assert_eq!(eval_to_string("(rassoc 12 names :key (lambda (n) (/ n 2)))", &mut env), "()");
+        assert_eq!(eval_to_string("(rassoc 15 names :key (lambda (n) (/ n 2)))", &mut env), "(\"ann\" . 30)");
+        assert_eq!(eval_str("(assoc 'a '(1 2))", &mut env), Err(LispError::new("assoc: 1 is not a cons")));
+        assert_eq!(eval_str("(assoc 'a nil :count 1)", &mut env), Err(LispError::new("assoc: unknown keyword argument :count")));
+    }
+
+    #[test]
+    fn test_acons_and_pairlis() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_to_string("(acons 'a 1 nil)", &mut env), "((a . 1))");
+        assert_eq!(eval_to_string("(acons 'b '(2 3) (acons 'a 1 nil))", &mut env), "((b 2 3) (a . 1))");
+        assert_eq!(eval_to_string("(pairlis '(a b) '(1 2))", &mut env), "((a . 1) (b . 2))");
+        assert_eq!(eval_to_string("(pairlis '(c) '(3) '((a . 1)))", &mut env), "((c . 3) (a . 1))");
+        assert_eq!(eval_to_string("(assoc 'b (pairlis '(a b) '(1 2)))", &mut env), "(b . 2)");
+        assert_eq!(
+            eval_str("(pairlis '(a b) '(1))", &mut env),
+            Err(LispError::new("pairlis: keys and data have different lengths"))
+        );
+    }
+
+    #[test]
+    fn test_getf_setf_getf_and_remf() {
+        let mut env = Environment::initialize();
+        eval_str("(setf props '(:name \"ann\" :age 30))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(getf props :age)", &mut env), "30");
+        assert_eq!(eval_to_string("(getf props :email)", &mut env), "()");
+        assert_eq!(eval_to_string("(getf props :email \"none\")", &mut env), "\"none\"");
+
+        assert_eq!(eval_to_string("(setf (getf props :age) 31)", &mut env), "31");
+        assert_eq!(eval_to_string("props", &mut env), "(:name \"ann\" :age 31)");
+        eval_str("(setf (getf props :email) \"a@b\")", &mut env).unwrap();
+        assert_eq!(eval_to_string("props", &mut env), "(:email \"a@b\" :name \"ann\" :age 31)");
+
+        assert_eq!(eval_to_string("(remf props :name)", &mut env), "t");
+        assert_eq!(eval_to_string("(remf props :name)", &mut env), "()");
+        assert_eq!(eval_to_string("props", &mut env), "(:email \"a@b\" :age 31)");
+
+        // 位置本身也可以是别的位置
+        eval_str("(setf (get 'ann 'info) nil)", &mut env).unwrap();
+        eval_str("(setf (getf (get 'ann 'info) :role) 'admin)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(get 'ann 'info)", &mut env), "(:role admin)");
+        assert_eq!(eval_str("(getf '(:a) :a)", &mut env), Err(LispError::new("getf: (:a) is not a property list")));
+    }
+
+    #[test]
+    fn test_getf_place_is_evaluated_once() {
+        let mut env = Environment::initialize();
+        // 计数器放在数组里，函数体中的修改对调用者可见
+        eval_str("(setf i (vector -1))", &mut env).unwrap();
+        eval_str("(setf v (vector nil '(:k 0)))", &mut env).unwrap();
+        eval_str("(defun next-index () (setf (aref i 0) (+ (aref i 0) 1)))", &mut env).unwrap();
+
+        assert_eq!(eval_str("(setf (getf (aref v (next-index)) :k) 1)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(aref i 0)", &mut env), Ok(Expr::Number(0)));
+        assert_eq!(eval_to_string("v", &mut env), "#((:k 1) (:k 0))");
+
+        assert_eq!(eval_str("(remf (aref v (next-index)) :k)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(aref i 0)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_to_string("v", &mut env), "#((:k 1) ())");
+
+        // 嵌套的 getf 位置也只求值一次
+        eval_str("(setf (aref i 0) -1)", &mut env).unwrap();
+        eval_str("(setf (getf (getf (aref v (next-index)) :inner) :x) 2)", &mut env).unwrap();
+        assert_eq!(eval_str("(aref i 0)", &mut env), Ok(Expr::Number(0)));
+        assert_eq!(eval_to_string("(aref v 0)", &mut env), "(:inner (:x 2) :k 1)");
+    }
+}
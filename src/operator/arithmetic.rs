@@ -5,30 +5,20 @@ use crate::environment::Environment;
 use crate::exception::LispError;
 use crate::expression::Expr;
 use crate::evaluator::Evaluator;
+use crate::bignum::BigInt;
+use crate::ratio::Ratio;
+use std::cmp::Ordering;
 
 pub struct Arithmetic;
 
 impl Arithmetic {
     pub fn eval_add(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        let mut sum = 0.0;
-        let mut has_float = false;
-
+        let mut sum = Expr::Number(0);
         for arg in args {
-            match Evaluator::eval(arg, env)? {
-                Expr::Number(n) => sum += n as f64,
-                Expr::Float(f) => {
-                    sum += f;
-                    has_float = true;
-                },
-                _ => return Err(LispError::new("Invalid number")),
-            }
-        }
-
-        if has_float || sum.fract() != 0.0 {
-            Ok(Expr::Float(sum))
-        } else {
-            Ok(Expr::Number(sum as i64))
+            let value = Evaluator::eval(arg, env)?;
+            sum = Arithmetic::add(&sum, &value)?;
         }
+        Ok(sum)
     }
 
     pub fn eval_subtract(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
@@ -36,51 +26,26 @@ impl Arithmetic {
         let first = iter
             .next()
             .ok_or_else(|| LispError::new("Subtraction requires at least one argument"))?;
-        let mut result = match Evaluator::eval(first, env)? {
-            Expr::Number(n) => n as f64,
-            Expr::Float(f) => f,
-            _ => return Err(LispError::new("Invalid number")),
-        };
-        let mut has_float = matches!(first, Expr::Float(_));
+        let mut result = Arithmetic::check_number(Evaluator::eval(first, env)?)?;
+        // 只有一个实参时返回它的相反数
+        if args.len() == 1 {
+            return Arithmetic::subtract(&Expr::Number(0), &result);
+        }
 
         for arg in iter {
-            match Evaluator::eval(arg, env)? {
-                Expr::Number(n) => result -= n as f64,
-                Expr::Float(f) => {
-                    result -= f;
-                    has_float = true;
-                },
-                _ => return Err(LispError::new("Invalid number")),
-            }
-        }
-
-        if has_float || result.fract() != 0.0 {
-            Ok(Expr::Float(result))
-        } else {
-            Ok(Expr::Number(result as i64))
+            let value = Evaluator::eval(arg, env)?;
+            result = Arithmetic::subtract(&result, &value)?;
         }
+        Ok(result)
     }
 
     pub fn eval_multiply(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        let mut product = 1.0;
-        let mut has_float = false;
-
+        let mut product = Expr::Number(1);
         for arg in args {
-            match Evaluator::eval(arg, env)? {
-                Expr::Number(n) => product *= n as f64,
-                Expr::Float(f) => {
-                    product *= f;
-                    has_float = true;
-                },
-                _ => return Err(LispError::new("Invalid number")),
-            }
-        }
-
-        if has_float || product.fract() != 0.0 {
-            Ok(Expr::Float(product))
-        } else {
-            Ok(Expr::Number(product as i64))
+            let value = Evaluator::eval(arg, env)?;
+            product = Arithmetic::multiply(&product, &value)?;
         }
+        Ok(product)
     }
 
     pub fn eval_divide(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
@@ -88,36 +53,186 @@ impl Arithmetic {
         let first = iter
             .next()
             .ok_or_else(|| LispError::new("Division requires at least one argument"))?;
-        let mut result = match Evaluator::eval(first, env)? {
-            Expr::Number(n) => n as f64,
-            Expr::Float(f) => f,
-            _ => return Err(LispError::new("Invalid number")),
-        };
-        let mut has_float = matches!(first, Expr::Float(_));
+        let mut result = Arithmetic::check_number(Evaluator::eval(first, env)?)?;
+        // 只有一个实参时返回它的倒数
+        if args.len() == 1 {
+            return Arithmetic::divide(&Expr::Number(1), &result);
+        }
 
         for arg in iter {
-            match Evaluator::eval(arg, env)? {
-                Expr::Number(n) => {
-                    if n == 0 {
-                        return Err(LispError::new("Division by zero"));
-                    }
-                    result /= n as f64;
-                }
-                Expr::Float(f) => {
-                    if f == 0.0 {
-                        return Err(LispError::new("Division by zero"));
-                    }
-                    result /= f;
-                    has_float = true;
-                }
-                _ => return Err(LispError::new("Invalid number")),
-            }
-        }
-
-        if has_float || result.fract() != 0.0 {
-            Ok(Expr::Float(result))
+            let value = Evaluator::eval(arg, env)?;
+            result = Arithmetic::divide(&result, &value)?;
+        }
+        Ok(result)
+    }
+
+    // (numerator q) / (denominator q)：整数的分母为 1
+    pub fn eval_numerator(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let ratio = Arithmetic::rational_argument("numerator", args, env)?;
+        Ok(Expr::from_bigint(ratio.numerator().clone()))
+    }
+
+    pub fn eval_denominator(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let ratio = Arithmetic::rational_argument("denominator", args, env)?;
+        Ok(Expr::from_bigint(ratio.denominator().clone()))
+    }
+
+    fn rational_argument(name: &str, args: &[Expr], env: &mut Environment) -> Result<Ratio, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new(&format!("{} expects exactly one argument", name)));
+        }
+        let value = Evaluator::eval(&args[0], env)?;
+        Arithmetic::to_ratio(&value)
+            .ok_or_else(|| LispError::new(&format!("{}: argument must be a rational number, got {}", name, value)))
+    }
+
+    fn check_number(value: Expr) -> Result<Expr, LispError> {
+        match value {
+            Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_) | Expr::Complex(_, _) => Ok(value),
+            _ => Err(LispError::new("Invalid number")),
+        }
+    }
+
+    // 整数（含任意精度整数）转换为 BigInt
+    pub fn to_bigint(value: &Expr) -> Option<BigInt> {
+        match value {
+            Expr::Number(n) => Some(BigInt::from_i64(*n)),
+            Expr::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    // 有理数（整数或分数）转换为 Ratio
+    pub fn to_ratio(value: &Expr) -> Option<Ratio> {
+        match value {
+            Expr::Ratio(r) => Some(r.clone()),
+            _ => Arithmetic::to_bigint(value).map(Ratio::from_integer),
+        }
+    }
+
+    pub fn to_f64(value: &Expr) -> Option<f64> {
+        match value {
+            Expr::Number(n) => Some(*n as f64),
+            Expr::BigInt(n) => Some(n.to_f64()),
+            Expr::Ratio(r) => Some(r.to_f64()),
+            Expr::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    // 二元运算的数值塔分派：i64 溢出时提升为 BigInt；
+    // 有浮点数参与时结果为浮点数，否则有分数参与时结果为精确的分数
+    fn combine(
+        left: &Expr,
+        right: &Expr,
+        fixnum_op: fn(i64, i64) -> Option<i64>,
+        bignum_op: fn(&BigInt, &BigInt) -> BigInt,
+        ratio_op: fn(&Ratio, &Ratio) -> Ratio,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Result<Expr, LispError> {
+        match (left, right) {
+            (Expr::Number(l), Expr::Number(r)) => match fixnum_op(*l, *r) {
+                Some(n) => Ok(Expr::Number(n)),
+                None => Ok(Expr::from_bigint(bignum_op(&BigInt::from_i64(*l), &BigInt::from_i64(*r)))),
+            },
+            (Expr::Float(_), _) | (_, Expr::Float(_)) => match (Arithmetic::to_f64(left), Arithmetic::to_f64(right)) {
+                (Some(l), Some(r)) => Ok(Expr::Float(float_op(l, r))),
+                _ => Err(LispError::new("Invalid number")),
+            },
+            (Expr::Ratio(_), _) | (_, Expr::Ratio(_)) => match (Arithmetic::to_ratio(left), Arithmetic::to_ratio(right)) {
+                (Some(l), Some(r)) => Ok(Expr::from_ratio(ratio_op(&l, &r))),
+                _ => Err(LispError::new("Invalid number")),
+            },
+            _ => match (Arithmetic::to_bigint(left), Arithmetic::to_bigint(right)) {
+                (Some(l), Some(r)) => Ok(Expr::from_bigint(bignum_op(&l, &r))),
+                _ => Err(LispError::new("Invalid number")),
+            },
+        }
+    }
+
+    // 构造规范化的复数：有一部分是浮点数时两部分都转换为浮点数；
+    // 两部分都是有理数且虚部为 0 时降级为实数
+    pub fn make_complex(re: Expr, im: Expr) -> Result<Expr, LispError> {
+        let (Some(re_float), Some(im_float)) = (Arithmetic::to_f64(&re), Arithmetic::to_f64(&im)) else {
+            return Err(LispError::new("complex: parts must be real numbers"));
+        };
+        if matches!(re, Expr::Float(_)) || matches!(im, Expr::Float(_)) {
+            Ok(Expr::Complex(Box::new(Expr::Float(re_float)), Box::new(Expr::Float(im_float))))
+        } else if im == Expr::Number(0) {
+            Ok(re)
         } else {
-            Ok(Expr::Number(result as i64))
+            Ok(Expr::Complex(Box::new(re), Box::new(im)))
+        }
+    }
+
+    // 数值的 (实部, 虚部)；实数的虚部为精确的 0
+    pub fn complex_parts(value: &Expr) -> Option<(Expr, Expr)> {
+        match value {
+            Expr::Complex(re, im) => Some(((**re).clone(), (**im).clone())),
+            _ => Arithmetic::to_f64(value).map(|_| (value.clone(), Expr::Number(0))),
+        }
+    }
+
+    // 至少有一个操作数是复数时，返回两个操作数的 (实部, 虚部)
+    fn complex_operands(left: &Expr, right: &Expr) -> Option<((Expr, Expr), (Expr, Expr))> {
+        if !matches!(left, Expr::Complex(_, _)) && !matches!(right, Expr::Complex(_, _)) {
+            return None;
+        }
+        Some((Arithmetic::complex_parts(left)?, Arithmetic::complex_parts(right)?))
+    }
+
+    pub fn add(left: &Expr, right: &Expr) -> Result<Expr, LispError> {
+        if let Some(((a, b), (c, d))) = Arithmetic::complex_operands(left, right) {
+            return Arithmetic::make_complex(Arithmetic::add(&a, &c)?, Arithmetic::add(&b, &d)?);
+        }
+        Arithmetic::combine(left, right, i64::checked_add, BigInt::add, Ratio::add, |l, r| l + r)
+    }
+
+    pub fn subtract(left: &Expr, right: &Expr) -> Result<Expr, LispError> {
+        if let Some(((a, b), (c, d))) = Arithmetic::complex_operands(left, right) {
+            return Arithmetic::make_complex(Arithmetic::subtract(&a, &c)?, Arithmetic::subtract(&b, &d)?);
+        }
+        Arithmetic::combine(left, right, i64::checked_sub, BigInt::sub, Ratio::sub, |l, r| l - r)
+    }
+
+    pub fn multiply(left: &Expr, right: &Expr) -> Result<Expr, LispError> {
+        // (a+bi)(c+di) = (ac-bd) + (ad+bc)i
+        if let Some(((a, b), (c, d))) = Arithmetic::complex_operands(left, right) {
+            let re = Arithmetic::subtract(&Arithmetic::multiply(&a, &c)?, &Arithmetic::multiply(&b, &d)?)?;
+            let im = Arithmetic::add(&Arithmetic::multiply(&a, &d)?, &Arithmetic::multiply(&b, &c)?)?;
+            return Arithmetic::make_complex(re, im);
+        }
+        Arithmetic::combine(left, right, i64::checked_mul, BigInt::mul, Ratio::mul, |l, r| l * r)
+    }
+
+    // 有理数相除得到精确结果（整除时为整数，否则为分数），有浮点数参与时为浮点数
+    pub fn divide(left: &Expr, right: &Expr) -> Result<Expr, LispError> {
+        // (a+bi)/(c+di) = ((ac+bd) + (bc-ad)i) / (c²+d²)
+        if let Some(((a, b), (c, d))) = Arithmetic::complex_operands(left, right) {
+            let norm = Arithmetic::add(&Arithmetic::multiply(&c, &c)?, &Arithmetic::multiply(&d, &d)?)?;
+            let re = Arithmetic::add(&Arithmetic::multiply(&a, &c)?, &Arithmetic::multiply(&b, &d)?)?;
+            let im = Arithmetic::subtract(&Arithmetic::multiply(&b, &c)?, &Arithmetic::multiply(&a, &d)?)?;
+            return Arithmetic::make_complex(Arithmetic::divide(&re, &norm)?, Arithmetic::divide(&im, &norm)?);
+        }
+        match (Arithmetic::to_ratio(left), Arithmetic::to_ratio(right)) {
+            (Some(l), Some(r)) => l
+                .div(&r)
+                .map(Expr::from_ratio)
+                .ok_or_else(|| LispError::new("Division by zero")),
+            _ => match (Arithmetic::to_f64(left), Arithmetic::to_f64(right)) {
+                (Some(_), Some(0.0)) => Err(LispError::new("Division by zero")),
+                (Some(l), Some(r)) => Ok(Expr::Float(l / r)),
+                _ => Err(LispError::new("Invalid number")),
+            },
+        }
+    }
+
+    // 实数之间的比较；参数不是数值或者含 NaN 时返回 None
+    pub fn compare(left: &Expr, right: &Expr) -> Option<Ordering> {
+        match (left, right) {
+            (Expr::Number(l), Expr::Number(r)) => Some(l.cmp(r)),
+            (Expr::Float(_), _) | (_, Expr::Float(_)) => Arithmetic::to_f64(left)?.partial_cmp(&Arithmetic::to_f64(right)?),
+            _ => Some(Arithmetic::to_ratio(left)?.cmp(&Arithmetic::to_ratio(right)?)),
         }
     }
 }
@@ -127,11 +242,14 @@ pub fn register_arithmetic_operators() {
     OperatorRegistry::register("-", Arithmetic::eval_subtract);
     OperatorRegistry::register("*", Arithmetic::eval_multiply);
     OperatorRegistry::register("/", Arithmetic::eval_divide);
+    OperatorRegistry::register("numerator", Arithmetic::eval_numerator);
+    OperatorRegistry::register("denominator", Arithmetic::eval_denominator);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
     use crate::environment::Environment;
     use crate::expression::Expr;
 
@@ -266,7 +384,11 @@ mod tests {
         let result = Arithmetic::eval_subtract(&args, &mut env);
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Expr::Number(5)); // Subtraction with a single argument returns the argument itself
+        assert_eq!(result.unwrap(), Expr::Number(-5)); // Subtraction with a single argument negates it
+
+        assert_eq!(eval_to_string("(- (expt 2 100))", &mut env), "-1267650600228229401496703205376");
+        assert_eq!(eval_to_string("(- -1/2)", &mut env), "1/2");
+        assert_eq!(eval_to_string("(- 2.5)", &mut env), "-2.5");
     }
 
     #[test]
@@ -463,7 +585,11 @@ mod tests {
         let args = vec![Expr::Number(10)];
         let result = Arithmetic::eval_divide(&args, &mut env);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Expr::Number(10)); // Division with a single argument returns the argument itself
+        assert_eq!(eval_to_string("(/ 10)", &mut env), "1/10"); // Division with a single argument returns the reciprocal
+        assert_eq!(eval_to_string("(/ 2)", &mut env), "1/2");
+        assert_eq!(eval_to_string("(/ 1/4)", &mut env), "4");
+        assert_eq!(eval_to_string("(/ 0.5)", &mut env), "2.0");
+        assert!(eval_str("(/ 0)", &mut env).is_err());
     }
 
     #[test]
@@ -502,7 +628,7 @@ mod tests {
         ]);
 
         let result = Evaluator::eval(&expr, &mut env);
-        assert_eq!(result, Ok(Expr::Float(750.0))); // Should return 750.0
+        assert_eq!(result, Ok(Expr::Number(750))); // (/ 3 2) 是精确的有理数 3/2，所以乘积恰好是 750
     }
 
     #[test]
@@ -565,4 +691,104 @@ mod tests {
         assert_eq!(result.unwrap(), Expr::Number(-6)); // 确认负数乘法
     }
 
+    #[test]
+    fn test_overflow_promotes_to_bigint() {
+        let mut env = Environment::initialize();
+        let result = eval_str("(+ 9223372036854775807 1)", &mut env).unwrap();
+        assert_eq!(result.to_string(), "9223372036854775808");
+
+        let result = eval_str("(* 4294967296 4294967296)", &mut env).unwrap();
+        assert_eq!(result.to_string(), "18446744073709551616");
+
+        let result = eval_str("(- -9223372036854775808 1)", &mut env).unwrap();
+        assert_eq!(result.to_string(), "-9223372036854775809");
+    }
+
+    #[test]
+    fn test_bigint_demotes_when_small() {
+        let mut env = Environment::initialize();
+        let result = eval_str("(- 9223372036854775808 1)", &mut env).unwrap();
+        assert_eq!(result, Expr::Number(i64::MAX));
+
+        let result = eval_str("(/ 18446744073709551616 4294967296)", &mut env).unwrap();
+        assert_eq!(result, Expr::Number(4294967296));
+    }
+
+    #[test]
+    fn test_bigint_literal_round_trip() {
+        let mut env = Environment::initialize();
+        let result = eval_str("123456789012345678901234567890", &mut env).unwrap();
+        assert_eq!(result.to_string(), "123456789012345678901234567890");
+
+        let result = eval_str("(+ 123456789012345678901234567890 -123456789012345678901234567890)", &mut env).unwrap();
+        assert_eq!(result, Expr::Number(0));
+    }
+
+    #[test]
+    fn test_factorial_of_50_is_exact() {
+        let mut env = Environment::initialize();
+        eval_str("(defun fact (n) (cond ((eq n 0) 1) (t (* n (fact (- n 1))))))", &mut env).unwrap();
+        let result = eval_str("(fact 50)", &mut env).unwrap();
+        assert_eq!(
+            result.to_string(),
+            "30414093201713378043612608166064768844377641568960512000000000000"
+        );
+    }
+
+    #[test]
+    fn test_bigint_float_contagion() {
+        let mut env = Environment::initialize();
+        let result = eval_str("(+ 18446744073709551616 0.5)", &mut env).unwrap();
+        assert_eq!(result, Expr::Float(18446744073709551616.5));
+    }
+
+    #[test]
+    fn test_divide_inexact_and_by_zero() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(/ 7.0 2)", &mut env).unwrap(), Expr::Float(3.5));
+        assert_eq!(eval_str("(/ 1 0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(eval_str("(/ 1.0 0.0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(
+            eval_str("(/ 18446744073709551616 0)", &mut env),
+            Err(LispError::new("Division by zero"))
+        );
+    }
+
+    #[test]
+    fn test_divide_produces_exact_ratio() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(/ 1 3)", &mut env).unwrap().to_string(), "1/3");
+        assert_eq!(eval_str("(/ 10 4)", &mut env).unwrap().to_string(), "5/2");
+        assert_eq!(eval_str("(/ -6 4)", &mut env).unwrap().to_string(), "-3/2");
+        assert_eq!(eval_str("(/ 12 4)", &mut env).unwrap(), Expr::Number(3));
+        assert_eq!(eval_str("(* 3 (/ 1 3))", &mut env).unwrap(), Expr::Number(1));
+    }
+
+    #[test]
+    fn test_ratio_contagion() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(+ 1/3 1/6)", &mut env).unwrap().to_string(), "1/2");
+        assert_eq!(eval_str("(+ 1/2 1)", &mut env).unwrap().to_string(), "3/2");
+        assert_eq!(eval_str("(- 1/2 1/2)", &mut env).unwrap(), Expr::Number(0));
+        assert_eq!(eval_str("(+ 1/2 0.25)", &mut env).unwrap(), Expr::Float(0.75));
+        assert_eq!(eval_str("(/ 1/2 0.5)", &mut env).unwrap(), Expr::Float(1.0));
+        assert_eq!(eval_str("(/ 1/2 0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(
+            eval_str("(/ 1 18446744073709551616)", &mut env).unwrap().to_string(),
+            "1/18446744073709551616"
+        );
+    }
+
+    #[test]
+    fn test_numerator_and_denominator() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(numerator 6/4)", &mut env).unwrap(), Expr::Number(3));
+        assert_eq!(eval_str("(denominator 6/4)", &mut env).unwrap(), Expr::Number(2));
+        assert_eq!(eval_str("(numerator -1/3)", &mut env).unwrap(), Expr::Number(-1));
+        assert_eq!(eval_str("(denominator 7)", &mut env).unwrap(), Expr::Number(1));
+        assert_eq!(
+            eval_str("(numerator 1.5)", &mut env),
+            Err(LispError::new("numerator: argument must be a rational number, got 1.5"))
+        );
+    }
 }
@@ -0,0 +1,366 @@
+// operator/array.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::lambda::eval_keyword_arguments;
+use crate::array::Array;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct ArrayOps;
+
+impl ArrayOps {
+    pub fn make(array: Array) -> Expr {
+        Expr::Array(Rc::new(RefCell::new(array)))
+    }
+
+
+    fn array_argument(name: &str, value: &Expr) -> Result<Rc<RefCell<Array>>, LispError> {
+        match value {
+            Expr::Array(array) => Ok(array.clone()),
+            other => Err(LispError::new(&format!("{}: {} is not an array", name, other))),
+        }
+    }
+
+    fn vector_argument(name: &str, value: &Expr) -> Result<Rc<RefCell<Array>>, LispError> {
+        let array = ArrayOps::array_argument(name, value)?;
+        if array.borrow().rank() != 1 {
+            return Err(LispError::new(&format!("{}: {} is not a vector", name, value)));
+        }
+        Ok(array)
+    }
+
+    fn index_argument(name: &str, value: &Expr) -> Result<usize, LispError> {
+        match value {
+            Expr::Number(n) if *n >= 0 => Ok(*n as usize),
+            other => Err(LispError::new(&format!("{}: index must be a non-negative integer, got {}", name, other))),
+        }
+    }
+
+    // 维度可以是一个整数，也可以是整数列表；() 表示零维数组
+    fn dimensions_argument(value: &Expr) -> Result<Vec<usize>, LispError> {
+        match value {
+            Expr::List(items) => items.iter().map(|item| ArrayOps::index_argument("make-array", item)).collect(),
+            other => Ok(vec![ArrayOps::index_argument("make-array", other)?]),
+        }
+    }
+
+    fn element_index(name: &str, array: &Array, subscripts: &[Expr]) -> Result<usize, LispError> {
+        let subscripts = subscripts.iter().map(|s| ArrayOps::index_argument(name, s)).collect::<Result<Vec<_>, _>>()?;
+        array.row_major_index(&subscripts).map_err(|message| LispError::new(&format!("{}: {}", name, message)))
+    }
+
+    // (vector items...)
+    pub fn eval_vector(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let items = args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        Ok(ArrayOps::make(Array::vector(items)))
+    }
+
+    // (make-array dimensions &key initial-element initial-contents fill-pointer adjustable element-type)
+    // 所有数组都可以调整大小，:adjustable 和 :element-type 只是为了兼容而接受
+    pub fn eval_make_array(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("make-array", args, 1, usize::MAX)?;
+        let dimensions = ArrayOps::dimensions_argument(&Evaluator::eval(&args[0], env)?)?;
+        let options = eval_keyword_arguments(
+            "make-array",
+            &args[1..],
+            env,
+            &["initial-element", "initial-contents", "fill-pointer", "adjustable", "element-type"],
+        )?;
+        let mut initial_element = Expr::nil();
+        let mut initial_contents = None;
+        let mut fill_pointer = None;
+        for (keyword, value) in options {
+            match keyword.as_str() {
+                "initial-element" => initial_element = value,
+                "initial-contents" => initial_contents = Some(value),
+                "fill-pointer" => fill_pointer = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut array = match initial_contents {
+            Some(contents) => {
+                let array = Array::from_nested(dimensions.len(), &contents)
+                    .map_err(|message| LispError::new(&format!("make-array: {}", message)))?;
+                if array.dimensions() != dimensions.as_slice() {
+                    return Err(LispError::new(&format!("make-array: initial contents {} do not match the dimensions", contents)));
+                }
+                array
+            }
+            None => Array::new(dimensions.clone(), initial_element)
+                .map_err(|message| LispError::new(&format!("make-array: {}", message)))?,
+        };
+        match fill_pointer {
+            None => {}
+            Some(value) if !value.is_truthy() => {}
+            Some(value) => {
+                if dimensions.len() != 1 {
+                    return Err(LispError::new("make-array: only vectors can have a fill pointer"));
+                }
+                let position = if value == Expr::t() { dimensions[0] } else { ArrayOps::index_argument("make-array", &value)? };
+                if position > dimensions[0] {
+                    return Err(LispError::new(&format!("make-array: fill pointer {} exceeds the size {}", position, dimensions[0])));
+                }
+                array = array.with_fill_pointer(position);
+            }
+        }
+        Ok(ArrayOps::make(array))
+    }
+
+    // (aref array subscripts...)：按下标访问，不受填充指针限制
+    pub fn eval_aref(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("aref", args, 1, usize::MAX)?;
+        let values = args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        let array = ArrayOps::array_argument("aref", &values[0])?;
+        let array = array.borrow();
+        let index = ArrayOps::element_index("aref", &array, &values[1..])?;
+        Ok(array.get(index).clone())
+    }
+
+    // (setf (aref array subscripts...) value)
+    pub fn setf_aref(args: &[Expr], value: Expr, _env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf aref)", args, 1, usize::MAX)?;
+        let array = ArrayOps::array_argument("(setf aref)", &args[0])?;
+        let mut array = array.borrow_mut();
+        let index = ArrayOps::element_index("(setf aref)", &array, &args[1..])?;
+        array.set(index, value.clone());
+        Ok(value)
+    }
+
+    pub fn eval_array_dimensions(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("array-dimensions", args, 1, 1)?;
+        let array = ArrayOps::array_argument("array-dimensions", &Evaluator::eval(&args[0], env)?)?;
+        let dimensions = array.borrow().dimensions().iter().map(|&d| Expr::Number(d as i64)).collect();
+        Ok(Expr::List(dimensions))
+    }
+
+    pub fn eval_array_dimension(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("array-dimension", args, 2, 2)?;
+        let array = ArrayOps::array_argument("array-dimension", &Evaluator::eval(&args[0], env)?)?;
+        let axis = ArrayOps::index_argument("array-dimension", &Evaluator::eval(&args[1], env)?)?;
+        let array = array.borrow();
+        match array.dimensions().get(axis) {
+            Some(&dimension) => Ok(Expr::Number(dimension as i64)),
+            None => Err(LispError::new(&format!("array-dimension: axis {} is out of range for rank {}", axis, array.rank()))),
+        }
+    }
+
+    pub fn eval_array_rank(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("array-rank", args, 1, 1)?;
+        let array = ArrayOps::array_argument("array-rank", &Evaluator::eval(&args[0], env)?)?;
+        let rank = array.borrow().rank();
+        Ok(Expr::Number(rank as i64))
+    }
+
+    pub fn eval_arrayp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("arrayp", args, 1, 1)?;
+        Ok(Expr::from_bool(matches!(Evaluator::eval(&args[0], env)?, Expr::Array(_))))
+    }
+
+    pub fn eval_vectorp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("vectorp", args, 1, 1)?;
+        Ok(Expr::from_bool(match Evaluator::eval(&args[0], env)? {
+            Expr::Array(array) => array.borrow().rank() == 1,
+            _ => false,
+        }))
+    }
+
+    pub fn eval_fill_pointer(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("fill-pointer", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let vector = ArrayOps::vector_argument("fill-pointer", &value)?;
+        let fill_pointer = vector.borrow().fill_pointer();
+        match fill_pointer {
+            Some(position) => Ok(Expr::Number(position as i64)),
+            None => Err(LispError::new(&format!("fill-pointer: {} has no fill pointer", value))),
+        }
+    }
+
+    // (setf (fill-pointer vector) n)
+    pub fn setf_fill_pointer(args: &[Expr], value: Expr, _env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf fill-pointer)", args, 1, 1)?;
+        let vector = ArrayOps::vector_argument("(setf fill-pointer)", &args[0])?;
+        let position = ArrayOps::index_argument("(setf fill-pointer)", &value)?;
+        vector
+            .borrow_mut()
+            .set_fill_pointer(position)
+            .map_err(|message| LispError::new(&format!("(setf fill-pointer): {}", message)))?;
+        Ok(value)
+    }
+
+    fn push(name: &str, value: Expr, vector: &Expr, extension: Option<usize>) -> Result<Expr, LispError> {
+        let vector = ArrayOps::vector_argument(name, vector)?;
+        let mut vector = vector.borrow_mut();
+        // 默认每次扩大一倍，至少扩大一个元素
+        let extension = extension.unwrap_or_else(|| vector.dimensions()[0].max(1));
+        match vector.push(value, extension) {
+            Ok(Some(index)) => Ok(Expr::Number(index as i64)),
+            Ok(None) => Ok(Expr::nil()),
+            Err(message) => Err(LispError::new(&format!("{}: {}", name, message))),
+        }
+    }
+
+    // (vector-push value vector)：空间已满时返回 nil
+    pub fn eval_vector_push(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("vector-push", args, 2, 2)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let vector = Evaluator::eval(&args[1], env)?;
+        ArrayOps::push("vector-push", value, &vector, Some(0))
+    }
+
+    // (vector-push-extend value vector [extension])：空间已满时扩大向量，返回新元素的下标
+    pub fn eval_vector_push_extend(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("vector-push-extend", args, 2, 3)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let vector = Evaluator::eval(&args[1], env)?;
+        let extension = match args.get(2) {
+            Some(arg) => match Evaluator::eval(arg, env)? {
+                Expr::Number(n) if n > 0 => Some(n as usize),
+                other => return Err(LispError::new(&format!("vector-push-extend: extension must be a positive integer, got {}", other))),
+            },
+            None => None,
+        };
+        ArrayOps::push("vector-push-extend", value, &vector, extension)
+    }
+
+    pub fn eval_vector_pop(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("vector-pop", args, 1, 1)?;
+        let vector = ArrayOps::vector_argument("vector-pop", &Evaluator::eval(&args[0], env)?)?;
+        let popped = vector.borrow_mut().pop();
+        popped.map_err(|message| LispError::new(&format!("vector-pop: {}", message)))
+    }
+}
+
+pub fn register_array_operators() {
+    OperatorRegistry::register("vector", ArrayOps::eval_vector);
+    OperatorRegistry::register("make-array", ArrayOps::eval_make_array);
+    OperatorRegistry::register("aref", ArrayOps::eval_aref);
+    OperatorRegistry::register("array-dimensions", ArrayOps::eval_array_dimensions);
+    OperatorRegistry::register("array-dimension", ArrayOps::eval_array_dimension);
+    OperatorRegistry::register("array-rank", ArrayOps::eval_array_rank);
+    OperatorRegistry::register("arrayp", ArrayOps::eval_arrayp);
+    OperatorRegistry::register("vectorp", ArrayOps::eval_vectorp);
+    OperatorRegistry::register("fill-pointer", ArrayOps::eval_fill_pointer);
+    OperatorRegistry::register("vector-push", ArrayOps::eval_vector_push);
+    OperatorRegistry::register("vector-push-extend", ArrayOps::eval_vector_push_extend);
+    OperatorRegistry::register("vector-pop", ArrayOps::eval_vector_pop);
+    OperatorRegistry::register_setf("aref", ArrayOps::setf_aref);
+    OperatorRegistry::register_setf("fill-pointer", ArrayOps::setf_fill_pointer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_in_new_env};
+
+    #[test]
+    fn test_vector_syntax_and_construction() {
+        assert_eq!(eval_in_new_env("#(1 2 3)"), "#(1 2 3)");
+        assert_eq!(eval_in_new_env("#(a (b c) \"d\")"), "#(a (b c) \"d\")");
+        assert_eq!(eval_in_new_env("(vector 1 (+ 1 1) 'x)"), "#(1 2 x)");
+        assert_eq!(eval_in_new_env("(vector)"), "#()");
+        assert_eq!(eval_in_new_env("(make-array 3 :initial-element 0)"), "#(0 0 0)");
+        assert_eq!(eval_in_new_env("(make-array '(2 3) :initial-element 'x)"), "#2A((x x x) (x x x))");
+        assert_eq!(eval_in_new_env("(make-array '(2 2) :initial-contents '((1 2) (3 4)))"), "#2A((1 2) (3 4))");
+        assert_eq!(eval_in_new_env("#2A((1 2) (3 4))"), "#2A((1 2) (3 4))");
+        assert_eq!(eval_in_new_env("(aref #2A((1 2) (3 4)) 1 0)"), "3");
+        assert_eq!(eval_in_new_env("(length #(1 2 3))"), "3");
+        assert_eq!(eval_in_new_env("(vectorp #(1))"), "t");
+        assert_eq!(eval_in_new_env("(vectorp #2A((1)))"), "()");
+        assert_eq!(eval_in_new_env("(arrayp #2A((1)))"), "t");
+        assert_eq!(eval_in_new_env("(arrayp '(1))"), "()");
+    }
+
+    #[test]
+    fn test_aref_and_setf_mutate_in_place() {
+        let mut env = Environment::initialize();
+        eval_str("(setf v (make-array 3 :initial-element 0))", &mut env).unwrap();
+        eval_str("(setf alias v)", &mut env).unwrap();
+        assert_eq!(eval_str("(setf (aref v 1) 'b)", &mut env), Ok(Expr::Symbol("b".to_string())));
+        assert_eq!(eval_str("alias", &mut env).unwrap().to_string(), "#(0 b 0)");
+        assert_eq!(eval_str("(aref alias 1)", &mut env), Ok(Expr::Symbol("b".to_string())));
+
+        // 在函数里修改向量，调用者能看到修改
+        eval_str("(defun bump (vec i) (setf (aref vec i) (+ (aref vec i) 1)))", &mut env).unwrap();
+        eval_str("(bump v 0)", &mut env).unwrap();
+        eval_str("(bump v 0)", &mut env).unwrap();
+        assert_eq!(eval_str("(aref v 0)", &mut env), Ok(Expr::Number(2)));
+
+        eval_str("(setf grid (make-array '(2 3) :initial-element 0))", &mut env).unwrap();
+        eval_str("(setf (aref grid 1 2) 5)", &mut env).unwrap();
+        assert_eq!(eval_str("grid", &mut env).unwrap().to_string(), "#2A((0 0 0) (0 0 5))");
+        assert_eq!(eval_str("(array-dimensions grid)", &mut env).unwrap().to_string(), "(2 3)");
+        assert_eq!(eval_str("(array-dimension grid 1)", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_str("(array-rank grid)", &mut env), Ok(Expr::Number(2)));
+    }
+
+    #[test]
+    fn test_fill_pointer_and_vector_push_extend() {
+        let mut env = Environment::initialize();
+        eval_str("(setf v (make-array 2 :fill-pointer 0))", &mut env).unwrap();
+        assert_eq!(eval_str("(length v)", &mut env), Ok(Expr::Number(0)));
+        assert_eq!(eval_str("(vector-push-extend 'a v)", &mut env), Ok(Expr::Number(0)));
+        assert_eq!(eval_str("(vector-push 'b v)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(vector-push 'c v)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(vector-push-extend 'c v)", &mut env), Ok(Expr::Number(2)));
+        assert_eq!(eval_str("(fill-pointer v)", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_str("v", &mut env).unwrap().to_string(), "#(a b c)");
+        assert_eq!(eval_str("(array-dimensions v)", &mut env).unwrap().to_string(), "(4)");
+        assert_eq!(eval_str("(vector-pop v)", &mut env), Ok(Expr::Symbol("c".to_string())));
+        eval_str("(setf (fill-pointer v) 1)", &mut env).unwrap();
+        assert_eq!(eval_str("v", &mut env).unwrap().to_string(), "#(a)");
+        assert_eq!(eval_str("(length v)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(length (make-array 3 :fill-pointer t))", &mut env), Ok(Expr::Number(3)));
+    }
+
+    #[test]
+    fn test_array_equality() {
+        let mut env = Environment::initialize();
+        eval_str("(setf v #(1 2))", &mut env).unwrap();
+        assert_eq!(eval_str("(eq v v)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equal #(1 2) #(1 2))", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(equalp #(1 \"a\") (vector 1.0 \"A\"))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equalp #(1 2) #2A((1 2)))", &mut env), Ok(Expr::nil()));
+    }
+
+    #[test]
+    fn test_array_errors() {
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(aref #(1 2) 2)", &mut env),
+            Err(LispError::new("aref: index 2 is out of bounds for dimension 2"))
+        );
+        assert_eq!(
+            eval_str("(aref #(1 2) 0 0)", &mut env),
+            Err(LispError::new("aref: expected 1 subscripts, got 2"))
+        );
+        assert_eq!(eval_str("(aref '(1 2) 0)", &mut env), Err(LispError::new("aref: (1 2) is not an array")));
+        assert_eq!(
+            eval_str("(vector-push-extend 1 #(1 2))", &mut env),
+            Err(LispError::new("vector-push-extend: vector has no fill pointer"))
+        );
+        assert_eq!(
+            eval_str("(make-array '(2 2) :fill-pointer 0)", &mut env),
+            Err(LispError::new("make-array: only vectors can have a fill pointer"))
+        );
+        assert_eq!(
+            eval_str("(setf (car x) 1)", &mut env),
+            Err(LispError::new("setf: car is not a settable place"))
+        );
+        assert_eq!(eval_str("(length #2A((1)))", &mut env), Err(LispError::new("length: argument is not a sequence")));
+
+        // 元素总数超过 array-total-size-limit 时报错，而不是耗尽内存
+        assert_eq!(eval_str("array-total-size-limit", &mut env), Ok(Expr::Number(4194304)));
+        assert_eq!(
+            eval_str("(make-array '(100000 100000))", &mut env),
+            Err(LispError::new("make-array: dimensions (100000 100000) exceed array-total-size-limit 4194304"))
+        );
+        assert!(eval_str("(make-array '(4294967296 4294967296 4294967296))", &mut env).is_err());
+        assert!(eval_str("(make-array (+ array-total-size-limit 1))", &mut env).is_err());
+        assert!(eval_str("(vector-push-extend 1 (make-array 0 :fill-pointer 0) 100000000)", &mut env).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// operator/bitwise.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::arithmetic::Arithmetic;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::bignum::BigInt;
+
+// 移位和字节字段允许的最大位数，防止一次操作分配过多内存
+const MAX_SHIFT: i64 = 1 << 24;
+
+pub struct Bitwise;
+
+impl Bitwise {
+    fn integer(name: &str, value: Expr) -> Result<BigInt, LispError> {
+        Arithmetic::to_bigint(&value)
+            .ok_or_else(|| LispError::new(&format!("{}: argument must be an integer, got {}", name, value)))
+    }
+
+    fn integer_argument(name: &str, arg: &Expr, env: &mut Environment) -> Result<BigInt, LispError> {
+        Bitwise::integer(name, Evaluator::eval(arg, env)?)
+    }
+
+    // 位数、位置等必须是不太大的非负整数
+    fn index(name: &str, value: &Expr) -> Result<usize, LispError> {
+        match value {
+            Expr::Number(n) if (0..=MAX_SHIFT).contains(n) => Ok(*n as usize),
+            _ => Err(LispError::new(&format!("{}: {} is not a valid bit index", name, value))),
+        }
+    }
+
+
+    fn fold(name: &str, args: &[Expr], env: &mut Environment, identity: i64, op: fn(&BigInt, &BigInt) -> BigInt) -> Result<Expr, LispError> {
+        let mut result = BigInt::from_i64(identity);
+        for arg in args {
+            result = op(&result, &Bitwise::integer_argument(name, arg, env)?);
+        }
+        Ok(Expr::from_bigint(result))
+    }
+
+    pub fn eval_logand(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Bitwise::fold("logand", args, env, -1, BigInt::and)
+    }
+
+    pub fn eval_logior(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Bitwise::fold("logior", args, env, 0, BigInt::or)
+    }
+
+    pub fn eval_logxor(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Bitwise::fold("logxor", args, env, 0, BigInt::xor)
+    }
+
+    pub fn eval_lognot(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("lognot", args, 1, 1)?;
+        Ok(Expr::from_bigint(Bitwise::integer_argument("lognot", &args[0], env)?.not()))
+    }
+
+    // (ash integer count)：count 为正时左移，为负时算术右移
+    pub fn eval_ash(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("ash", args, 2, 2)?;
+        let integer = Bitwise::integer_argument("ash", &args[0], env)?;
+        let count = Bitwise::integer_argument("ash", &args[1], env)?;
+        let result = if count.is_negative() {
+            // 右移超过整数本身的位数后结果只取决于符号
+            let limit = integer.integer_length() as i64 + 1;
+            let bits = count.abs().to_i64().map_or(limit, |bits| bits.min(limit));
+            integer.shift_right(bits as usize)
+        } else if integer.is_zero() {
+            integer
+        } else {
+            integer.shift_left(Bitwise::index("ash", &Expr::from_bigint(count))?)
+        };
+        Ok(Expr::from_bigint(result))
+    }
+
+    pub fn eval_logbitp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("logbitp", args, 2, 2)?;
+        let index = Bitwise::index("logbitp", &Evaluator::eval(&args[0], env)?)?;
+        let integer = Bitwise::integer_argument("logbitp", &args[1], env)?;
+        Ok(Expr::from_bool(integer.bit(index)))
+    }
+
+    pub fn eval_logcount(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("logcount", args, 1, 1)?;
+        let integer = Bitwise::integer_argument("logcount", &args[0], env)?;
+        Ok(Expr::Number(integer.count_ones() as i64))
+    }
+
+    pub fn eval_integer_length(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("integer-length", args, 1, 1)?;
+        let integer = Bitwise::integer_argument("integer-length", &args[0], env)?;
+        Ok(Expr::Number(integer.integer_length() as i64))
+    }
+
+    // 字节说明符 (byte size position) 表示为点对 (size . position)
+    pub fn eval_byte(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("byte", args, 2, 2)?;
+        let size = Evaluator::eval(&args[0], env)?;
+        let position = Evaluator::eval(&args[1], env)?;
+        Bitwise::index("byte", &size)?;
+        Bitwise::index("byte", &position)?;
+        Ok(Expr::DottedPair(Box::new(size), Box::new(position)))
+    }
+
+    pub fn eval_byte_size(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("byte-size", args, 1, 1)?;
+        let (size, _) = Bitwise::byte_spec("byte-size", &Evaluator::eval(&args[0], env)?)?;
+        Ok(Expr::Number(size as i64))
+    }
+
+    pub fn eval_byte_position(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("byte-position", args, 1, 1)?;
+        let (_, position) = Bitwise::byte_spec("byte-position", &Evaluator::eval(&args[0], env)?)?;
+        Ok(Expr::Number(position as i64))
+    }
+
+    fn byte_spec(name: &str, spec: &Expr) -> Result<(usize, usize), LispError> {
+        match spec {
+            Expr::DottedPair(size, position) => Ok((Bitwise::index(name, size)?, Bitwise::index(name, position)?)),
+            _ => Err(LispError::new(&format!("{}: {} is not a byte specifier", name, spec))),
+        }
+    }
+
+    // size 个 1 组成的掩码
+    fn mask(size: usize) -> BigInt {
+        BigInt::from_i64(1).shift_left(size).sub(&BigInt::from_i64(1))
+    }
+
+    // (ldb bytespec integer)：取出 integer 中 position 开始的 size 位
+    pub fn eval_ldb(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("ldb", args, 2, 2)?;
+        let (size, position) = Bitwise::byte_spec("ldb", &Evaluator::eval(&args[0], env)?)?;
+        let integer = Bitwise::integer_argument("ldb", &args[1], env)?;
+        Ok(Expr::from_bigint(integer.shift_right(position).and(&Bitwise::mask(size))))
+    }
+
+    // (dpb newbyte bytespec integer)：把 newbyte 的低 size 位放到 integer 的对应位置
+    pub fn eval_dpb(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("dpb", args, 3, 3)?;
+        let newbyte = Bitwise::integer_argument("dpb", &args[0], env)?;
+        let (size, position) = Bitwise::byte_spec("dpb", &Evaluator::eval(&args[1], env)?)?;
+        let integer = Bitwise::integer_argument("dpb", &args[2], env)?;
+        let mask = Bitwise::mask(size).shift_left(position);
+        let field = newbyte.shift_left(position).and(&mask);
+        Ok(Expr::from_bigint(integer.and(&mask.not()).or(&field)))
+    }
+}
+
+pub fn register_bitwise_operators() {
+    OperatorRegistry::register("logand", Bitwise::eval_logand);
+    OperatorRegistry::register("logior", Bitwise::eval_logior);
+    OperatorRegistry::register("logxor", Bitwise::eval_logxor);
+    OperatorRegistry::register("lognot", Bitwise::eval_lognot);
+    OperatorRegistry::register("ash", Bitwise::eval_ash);
+    OperatorRegistry::register("logbitp", Bitwise::eval_logbitp);
+    OperatorRegistry::register("logcount", Bitwise::eval_logcount);
+    OperatorRegistry::register("integer-length", Bitwise::eval_integer_length);
+    OperatorRegistry::register("byte", Bitwise::eval_byte);
+    OperatorRegistry::register("byte-size", Bitwise::eval_byte_size);
+    OperatorRegistry::register("byte-position", Bitwise::eval_byte_position);
+    OperatorRegistry::register("ldb", Bitwise::eval_ldb);
+    OperatorRegistry::register("dpb", Bitwise::eval_dpb);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_in_new_env};
+    use crate::environment::Environment;
+
+    #[test]
+    fn test_logical_operations() {
+        assert_eq!(eval_in_new_env("(logand)"), "-1");
+        assert_eq!(eval_in_new_env("(logior)"), "0");
+        assert_eq!(eval_in_new_env("(logand 12 10)"), "8");
+        assert_eq!(eval_in_new_env("(logior 12 10 1)"), "15");
+        assert_eq!(eval_in_new_env("(logxor 12 10)"), "6");
+        assert_eq!(eval_in_new_env("(logand -1 255)"), "255");
+        assert_eq!(eval_in_new_env("(logand -16 255)"), "240");
+        assert_eq!(eval_in_new_env("(lognot 0)"), "-1");
+        assert_eq!(eval_in_new_env("(lognot -9223372036854775808)"), "9223372036854775807");
+        assert_eq!(eval_in_new_env("(logior 18446744073709551616 1)"), "18446744073709551617");
+        assert_eq!(eval_in_new_env("(logand -18446744073709551617 18446744073709551615)"), "18446744073709551615");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(logand 1 2.0)", &mut env),
+            Err(LispError::new("logand: argument must be an integer, got 2.0"))
+        );
+        assert_eq!(eval_str("(lognot)", &mut env), Err(LispError::new("lognot expects 1 argument")));
+    }
+
+    #[test]
+    fn test_ash() {
+        assert_eq!(eval_in_new_env("(ash 1 10)"), "1024");
+        assert_eq!(eval_in_new_env("(ash 1 64)"), "18446744073709551616");
+        assert_eq!(eval_in_new_env("(ash 1024 -3)"), "128");
+        assert_eq!(eval_in_new_env("(ash -5 -1)"), "-3");
+        assert_eq!(eval_in_new_env("(ash -1 -1000)"), "-1");
+        assert_eq!(eval_in_new_env("(ash 5 -9223372036854775808)"), "0");
+        assert_eq!(eval_in_new_env("(ash 18446744073709551616 -64)"), "1");
+        assert_eq!(eval_in_new_env("(ash -7 -100000000000000000000)"), "-1");
+        assert_eq!(eval_in_new_env("(ash 0 100000000000000000000)"), "0");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(ash 1 100000000000)", &mut env),
+            Err(LispError::new("ash: 100000000000 is not a valid bit index"))
+        );
+    }
+
+    #[test]
+    fn test_bit_queries() {
+        assert_eq!(eval_in_new_env("(logbitp 2 5)"), "t");
+        assert_eq!(eval_in_new_env("(logbitp 1 5)"), "()");
+        assert_eq!(eval_in_new_env("(logbitp 200 -1)"), "t");
+        assert_eq!(eval_in_new_env("(logcount 255)"), "8");
+        assert_eq!(eval_in_new_env("(logcount -256)"), "8");
+        assert_eq!(eval_in_new_env("(integer-length 255)"), "8");
+        assert_eq!(eval_in_new_env("(integer-length -256)"), "8");
+        assert_eq!(eval_in_new_env("(integer-length 0)"), "0");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(logbitp -1 5)", &mut env),
+            Err(LispError::new("logbitp: -1 is not a valid bit index"))
+        );
+    }
+
+    #[test]
+    fn test_byte_fields() {
+        assert_eq!(eval_in_new_env("(byte 8 4)"), "(8 . 4)");
+        assert_eq!(eval_in_new_env("(byte-size (byte 8 4))"), "8");
+        assert_eq!(eval_in_new_env("(byte-position (byte 8 4))"), "4");
+        assert_eq!(eval_in_new_env("(ldb (byte 8 8) 4660)"), "18");
+        assert_eq!(eval_in_new_env("(ldb (byte 4 0) -1)"), "15");
+        assert_eq!(eval_in_new_env("(ldb (byte 8 64) 18446744073709551616)"), "1");
+        assert_eq!(eval_in_new_env("(dpb 255 (byte 8 8) 0)"), "65280");
+        assert_eq!(eval_in_new_env("(dpb 1 (byte 4 4) 4095)"), "3871");
+        assert_eq!(eval_in_new_env("(dpb 0 (byte 8 0) -1)"), "-256");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(ldb 8 255)", &mut env),
+            Err(LispError::new("ldb: 8 is not a byte specifier"))
+        );
+    }
+}
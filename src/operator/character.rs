@@ -0,0 +1,248 @@
+// operator/character.rs
+
+use crate::operator::OperatorRegistry;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+
+pub struct Character;
+
+impl Character {
+    fn char_value(name: &str, value: Expr) -> Result<char, LispError> {
+        match value {
+            Expr::Char(ch) => Ok(ch),
+            other => Err(LispError::new(&format!("{}: argument must be a character, got {}", name, other))),
+        }
+    }
+
+    fn char_argument(name: &str, args: &[Expr], env: &mut Environment) -> Result<char, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new(&format!("{} expects exactly one argument", name)));
+        }
+        Character::char_value(name, Evaluator::eval(&args[0], env)?)
+    }
+
+    // 大小写转换只在结果仍是单个字符时进行，例如 ß 的大写是两个字符，保持不变
+    fn single(mut mapped: impl Iterator<Item = char>, original: char) -> char {
+        match (mapped.next(), mapped.next()) {
+            (Some(ch), None) => ch,
+            _ => original,
+        }
+    }
+
+    pub fn upcase(ch: char) -> char {
+        Character::single(ch.to_uppercase(), ch)
+    }
+
+    pub fn downcase(ch: char) -> char {
+        Character::single(ch.to_lowercase(), ch)
+    }
+
+    pub fn eval_characterp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("characterp expects exactly one argument"));
+        }
+        Ok(Expr::from_bool(matches!(Evaluator::eval(&args[0], env)?, Expr::Char(_))))
+    }
+
+    // 字符的 Unicode 码点
+    pub fn eval_char_code(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let ch = Character::char_argument("char-code", args, env)?;
+        Ok(Expr::Number(ch as i64))
+    }
+
+    // 码点不是合法的 Unicode 标量值（例如代理项）时返回 nil
+    pub fn eval_code_char(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("code-char expects exactly one argument"));
+        }
+        // 实参必须是 Unicode 码位；代理项这类码位合法但没有对应的字符，返回 nil
+        match Evaluator::eval(&args[0], env)? {
+            Expr::Number(code) if (0..=0x10FFFF).contains(&code) => {
+                Ok(char::from_u32(code as u32).map_or_else(Expr::nil, Expr::Char))
+            }
+            other => Err(LispError::new(&format!("code-char: {} is not of type (integer 0 1114111)", other))),
+        }
+    }
+
+    pub fn eval_char_upcase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::Char(Character::upcase(Character::char_argument("char-upcase", args, env)?)))
+    }
+
+    pub fn eval_char_downcase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::Char(Character::downcase(Character::char_argument("char-downcase", args, env)?)))
+    }
+
+    pub fn eval_alpha_char_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(Character::char_argument("alpha-char-p", args, env)?.is_alphabetic()))
+    }
+
+    pub fn eval_upper_case_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(Character::char_argument("upper-case-p", args, env)?.is_uppercase()))
+    }
+
+    pub fn eval_lower_case_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(Character::char_argument("lower-case-p", args, env)?.is_lowercase()))
+    }
+
+    pub fn eval_whitespace_char_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(Character::char_argument("whitespace-char-p", args, env)?.is_whitespace()))
+    }
+
+    // (digit-char-p ch [radix])：是该进制的数字时返回它的值，否则返回 nil
+    pub fn eval_digit_char_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(LispError::new("digit-char-p expects one or two arguments"));
+        }
+        let ch = Character::char_value("digit-char-p", Evaluator::eval(&args[0], env)?)?;
+        let radix = match args.get(1) {
+            Some(arg) => match Evaluator::eval(arg, env)? {
+                Expr::Number(radix) if (2..=36).contains(&radix) => radix as u32,
+                other => return Err(LispError::new(&format!("digit-char-p: radix must be an integer between 2 and 36, got {}", other))),
+            },
+            None => 10,
+        };
+        Ok(ch.to_digit(radix).map_or_else(Expr::nil, |digit| Expr::Number(digit as i64)))
+    }
+
+    // 求值全部参数，并检查它们都是字符；ignore_case 为真时统一转换成小写再比较
+    fn eval_chars(name: &str, args: &[Expr], env: &mut Environment, ignore_case: bool) -> Result<Vec<char>, LispError> {
+        if args.is_empty() {
+            return Err(LispError::new(&format!("{} expects at least one argument", name)));
+        }
+        args.iter()
+            .map(|arg| {
+                let ch = Character::char_value(name, Evaluator::eval(arg, env)?)?;
+                Ok(if ignore_case { Character::downcase(ch) } else { ch })
+            })
+            .collect()
+    }
+
+    // 相邻的每一对字符都满足 holds 时为真，按码点比较
+    fn compare_adjacent(name: &str, args: &[Expr], env: &mut Environment, ignore_case: bool, holds: fn(char, char) -> bool) -> Result<Expr, LispError> {
+        let chars = Character::eval_chars(name, args, env, ignore_case)?;
+        Ok(Expr::from_bool(chars.windows(2).all(|pair| holds(pair[0], pair[1]))))
+    }
+
+    pub fn eval_char_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char=", args, env, false, |l, r| l == r)
+    }
+
+    // 所有字符两两不同
+    pub fn eval_char_not_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let chars = Character::eval_chars("char/=", args, env, false)?;
+        let all_distinct = chars.iter().enumerate().all(|(i, ch)| !chars[i + 1..].contains(ch));
+        Ok(Expr::from_bool(all_distinct))
+    }
+
+    pub fn eval_char_less(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char<", args, env, false, |l, r| l < r)
+    }
+
+    pub fn eval_char_greater(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char>", args, env, false, |l, r| l > r)
+    }
+
+    pub fn eval_char_less_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char<=", args, env, false, |l, r| l <= r)
+    }
+
+    pub fn eval_char_greater_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char>=", args, env, false, |l, r| l >= r)
+    }
+
+    // 不区分大小写的 char=
+    pub fn eval_char_equal_ignore_case(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char-equal", args, env, true, |l, r| l == r)
+    }
+
+    pub fn eval_char_lessp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Character::compare_adjacent("char-lessp", args, env, true, |l, r| l < r)
+    }
+}
+
+pub fn register_character_operators() {
+    OperatorRegistry::register("characterp", Character::eval_characterp);
+    OperatorRegistry::register("char-code", Character::eval_char_code);
+    OperatorRegistry::register("code-char", Character::eval_code_char);
+    OperatorRegistry::register("char-upcase", Character::eval_char_upcase);
+    OperatorRegistry::register("char-downcase", Character::eval_char_downcase);
+    OperatorRegistry::register("alpha-char-p", Character::eval_alpha_char_p);
+    OperatorRegistry::register("upper-case-p", Character::eval_upper_case_p);
+    OperatorRegistry::register("lower-case-p", Character::eval_lower_case_p);
+    OperatorRegistry::register("digit-char-p", Character::eval_digit_char_p);
+    OperatorRegistry::register("whitespace-char-p", Character::eval_whitespace_char_p);
+    OperatorRegistry::register("char=", Character::eval_char_equal);
+    OperatorRegistry::register("char/=", Character::eval_char_not_equal);
+    OperatorRegistry::register("char<", Character::eval_char_less);
+    OperatorRegistry::register("char>", Character::eval_char_greater);
+    OperatorRegistry::register("char<=", Character::eval_char_less_equal);
+    OperatorRegistry::register("char>=", Character::eval_char_greater_equal);
+    OperatorRegistry::register("char-equal", Character::eval_char_equal_ignore_case);
+    OperatorRegistry::register("char-lessp", Character::eval_char_lessp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_in_new_env};
+
+    #[test]
+    fn test_codes_and_case() {
+        assert_eq!(eval_in_new_env("(char-code #\\a)"), "97");
+        assert_eq!(eval_in_new_env("(char-code #\\λ)"), "955");
+        assert_eq!(eval_in_new_env("(code-char 955)"), "#\\λ");
+        assert_eq!(eval_in_new_env("(code-char 32)"), "#\\Space");
+        assert_eq!(eval_in_new_env("(code-char 55296)"), "()");
+        assert_eq!(eval_in_new_env("(code-char 57343)"), "()");
+        let mut env = Environment::initialize();
+        for arg in ["-1", "1114112", "(expt 2 70)", "1.5", "#\\a"] {
+            let message = format!("code-char: {} is not of type (integer 0 1114111)", eval_str(arg, &mut env).unwrap());
+            assert_eq!(eval_str(&format!("(code-char {})", arg), &mut env), Err(LispError::new(&message)));
+        }
+        assert_eq!(eval_in_new_env("(char-upcase #\\ä)"), "#\\Ä");
+        assert_eq!(eval_in_new_env("(char-downcase #\\Σ)"), "#\\σ");
+        assert_eq!(eval_in_new_env("(char-upcase #\\ß)"), "#\\ß");
+        assert_eq!(eval_in_new_env("(char-upcase #\\1)"), "#\\1");
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert_eq!(eval_in_new_env("(alpha-char-p #\\é)"), "t");
+        assert_eq!(eval_in_new_env("(alpha-char-p #\\字)"), "t");
+        assert_eq!(eval_in_new_env("(alpha-char-p #\\3)"), "()");
+        assert_eq!(eval_in_new_env("(digit-char-p #\\7)"), "7");
+        assert_eq!(eval_in_new_env("(digit-char-p #\\f 16)"), "15");
+        assert_eq!(eval_in_new_env("(digit-char-p #\\f)"), "()");
+        assert_eq!(eval_in_new_env("(whitespace-char-p #\\Tab)"), "t");
+        assert_eq!(eval_in_new_env("(whitespace-char-p #\\U+3000)"), "t");
+        assert_eq!(eval_in_new_env("(whitespace-char-p #\\a)"), "()");
+        assert_eq!(eval_in_new_env("(upper-case-p #\\Ä)"), "t");
+        assert_eq!(eval_in_new_env("(lower-case-p #\\Ä)"), "()");
+        assert_eq!(eval_in_new_env("(characterp #\\a)"), "t");
+        assert_eq!(eval_in_new_env("(characterp \"a\")"), "()");
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert_eq!(eval_in_new_env("(char= #\\a #\\a #\\a)"), "t");
+        assert_eq!(eval_in_new_env("(char= #\\a #\\A)"), "()");
+        assert_eq!(eval_in_new_env("(char-equal #\\a #\\A)"), "t");
+        assert_eq!(eval_in_new_env("(char< #\\a #\\b #\\c)"), "t");
+        assert_eq!(eval_in_new_env("(char< #\\a #\\c #\\b)"), "()");
+        assert_eq!(eval_in_new_env("(char/= #\\a #\\b #\\a)"), "()");
+        assert_eq!(eval_in_new_env("(char>= #\\c #\\c #\\a)"), "t");
+        assert_eq!(eval_in_new_env("(char-lessp #\\a #\\B)"), "t");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(char= #\\a \"a\")", &mut env),
+            Err(LispError::new("char=: argument must be a character, got \"a\""))
+        );
+        assert_eq!(
+            eval_str("(char-code 97)", &mut env),
+            Err(LispError::new("char-code: argument must be a character, got 97"))
+        );
+    }
+}
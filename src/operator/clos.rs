@@ -0,0 +1,809 @@
+// operator/clos.rs
+
+use crate::operator::OperatorRegistry;
+use crate::operator::lambda::Lambda;
+use crate::clos::{self, Class, GenericFunction, Instance, Method, Qualifier, SlotDefinition};
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn symbol(name: &str) -> Expr {
+    Expr::Symbol(name.to_string())
+}
+
+fn quote(expr: Expr) -> Expr {
+    Expr::List(vec![symbol("quote"), expr])
+}
+
+// 一次泛型函数调用中尚未执行的方法，按方法在泛型函数方法表中的下标记录。
+// 方法体中用符号 %next-methods 保存它，call-next-method 从这里继续
+#[derive(Clone)]
+struct EffectiveMethod {
+    name: String,
+    args: Vec<Expr>,
+    arounds: Vec<usize>,
+    befores: Vec<usize>,
+    primaries: Vec<usize>,
+    // 由最不具体到最具体
+    afters: Vec<usize>,
+}
+
+impl EffectiveMethod {
+    fn has_next(&self) -> bool {
+        !self.arounds.is_empty() || !self.primaries.is_empty()
+    }
+
+    // 主方法之后只剩下后续的主方法
+    fn rest_of_primaries(&self, rest: &[usize]) -> Self {
+        EffectiveMethod { arounds: vec![], befores: vec![], primaries: rest.to_vec(), afters: vec![], ..self.clone() }
+    }
+
+    fn encode(&self) -> Expr {
+        let indices = |list: &[usize]| Expr::List(list.iter().map(|&i| Expr::Number(i as i64)).collect());
+        Expr::List(vec![
+            symbol(&self.name),
+            Expr::List(self.args.clone()),
+            indices(&self.arounds),
+            indices(&self.befores),
+            indices(&self.primaries),
+            indices(&self.afters),
+        ])
+    }
+
+    fn decode(expr: &Expr) -> Option<Self> {
+        let indices = |expr: &Expr| match expr {
+            Expr::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Expr::Number(n) => Some(*n as usize),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>(),
+            _ => None,
+        };
+        match expr {
+            Expr::List(parts) if parts.len() == 6 => match (&parts[0], &parts[1]) {
+                (Expr::Symbol(name), Expr::List(args)) => Some(EffectiveMethod {
+                    name: name.clone(),
+                    args: args.clone(),
+                    arounds: indices(&parts[2])?,
+                    befores: indices(&parts[3])?,
+                    primaries: indices(&parts[4])?,
+                    afters: indices(&parts[5])?,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+pub struct Clos;
+
+impl Clos {
+    pub fn class_exists(name: &str, env: &Environment) -> bool {
+        clos::BUILTIN_CLASSES.contains(&name) || env.get_class(name).is_some() || env.get_structure(name).is_some()
+    }
+
+    // 函数名：符号或 (setf name)
+    fn function_name(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Symbol(name) if !expr.is_keyword() => Some(name.clone()),
+            Expr::List(parts) if parts.len() == 2 && parts[0] == symbol("setf") => match &parts[1] {
+                Expr::Symbol(name) => Some(format!("(setf {})", name)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn required_count(params: &[Expr]) -> usize {
+        params.iter().take_while(|param| !matches!(param, Expr::Symbol(s) if s.starts_with('&'))).count()
+    }
+
+    // 取得名为 name 的泛型函数，不存在时创建它，并在函数表中登记一个转发到 %call-generic 的函数
+    fn ensure_generic(name: &str, required: usize, env: &mut Environment) -> Result<Rc<RefCell<GenericFunction>>, LispError> {
+        if let Some(generic) = env.get_generic(name) {
+            let expected = generic.borrow().required;
+            if expected != required {
+                return Err(LispError::new(&format!(
+                    "{}: the generic function takes {} required arguments, but the method takes {}",
+                    name, expected, required
+                )));
+            }
+            return Ok(generic.clone());
+        }
+        let generic = Rc::new(RefCell::new(GenericFunction::new(required)));
+        env.set_generic(name.to_string(), generic.clone());
+        let dispatcher = Expr::List(vec![
+            symbol("lambda"),
+            Expr::List(vec![symbol("&rest"), symbol("args")]),
+            Expr::List(vec![symbol("%call-generic"), quote(symbol(name)), symbol("args")]),
+        ]);
+        env.set_function(name.to_string(), dispatcher);
+        Ok(generic)
+    }
+
+    // 槽描述：name 或 (name :initarg :k :initform form :accessor a :reader r :writer w ...)，
+    // 返回槽定义以及要生成的读取和写入方法名
+    fn parse_slot(spec: &Expr) -> Result<(SlotDefinition, Vec<String>, Vec<String>), LispError> {
+        let invalid = || LispError::new(&format!("defclass: invalid slot specification {}", spec));
+        let (name, options) = match spec {
+            Expr::Symbol(name) if !spec.is_keyword() => (name.clone(), &[][..]),
+            Expr::List(parts) => match parts.split_first() {
+                Some((Expr::Symbol(name), options)) if !parts[0].is_keyword() => (name.clone(), options),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        };
+        if !options.len().is_multiple_of(2) {
+            return Err(LispError::new(&format!("defclass: odd number of slot options in {}", spec)));
+        }
+        let mut slot = SlotDefinition { name, initargs: Vec::new(), initform: None };
+        let mut readers = Vec::new();
+        let mut writers = Vec::new();
+        for pair in options.chunks(2) {
+            let option = match &pair[0] {
+                Expr::Symbol(option) => option.as_str(),
+                _ => return Err(invalid()),
+            };
+            match (option, &pair[1]) {
+                (":initarg", Expr::Symbol(initarg)) => slot.initargs.push(initarg.clone()),
+                (":initform", form) => slot.initform = Some(form.clone()),
+                (":reader", Expr::Symbol(reader)) => readers.push(reader.clone()),
+                (":accessor", Expr::Symbol(accessor)) => {
+                    readers.push(accessor.clone());
+                    writers.push(format!("(setf {})", accessor));
+                }
+                (":writer", writer) => writers.push(Clos::function_name(writer).ok_or_else(invalid)?),
+                (":type", _) | (":documentation", _) | (":allocation", _) => {}
+                _ => return Err(LispError::new(&format!("defclass: invalid slot option {} {}", pair[0], pair[1]))),
+            }
+        }
+        Ok((slot, readers, writers))
+    }
+
+    // (defclass name (superclass...) (slot...) option...)
+    pub fn eval_defclass(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 3 {
+            return Err(LispError::new("defclass requires a name, a superclass list and a slot list"));
+        }
+        let name = match &args[0] {
+            Expr::Symbol(name) if !args[0].is_keyword() => name.clone(),
+            other => return Err(LispError::new(&format!("defclass: class name must be a symbol, got {}", other))),
+        };
+        if clos::BUILTIN_CLASSES.contains(&name.as_str()) {
+            return Err(LispError::new(&format!("defclass: cannot redefine the built-in class {}", name)));
+        }
+        let supers = match &args[1] {
+            Expr::List(supers) => supers
+                .iter()
+                .map(|s| match s {
+                    Expr::Symbol(s) if env.get_class(s).is_some() => Ok(s.clone()),
+                    other => Err(LispError::new(&format!("defclass: unknown superclass {}", other))),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            other => return Err(LispError::new(&format!("defclass: superclasses must be a list, got {}", other))),
+        };
+        let specs = match &args[2] {
+            Expr::List(specs) => specs,
+            other => return Err(LispError::new(&format!("defclass: slots must be a list, got {}", other))),
+        };
+        for option in &args[3..] {
+            match option {
+                Expr::List(parts) if parts.len() == 2 && parts[0] == symbol(":documentation") => {
+                    if let Expr::Str(doc) = &parts[1] {
+                        env.set_documentation(&name, "type", doc.clone());
+                    }
+                }
+                other => return Err(LispError::new(&format!("defclass: unsupported class option {}", other))),
+            }
+        }
+
+        let mut direct_slots = Vec::new();
+        let mut accessors = Vec::new();
+        for spec in specs {
+            let (slot, readers, writers) = Clos::parse_slot(spec)?;
+            if direct_slots.iter().any(|existing: &SlotDefinition| existing.name == slot.name) {
+                return Err(LispError::new(&format!("defclass: duplicate slot {}", slot.name)));
+            }
+            accessors.push((slot.name.clone(), readers, writers));
+            direct_slots.push(slot);
+        }
+
+        // 优先级列表：对用户定义的类做 C3 线性化，末尾是 standard-object 和 t
+        let mut lists = HashMap::new();
+        for s in &supers {
+            let list = &env.get_class(s).unwrap().precedence_list;
+            lists.insert(s.clone(), list[..list.len() - 2].to_vec());
+        }
+        let mut precedence_list = clos::linearize(&name, &supers, &lists).map_err(|message| LispError::new(&format!("defclass: {}", message)))?;
+        precedence_list.push("standard-object".to_string());
+        precedence_list.push("t".to_string());
+
+        // 有效槽：从最一般的类到最具体的类合并，同名槽合并初始化参数，越具体的 :initform 越优先
+        let mut slots: Vec<SlotDefinition> = Vec::new();
+        for class_name in precedence_list[1..precedence_list.len() - 2].iter().rev() {
+            let class = env.get_class(class_name).unwrap().clone();
+            Clos::merge_slots(&mut slots, &class.direct_slots);
+        }
+        Clos::merge_slots(&mut slots, &direct_slots);
+
+        env.set_class(name.clone(), Rc::new(Class { name: name.clone(), precedence_list, slots, direct_slots }));
+
+        for (slot, readers, writers) in accessors {
+            for reader in readers {
+                let body = Expr::List(vec![symbol("slot-value"), symbol("object"), quote(symbol(&slot))]);
+                let method = Method { qualifier: Qualifier::Primary, specializers: vec![name.clone()], params: vec![symbol("object")], body };
+                Clos::ensure_generic(&reader, 1, env)?.borrow_mut().add_method(method);
+            }
+            for writer in writers {
+                let place = Expr::List(vec![symbol("slot-value"), symbol("object"), quote(symbol(&slot))]);
+                let body = Expr::List(vec![symbol("setf"), place, symbol("value")]);
+                let method = Method {
+                    qualifier: Qualifier::Primary,
+                    specializers: vec!["t".to_string(), name.clone()],
+                    params: vec![symbol("value"), symbol("object")],
+                    body,
+                };
+                Clos::ensure_generic(&writer, 2, env)?.borrow_mut().add_method(method);
+            }
+        }
+        Ok(symbol(&name))
+    }
+
+    fn merge_slots(slots: &mut Vec<SlotDefinition>, direct_slots: &[SlotDefinition]) {
+        for slot in direct_slots {
+            match slots.iter_mut().find(|existing| existing.name == slot.name) {
+                Some(existing) => {
+                    for initarg in &slot.initargs {
+                        if !existing.initargs.contains(initarg) {
+                            existing.initargs.push(initarg.clone());
+                        }
+                    }
+                    if slot.initform.is_some() {
+                        existing.initform = slot.initform.clone();
+                    }
+                }
+                None => slots.push(slot.clone()),
+            }
+        }
+    }
+
+    // (make-instance 'class :initarg value...)：初始化参数优先，其次是 :initform，否则槽保持未绑定
+    pub fn eval_make_instance(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        let class = match values.first() {
+            Some(Expr::Symbol(name)) => env
+                .get_class(name)
+                .cloned()
+                .ok_or_else(|| LispError::new(&format!("make-instance: unknown class {}", name)))?,
+            Some(other) => return Err(LispError::new(&format!("make-instance: class name must be a symbol, got {}", other))),
+            None => return Err(LispError::new("make-instance requires a class name")),
+        };
+        let initargs = &values[1..];
+        if !initargs.len().is_multiple_of(2) {
+            return Err(LispError::new("make-instance: odd number of initialization arguments"));
+        }
+        for pair in initargs.chunks(2) {
+            let known = matches!(&pair[0], Expr::Symbol(key) if class.slots.iter().any(|slot| slot.initargs.contains(key)));
+            if !known {
+                return Err(LispError::new(&format!("make-instance: unknown initarg {} for class {}", pair[0], class.name)));
+            }
+        }
+        let mut slots = Vec::with_capacity(class.slots.len());
+        for slot in &class.slots {
+            // 同一个初始化参数出现多次时，最左边的生效
+            let supplied = initargs
+                .chunks(2)
+                .find(|pair| matches!(&pair[0], Expr::Symbol(key) if slot.initargs.contains(key)))
+                .map(|pair| pair[1].clone());
+            let value = match (supplied, &slot.initform) {
+                (Some(value), _) => Some(value),
+                (None, Some(initform)) => Some(Evaluator::eval(initform, env)?),
+                (None, None) => None,
+            };
+            slots.push(value);
+        }
+        Ok(Expr::Instance(Rc::new(RefCell::new(Instance::new(class, slots)))))
+    }
+
+    // (defgeneric name lambda-list option...)，选项可以是 (:documentation "...") 或 (:method ...)
+    pub fn eval_defgeneric(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new("defgeneric requires a name and a lambda list"));
+        }
+        let name = Clos::function_name(&args[0])
+            .ok_or_else(|| LispError::new(&format!("defgeneric: invalid function name {}", args[0])))?;
+        let required = match &args[1] {
+            Expr::List(params) => Clos::required_count(params),
+            other => return Err(LispError::new(&format!("defgeneric: lambda list must be a list, got {}", other))),
+        };
+        // 必需参数个数改变时丢弃原有的方法
+        if env.get_generic(&name).is_some_and(|generic| generic.borrow().required != required) {
+            let generic = Rc::new(RefCell::new(GenericFunction::new(required)));
+            env.set_generic(name.clone(), generic);
+        }
+        Clos::ensure_generic(&name, required, env)?;
+        for option in &args[2..] {
+            match option {
+                Expr::List(parts) if parts.len() == 2 && parts[0] == symbol(":documentation") => {
+                    if let Expr::Str(doc) = &parts[1] {
+                        env.set_documentation(&name, "function", doc.clone());
+                    }
+                }
+                Expr::List(parts) if parts.first() == Some(&symbol(":method")) => {
+                    let mut method_args = vec![args[0].clone()];
+                    method_args.extend(parts[1..].iter().cloned());
+                    Clos::eval_defmethod(&method_args, env)?;
+                }
+                other => return Err(LispError::new(&format!("defgeneric: unsupported option {}", other))),
+            }
+        }
+        Ok(symbol(&name))
+    }
+
+    // (defmethod name [qualifier] specialized-lambda-list body...)，必需形参可以写成 (var class)
+    pub fn eval_defmethod(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let name = match args.first() {
+            Some(name) => Clos::function_name(name).ok_or_else(|| LispError::new(&format!("defmethod: invalid function name {}", name)))?,
+            None => return Err(LispError::new("defmethod requires a name, a lambda list and a body")),
+        };
+        let mut rest = &args[1..];
+        let mut qualifier = Qualifier::Primary;
+        if let Some(Expr::Symbol(keyword)) = rest.first().filter(|first| first.is_keyword()) {
+            qualifier = Qualifier::from_keyword(keyword)
+                .ok_or_else(|| LispError::new(&format!("defmethod: unsupported method qualifier {}", keyword)))?;
+            rest = &rest[1..];
+        }
+        let specialized = match rest.first() {
+            Some(Expr::List(params)) => params,
+            _ => return Err(LispError::new(&format!("defmethod: {} requires a lambda list", name))),
+        };
+        let required = Clos::required_count(specialized);
+        let mut specializers = Vec::with_capacity(required);
+        let mut params = Vec::with_capacity(specialized.len());
+        for (i, param) in specialized.iter().enumerate() {
+            if i >= required {
+                params.push(param.clone());
+                continue;
+            }
+            match param {
+                Expr::Symbol(_) => {
+                    specializers.push("t".to_string());
+                    params.push(param.clone());
+                }
+                Expr::List(parts) if parts.len() == 2 => match (&parts[0], &parts[1]) {
+                    (Expr::Symbol(_), Expr::Symbol(class)) => {
+                        if !Clos::class_exists(class, env) {
+                            return Err(LispError::new(&format!("defmethod: unknown class {}", class)));
+                        }
+                        specializers.push(class.clone());
+                        params.push(parts[0].clone());
+                    }
+                    _ => return Err(LispError::new(&format!("defmethod: invalid specialized parameter {}", param))),
+                },
+                _ => return Err(LispError::new(&format!("defmethod: invalid specialized parameter {}", param))),
+            }
+        }
+        let (_, body) = Lambda::parse_body(&rest[1..]);
+        let generic = Clos::ensure_generic(&name, required, env)?;
+        generic.borrow_mut().add_method(Method { qualifier, specializers, params, body });
+        Ok(symbol(&name))
+    }
+
+    // (%call-generic 'name args)：按全部必需实参的类选出适用的方法，并按标准方法组合调用
+    pub fn eval_call_generic(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("%call-generic expects 2 arguments"));
+        }
+        let name = match Evaluator::eval(&args[0], env)? {
+            Expr::Symbol(name) => name,
+            other => return Err(LispError::new(&format!("%call-generic: invalid function name {}", other))),
+        };
+        let values = match Evaluator::eval(&args[1], env)? {
+            Expr::List(values) => values,
+            other => return Err(LispError::new(&format!("%call-generic: arguments must be a list, got {}", other))),
+        };
+        let generic = env
+            .get_generic(&name)
+            .cloned()
+            .ok_or_else(|| LispError::new(&format!("{} is not a generic function", name)))?;
+        let (required, methods) = {
+            let generic = generic.borrow();
+            (generic.required, generic.methods.clone())
+        };
+        if values.len() < required {
+            return Err(LispError::new(&format!("{}: expected at least {} arguments, got {}", name, required, values.len())));
+        }
+
+        // 每个适用方法的排序键：各个特化类在对应实参的类优先级列表中的位置，按字典序越小越具体
+        let lists: Vec<Vec<String>> = values[..required].iter().map(clos::precedence_list).collect();
+        let mut applicable: Vec<(Vec<usize>, usize, Qualifier)> = methods
+            .iter()
+            .enumerate()
+            .filter_map(|(index, method)| {
+                let key = method
+                    .specializers
+                    .iter()
+                    .zip(&lists)
+                    .map(|(specializer, list)| list.iter().position(|class| class == specializer))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((key, index, method.qualifier))
+            })
+            .collect();
+        applicable.sort_by(|a, b| a.0.cmp(&b.0));
+        let pick = |qualifier: Qualifier| -> Vec<usize> {
+            applicable.iter().filter(|m| m.2 == qualifier).map(|m| m.1).collect()
+        };
+        let mut afters = pick(Qualifier::After);
+        afters.reverse();
+        let effective = EffectiveMethod {
+            name,
+            args: values,
+            arounds: pick(Qualifier::Around),
+            befores: pick(Qualifier::Before),
+            primaries: pick(Qualifier::Primary),
+            afters,
+        };
+        Clos::run(effective, env)
+    }
+
+    // 标准方法组合：先调用最具体的 :around 方法；没有时依次调用全部 :before 方法、最具体的主方法，
+    // 再按相反顺序调用 :after 方法，结果是主方法的值
+    fn run(effective: EffectiveMethod, env: &mut Environment) -> Result<Expr, LispError> {
+        if let Some((&first, rest)) = effective.arounds.split_first() {
+            let next = EffectiveMethod { arounds: rest.to_vec(), ..effective.clone() };
+            return Clos::call_method(first, next, env);
+        }
+        let terminal = effective.rest_of_primaries(&[]);
+        for &index in &effective.befores {
+            Clos::call_method(index, terminal.clone(), env)?;
+        }
+        let (&first, rest) = effective.primaries.split_first().ok_or_else(|| {
+            let args: Vec<String> = effective.args.iter().map(|arg| arg.to_string()).collect();
+            LispError::new(&format!("No applicable method for {} with arguments ({})", effective.name, args.join(" ")))
+        })?;
+        let depth = env.form_depth() + 1;
+        let result = Clos::call_method(first, effective.rest_of_primaries(rest), env)?;
+        // :after 方法会清掉多值，先取出主方法的多值，调用完后再登记为本形式的值
+        let values = env.take_values(depth);
+        for &index in &effective.afters {
+            Clos::call_method(index, terminal.clone(), env)?;
+        }
+        Ok(match values {
+            Some(values) => env.set_values(values),
+            None => result,
+        })
+    }
+
+    fn call_method(index: usize, next: EffectiveMethod, env: &mut Environment) -> Result<Expr, LispError> {
+        let method = env
+            .get_generic(&next.name)
+            .and_then(|generic| generic.borrow().methods.get(index).cloned())
+            .ok_or_else(|| LispError::new(&format!("{}: method was removed during the call", next.name)))?;
+        let mut method_env = env.clone();
+        let args = next.args.clone();
+        method_env.set_symbol("%next-methods".to_string(), next.encode());
+        Lambda::apply(&next.name, &method.params, &method.body, args, &mut method_env)
+    }
+
+    fn next_methods(name: &str, env: &Environment) -> Result<EffectiveMethod, LispError> {
+        env.get_symbol("%next-methods")
+            .and_then(EffectiveMethod::decode)
+            .ok_or_else(|| LispError::new(&format!("{} can only be used inside a method", name)))
+    }
+
+    // (call-next-method [args...])：不带实参时沿用当前方法收到的实参
+    pub fn eval_call_next_method(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let mut next = Clos::next_methods("call-next-method", env)?;
+        if !next.has_next() {
+            return Err(LispError::new(&format!("call-next-method: no next method for {}", next.name)));
+        }
+        if !args.is_empty() {
+            next.args = args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        }
+        Clos::run(next, env)
+    }
+
+    pub fn eval_next_method_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if !args.is_empty() {
+            return Err(LispError::new("next-method-p expects no arguments"));
+        }
+        Ok(Expr::from_bool(Clos::next_methods("next-method-p", env)?.has_next()))
+    }
+
+    fn slot_name(caller: &str, value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Symbol(name) => Ok(name.clone()),
+            other => Err(LispError::new(&format!("{}: slot name must be a symbol, got {}", caller, other))),
+        }
+    }
+
+    // (slot-value object 'slot)：也可以读取结构实例的槽
+    pub fn eval_slot_value(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("slot-value expects 2 arguments"));
+        }
+        let object = Evaluator::eval(&args[0], env)?;
+        let slot = Clos::slot_name("slot-value", &Evaluator::eval(&args[1], env)?)?;
+        match &object {
+            Expr::Instance(instance) => match instance.borrow().slot(&slot) {
+                Some(Some(value)) => Ok(value.clone()),
+                Some(None) => Err(LispError::new(&format!("slot-value: the slot {} of {} is unbound", slot, object))),
+                None => Err(LispError::new(&format!("slot-value: {} has no slot named {}", object, slot))),
+            },
+            Expr::Struct(instance) => {
+                let instance = instance.borrow();
+                match instance.structure().slots.iter().position(|s| s.name == slot) {
+                    Some(index) => Ok(instance.get(index).clone()),
+                    None => Err(LispError::new(&format!("slot-value: {} has no slot named {}", object, slot))),
+                }
+            }
+            other => Err(LispError::new(&format!("slot-value: {} is not an object with slots", other))),
+        }
+    }
+
+    // (setf (slot-value object 'slot) value)
+    pub fn setf_slot_value(args: &[Expr], value: Expr, _env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("(setf slot-value) expects 2 arguments"));
+        }
+        let slot = Clos::slot_name("(setf slot-value)", &args[1])?;
+        let found = match &args[0] {
+            Expr::Instance(instance) => instance.borrow_mut().set_slot(&slot, value.clone()),
+            Expr::Struct(instance) => {
+                let mut instance = instance.borrow_mut();
+                match instance.structure().slots.iter().position(|s| s.name == slot) {
+                    Some(index) => {
+                        instance.set(index, value.clone());
+                        true
+                    }
+                    None => false,
+                }
+            }
+            other => return Err(LispError::new(&format!("(setf slot-value): {} is not an object with slots", other))),
+        };
+        if !found {
+            return Err(LispError::new(&format!("(setf slot-value): {} has no slot named {}", args[0], slot)));
+        }
+        Ok(value)
+    }
+
+    pub fn eval_slot_boundp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("slot-boundp expects 2 arguments"));
+        }
+        let object = Evaluator::eval(&args[0], env)?;
+        let slot = Clos::slot_name("slot-boundp", &Evaluator::eval(&args[1], env)?)?;
+        match &object {
+            Expr::Instance(instance) => match instance.borrow().slot(&slot) {
+                Some(value) => Ok(Expr::from_bool(value.is_some())),
+                None => Err(LispError::new(&format!("slot-boundp: {} has no slot named {}", object, slot))),
+            },
+            other => Err(LispError::new(&format!("slot-boundp: {} is not an instance", other))),
+        }
+    }
+
+    // (class-of object)：返回类名，即类优先级列表的第一项
+    pub fn eval_class_of(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("class-of expects 1 argument"));
+        }
+        let object = Evaluator::eval(&args[0], env)?;
+        Ok(symbol(&clos::precedence_list(&object)[0]))
+    }
+}
+
+pub fn register_clos_operators() {
+    OperatorRegistry::register("defclass", Clos::eval_defclass);
+    OperatorRegistry::register("make-instance", Clos::eval_make_instance);
+    OperatorRegistry::register("defgeneric", Clos::eval_defgeneric);
+    OperatorRegistry::register("defmethod", Clos::eval_defmethod);
+    OperatorRegistry::register("%call-generic", Clos::eval_call_generic);
+    OperatorRegistry::register("call-next-method", Clos::eval_call_next_method);
+    OperatorRegistry::register("next-method-p", Clos::eval_next_method_p);
+    OperatorRegistry::register("slot-value", Clos::eval_slot_value);
+    OperatorRegistry::register("slot-boundp", Clos::eval_slot_boundp);
+    OperatorRegistry::register("class-of", Clos::eval_class_of);
+    OperatorRegistry::register_setf("slot-value", Clos::setf_slot_value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    fn define_shapes(env: &mut Environment) {
+        for form in [
+            "(defclass shape () ((name :initarg :name :initform \"shape\" :accessor shape-name)))",
+            "(defclass circle (shape) ((radius :initarg :radius :initform 1 :reader radius)))",
+            "(defclass square (shape) ((side :initarg :side :accessor side)))",
+            "(defgeneric area (shape))",
+            "(defmethod area ((c circle)) (* 3 (radius c) (radius c)))",
+            "(defmethod area ((s square)) (* (side s) (side s)))",
+        ] {
+            eval_str(form, env).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_classes_and_accessors() {
+        let mut env = Environment::initialize();
+        define_shapes(&mut env);
+        eval_str("(setf c (make-instance 'circle :radius 2))", &mut env).unwrap();
+        assert_eq!(eval_to_string("c", &mut env), "#<circle>");
+        assert_eq!(eval_str("(radius c)", &mut env), Ok(Expr::Number(2)));
+        // 继承来的槽和 :initform
+        assert_eq!(eval_str("(shape-name c)", &mut env), Ok(Expr::Str("shape".to_string())));
+        assert_eq!(eval_str("(setf (shape-name c) \"wheel\")", &mut env), Ok(Expr::Str("wheel".to_string())));
+        assert_eq!(eval_str("(slot-value c 'name)", &mut env), Ok(Expr::Str("wheel".to_string())));
+        eval_str("(setf (slot-value c 'radius) 5)", &mut env).unwrap();
+        assert_eq!(eval_str("(radius c)", &mut env), Ok(Expr::Number(5)));
+        assert_eq!(eval_to_string("(class-of c)", &mut env), "circle");
+        assert_eq!(eval_to_string("(class-of \"s\")", &mut env), "string");
+
+        eval_str("(setf s (make-instance 'square))", &mut env).unwrap();
+        assert_eq!(eval_str("(slot-boundp s 'side)", &mut env), Ok(Expr::nil()));
+        assert_eq!(
+            eval_str("(side s)", &mut env),
+            Err(LispError::new("slot-value: the slot side of #<square> is unbound"))
+        );
+        assert_eq!(
+            eval_str("(make-instance 'circle :side 1)", &mut env),
+            Err(LispError::new("make-instance: unknown initarg :side for class circle"))
+        );
+    }
+
+    #[test]
+    fn test_dispatch_on_classes() {
+        let mut env = Environment::initialize();
+        define_shapes(&mut env);
+        assert_eq!(eval_str("(area (make-instance 'circle :radius 2))", &mut env), Ok(Expr::Number(12)));
+        assert_eq!(eval_str("(area (make-instance 'square :side 3))", &mut env), Ok(Expr::Number(9)));
+        assert_eq!(
+            eval_str("(area 5)", &mut env),
+            Err(LispError::new("No applicable method for area with arguments (5)"))
+        );
+    }
+
+    #[test]
+    fn test_dispatch_on_builtin_classes_and_all_arguments() {
+        let mut env = Environment::initialize();
+        for form in [
+            "(defmethod describe-value ((x integer)) \"integer\")",
+            "(defmethod describe-value ((x number)) \"number\")",
+            "(defmethod describe-value ((x string)) \"string\")",
+            "(defmethod describe-value ((x list)) \"list\")",
+            "(defmethod describe-value ((x null)) \"empty\")",
+            "(defmethod describe-value (x) \"something\")",
+            "(defmethod combine ((a integer) (b integer)) 'integers)",
+            "(defmethod combine ((a integer) (b string)) 'integer-string)",
+            "(defmethod combine ((a string) b) 'string-anything)",
+            "(defmethod combine (a b) 'default)",
+        ] {
+            eval_str(form, &mut env).unwrap();
+        }
+        assert_eq!(eval_to_string("(describe-value 1)", &mut env), "\"integer\"");
+        assert_eq!(eval_to_string("(describe-value 1.5)", &mut env), "\"number\"");
+        assert_eq!(eval_to_string("(describe-value \"a\")", &mut env), "\"string\"");
+        assert_eq!(eval_to_string("(describe-value '(1))", &mut env), "\"list\"");
+        assert_eq!(eval_to_string("(describe-value nil)", &mut env), "\"empty\"");
+        assert_eq!(eval_to_string("(describe-value #\\a)", &mut env), "\"something\"");
+        assert_eq!(eval_to_string("(combine 1 2)", &mut env), "integers");
+        assert_eq!(eval_to_string("(combine 1 \"x\")", &mut env), "integer-string");
+        assert_eq!(eval_to_string("(combine \"x\" 1)", &mut env), "string-anything");
+        assert_eq!(eval_to_string("(combine 1 'x)", &mut env), "default");
+
+        // 结构类型也可以作为特化类
+        eval_str("(defstruct point x)", &mut env).unwrap();
+        eval_str("(defmethod describe-value ((p point)) \"point\")", &mut env).unwrap();
+        assert_eq!(eval_to_string("(describe-value (make-point :x 1))", &mut env), "\"point\"");
+    }
+
+    #[test]
+    fn test_call_next_method_and_method_combination() {
+        let mut env = Environment::initialize();
+        define_shapes(&mut env);
+        eval_str("(setf log (make-array 0 :fill-pointer 0))", &mut env).unwrap();
+        for form in [
+            "(defgeneric describe-shape (shape))",
+            "(defmethod describe-shape ((s shape)) (vector-push-extend 'shape log) 'shape)",
+            "(defmethod describe-shape ((c circle)) (vector-push-extend 'circle log) (list-of (call-next-method) (next-method-p)))",
+            "(defmethod describe-shape :before ((s shape)) (vector-push-extend 'before-shape log))",
+            "(defmethod describe-shape :before ((c circle)) (vector-push-extend 'before-circle log))",
+            "(defmethod describe-shape :after ((s shape)) (vector-push-extend 'after-shape log))",
+            "(defmethod describe-shape :after ((c circle)) (vector-push-extend 'after-circle log))",
+            "(defmethod describe-shape :around ((c circle)) (vector-push-extend 'around log) (cons 'wrapped (call-next-method)))",
+            "(defun list-of (a b) (cons a (cons b nil)))",
+        ] {
+            eval_str(form, &mut env).unwrap();
+        }
+        assert_eq!(
+            eval_to_string("(describe-shape (make-instance 'circle))", &mut env),
+            "(wrapped shape t)"
+        );
+        assert_eq!(
+            eval_to_string("log", &mut env),
+            "#(around before-circle before-shape circle shape after-shape after-circle)"
+        );
+
+        // 调用下一个方法时可以换一组实参
+        eval_str("(defmethod scale ((n number)) (* n 10))", &mut env).unwrap();
+        eval_str("(defmethod scale ((n integer)) (call-next-method (+ n 1)))", &mut env).unwrap();
+        assert_eq!(eval_str("(scale 1)", &mut env), Ok(Expr::Number(20)));
+
+        eval_str("(defmethod lonely ((n integer)) (call-next-method))", &mut env).unwrap();
+        assert_eq!(eval_str("(lonely 1)", &mut env), Err(LispError::new("call-next-method: no next method for lonely")));
+        assert_eq!(
+            eval_str("(call-next-method)", &mut env),
+            Err(LispError::new("call-next-method can only be used inside a method"))
+        );
+    }
+
+    #[test]
+    fn test_methods_return_multiple_values() {
+        let mut env = Environment::initialize();
+        eval_str("(defmethod mv ((x integer)) (values x 2))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(multiple-value-list (mv 1))", &mut env), "(1 2)");
+
+        // :after 方法的值被丢弃，主方法的多值原样传出
+        eval_str("(defmethod mv :after ((x number)) (values 'after 'ignored))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(multiple-value-list (mv 1))", &mut env), "(1 2)");
+
+        // 多值经过 call-next-method 和 :around 方法传出
+        eval_str("(defmethod mv ((x number)) (values x 2))", &mut env).unwrap();
+        eval_str("(defmethod mv ((x integer)) (call-next-method))", &mut env).unwrap();
+        eval_str("(defmethod mv :around ((x integer)) (call-next-method))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(multiple-value-list (mv 3))", &mut env), "(3 2)");
+        assert_eq!(eval_str("(mv 3)", &mut env), Ok(Expr::Number(3)));
+    }
+
+    #[test]
+    fn test_class_precedence_and_errors() {
+        let mut env = Environment::initialize();
+        for form in [
+            "(defclass a () ((x :initform 'a :reader x)))",
+            "(defclass b (a) ((x :initform 'b)))",
+            "(defclass c (a) ())",
+            "(defclass d (b c) ())",
+            "(defmethod who ((obj a)) 'a)",
+            "(defmethod who ((obj c)) 'c)",
+        ] {
+            eval_str(form, &mut env).unwrap();
+        }
+        // d 的优先级列表是 (d b c a standard-object t)：c 比 a 具体，b 的 :initform 覆盖 a 的
+        assert_eq!(eval_to_string("(who (make-instance 'd))", &mut env), "c");
+        assert_eq!(eval_to_string("(x (make-instance 'd))", &mut env), "b");
+
+        assert_eq!(
+            eval_str("(defclass e (missing) ())", &mut env),
+            Err(LispError::new("defclass: unknown superclass missing"))
+        );
+        assert_eq!(
+            eval_str("(defclass integer () ())", &mut env),
+            Err(LispError::new("defclass: cannot redefine the built-in class integer"))
+        );
+        assert_eq!(
+            eval_str("(defclass standard-object () ())", &mut env),
+            Err(LispError::new("defclass: cannot redefine the built-in class standard-object"))
+        );
+        assert_eq!(eval_str("(typep 1 'integer)", &mut env), Ok(Expr::t()));
+        assert_eq!(
+            eval_str("(defmethod who ((obj missing)) 1)", &mut env),
+            Err(LispError::new("defmethod: unknown class missing"))
+        );
+        assert_eq!(
+            eval_str("(defmethod who ((obj a) extra) 1)", &mut env),
+            Err(LispError::new("who: the generic function takes 1 required arguments, but the method takes 2"))
+        );
+        assert_eq!(
+            eval_str("(make-instance 'missing)", &mut env),
+            Err(LispError::new("make-instance: unknown class missing"))
+        );
+    }
+}
@@ -4,139 +4,209 @@ use crate::environment::Environment;
 use crate::exception::LispError;
 use crate::expression::Expr;
 use crate::evaluator::Evaluator;
+use crate::operator::arithmetic::Arithmetic;
+use std::cmp::Ordering;
+use std::rc::Rc;
 
 pub struct Comparison;
 
-macro_rules! match_comparison {
-    ($left:expr, $right:expr, {
-        $(
-            ($lpat:pat, $rpat:pat) => $cond:expr,
-        )*
-    }, $default:expr) => {
-        match ($left, $right) {
-            $(
-                ($lpat, $rpat) => {
-                    if $cond {
-                        Ok(Expr::Symbol("t".to_string()))
-                    } else {
-                        Ok(Expr::List(vec![]))
-                    }
-                },
-            )*
-            _ => $default,
-        }
-    };
-}
-
+// 相等性谓词从严到宽依次是 eq、eql、equal、equalp，前者成立时后者一定成立：
+// - eq 比较同一性。符号按名字、定长整数和字符按值比较，nil 与 nil 相同，可变对象比较是否是同一个对象。
+//   浮点数、大整数、有理数、复数、字符串和非空列表在求值时都会被复制，没有可以比较的同一性，因此互不 eq。
+// - eql 在 eq 的基础上，把类型相同、值相同的数值视为相同，所以 (eql 1 1.0) 为假，(eql 0.0 -0.0) 也为假。
+// - equal 比较结构：字符串逐字符比较（区分大小写），列表和点对逐元素用 equal 比较。
+// - equalp 最宽松：数值按 = 比较而忽略类型，字符和字符串不区分大小写，列表和数组逐元素用 equalp 比较，哈希表逐个条目比较，结构实例逐个槽比较。
 impl Comparison {
-    pub fn eval_greater(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 2 {
-            return Err(LispError::new("`>` expects exactly two arguments"));
+    pub fn is_eq(left: &Expr, right: &Expr) -> bool {
+        match (left, right) {
+            (Expr::Symbol(l), Expr::Symbol(r)) => l == r,
+            (Expr::Number(l), Expr::Number(r)) => l == r,
+            (Expr::Char(l), Expr::Char(r)) => l == r,
+            (Expr::RandomState(l), Expr::RandomState(r)) => Rc::ptr_eq(l, r),
+            (Expr::Stream(l), Expr::Stream(r)) => Rc::ptr_eq(l, r),
+            (Expr::Array(l), Expr::Array(r)) => Rc::ptr_eq(l, r),
+            (Expr::HashTable(l), Expr::HashTable(r)) => Rc::ptr_eq(l, r),
+            (Expr::Struct(l), Expr::Struct(r)) => Rc::ptr_eq(l, r),
+            (Expr::Instance(l), Expr::Instance(r)) => Rc::ptr_eq(l, r),
+            // 空列表和符号 nil 是同一个对象
+            (Expr::List(_) | Expr::Symbol(_), Expr::List(_) | Expr::Symbol(_)) => !left.is_truthy() && !right.is_truthy(),
+            _ => false,
         }
+    }
 
-        let left = Evaluator::eval(&args[0], env)?;
-        let right = Evaluator::eval(&args[1], env)?;
+    pub fn is_eql(left: &Expr, right: &Expr) -> bool {
+        match (left, right) {
+            (Expr::BigInt(l), Expr::BigInt(r)) => l == r,
+            (Expr::Ratio(l), Expr::Ratio(r)) => l == r,
+            (Expr::Float(l), Expr::Float(r)) => l.to_bits() == r.to_bits(),
+            (Expr::Complex(lre, lim), Expr::Complex(rre, rim)) => Comparison::is_eql(lre, rre) && Comparison::is_eql(lim, rim),
+            _ => Comparison::is_eq(left, right),
+        }
+    }
 
-        match_comparison!(left, right, {
-            (Expr::Number(l), Expr::Number(r)) => l > r,
-            (Expr::Float(l), Expr::Float(r)) => l > r,
-            (Expr::Number(l), Expr::Float(r)) => (l as f64) > r,
-            (Expr::Float(l), Expr::Number(r)) => l > (r as f64),
-        }, Err(LispError::new("`>` arguments must be numbers")))
+    pub fn is_equal(left: &Expr, right: &Expr) -> bool {
+        match (left, right) {
+            (Expr::Str(l), Expr::Str(r)) => l == r,
+            (Expr::List(l), Expr::List(r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| Comparison::is_equal(l, r))
+            }
+            (Expr::DottedPair(lcar, lcdr), Expr::DottedPair(rcar, rcdr)) => {
+                Comparison::is_equal(lcar, rcar) && Comparison::is_equal(lcdr, rcdr)
+            }
+            _ => Comparison::is_eql(left, right),
+        }
     }
 
-    pub fn eval_greater_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 2 {
-            return Err(LispError::new("`>=` expects exactly two arguments"));
+    pub fn is_equalp(left: &Expr, right: &Expr) -> bool {
+        if Comparison::is_number(left) && Comparison::is_number(right) {
+            return Comparison::numbers_equal(left, right);
+        }
+        match (left, right) {
+            (Expr::Char(l), Expr::Char(r)) => l.to_lowercase().eq(r.to_lowercase()),
+            (Expr::Str(l), Expr::Str(r)) => l.to_lowercase() == r.to_lowercase(),
+            (Expr::List(l), Expr::List(r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| Comparison::is_equalp(l, r))
+            }
+            (Expr::DottedPair(lcar, lcdr), Expr::DottedPair(rcar, rcdr)) => {
+                Comparison::is_equalp(lcar, rcar) && Comparison::is_equalp(lcdr, rcdr)
+            }
+            // 同一个对象总是 equalp，这样包含自身的哈希表也不会无限递归
+            (Expr::Array(l), Expr::Array(r)) if Rc::ptr_eq(l, r) => true,
+            (Expr::HashTable(l), Expr::HashTable(r)) if Rc::ptr_eq(l, r) => true,
+            // 数组只有 equalp 才比较内容：维度相同且元素逐个 equalp；正在被修改的对象读不到内容，只按同一性比较
+            (Expr::Array(l), Expr::Array(r)) => match (l.try_borrow(), r.try_borrow()) {
+                (Ok(l), Ok(r)) => {
+                    l.dimensions() == r.dimensions()
+                        && l.active_elements().len() == r.active_elements().len()
+                        && l.active_elements().iter().zip(r.active_elements()).all(|(l, r)| Comparison::is_equalp(l, r))
+                }
+                _ => false,
+            },
+            // 哈希表在 equalp 下比较内容：比较方式和条目数相同，每个键在另一个表中都有 equalp 的值
+            (Expr::HashTable(l), Expr::HashTable(r)) => match (l.try_borrow(), r.try_borrow()) {
+                (Ok(l), Ok(r)) => {
+                    l.test() == r.test()
+                        && l.len() == r.len()
+                        && l.entries().all(|(key, value)| r.get(key).is_some_and(|other| Comparison::is_equalp(value, other)))
+                }
+                _ => false,
+            },
+            // 结构实例在 equalp 下比较类型和每个槽的值
+            (Expr::Struct(l), Expr::Struct(r)) => {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.structure().name == r.structure().name
+                    && l.values().len() == r.values().len()
+                    && l.values().iter().zip(r.values()).all(|(l, r)| Comparison::is_equalp(l, r))
+            }
+            _ => Comparison::is_equal(left, right),
         }
+    }
 
-        let left = Evaluator::eval(&args[0], env)?;
-        let right = Evaluator::eval(&args[1], env)?;
+    fn is_number(value: &Expr) -> bool {
+        matches!(value, Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_) | Expr::Complex(_, _))
+    }
 
-        match_comparison!(left, right, {
-            (Expr::Number(l), Expr::Number(r)) => l >= r,
-            (Expr::Float(l), Expr::Float(r)) => l >= r,
-            (Expr::Number(l), Expr::Float(r)) => (l as f64) >= r,
-            (Expr::Float(l), Expr::Number(r)) => l >= (r as f64),
-        }, Err(LispError::new("`>=` arguments must be numbers")))
+    fn is_real(value: &Expr) -> bool {
+        matches!(value, Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_))
     }
 
-    pub fn eval_less(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 2 {
-            return Err(LispError::new("`<` expects exactly two arguments"));
+    // 数值相等：忽略类型，复数比较实部和虚部
+    fn numbers_equal(left: &Expr, right: &Expr) -> bool {
+        match (Arithmetic::complex_parts(left), Arithmetic::complex_parts(right)) {
+            (Some((lre, lim)), Some((rre, rim))) => {
+                Arithmetic::compare(&lre, &rre) == Some(Ordering::Equal)
+                    && Arithmetic::compare(&lim, &rim) == Some(Ordering::Equal)
+            }
+            _ => false,
         }
+    }
 
-        let left = Evaluator::eval(&args[0], env)?;
-        let right = Evaluator::eval(&args[1], env)?;
+    // 先求值全部参数，再检查类型：CL 的比较操作符都是普通函数
+    fn eval_numbers(name: &str, args: &[Expr], env: &mut Environment, is_valid: fn(&Expr) -> bool, kind: &str) -> Result<Vec<Expr>, LispError> {
+        if args.is_empty() {
+            return Err(LispError::new(&format!("`{}` expects at least one argument", name)));
+        }
+        let values = args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        if values.iter().all(is_valid) {
+            Ok(values)
+        } else {
+            Err(LispError::new(&format!("`{}` arguments must be {}", name, kind)))
+        }
+    }
 
-        match_comparison!(left, right, {
-            (Expr::Number(l), Expr::Number(r)) => l < r,
-            (Expr::Float(l), Expr::Float(r)) => l < r,
-            (Expr::Number(l), Expr::Float(r)) => (l as f64) < r,
-            (Expr::Float(l), Expr::Number(r)) => l < (r as f64),
-        }, Err(LispError::new("`<` arguments must be numbers")))
+    // 相邻的每一对参数都满足 holds 时为真
+    fn eval_ordering(name: &str, args: &[Expr], env: &mut Environment, holds: fn(Ordering) -> bool) -> Result<Expr, LispError> {
+        let values = Comparison::eval_numbers(name, args, env, Comparison::is_real, "numbers")?;
+        Ok(Expr::from_bool(values.windows(2).all(|pair| {
+            // NaN 与任何数都无法比较，比较结果总是假
+            Arithmetic::compare(&pair[0], &pair[1]).is_some_and(holds)
+        })))
+    }
+
+    pub fn eval_greater(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Comparison::eval_ordering(">", args, env, |ordering| ordering == Ordering::Greater)
+    }
+
+    pub fn eval_greater_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Comparison::eval_ordering(">=", args, env, |ordering| ordering != Ordering::Less)
+    }
+
+    pub fn eval_less(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Comparison::eval_ordering("<", args, env, |ordering| ordering == Ordering::Less)
     }
 
     pub fn eval_less_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 2 {
-            return Err(LispError::new("`<=` expects exactly two arguments"));
-        }
+        Comparison::eval_ordering("<=", args, env, |ordering| ordering != Ordering::Greater)
+    }
 
-        let left = Evaluator::eval(&args[0], env)?;
-        let right = Evaluator::eval(&args[1], env)?;
+    // (= a b ...)：所有参数的数值都相等，整数、有理数、浮点数和复数之间可以混合比较
+    pub fn eval_numeric_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Comparison::eval_numbers("=", args, env, Comparison::is_number, "numbers")?;
+        Ok(Expr::from_bool(values.windows(2).all(|pair| Comparison::numbers_equal(&pair[0], &pair[1]))))
+    }
 
-        match_comparison!(left, right, {
-            (Expr::Number(l), Expr::Number(r)) => l <= r,
-            (Expr::Float(l), Expr::Float(r)) => l <= r,
-            (Expr::Number(l), Expr::Float(r)) => (l as f64) <= r,
-            (Expr::Float(l), Expr::Number(r)) => l <= (r as f64),
-        }, Err(LispError::new("`<=` arguments must be numbers")))
+    // (/= a b ...)：所有参数两两不相等
+    pub fn eval_numeric_not_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Comparison::eval_numbers("/=", args, env, Comparison::is_number, "numbers")?;
+        let all_distinct = values.iter().enumerate().all(|(i, left)| {
+            values[i + 1..].iter().all(|right| !Comparison::numbers_equal(left, right))
+        });
+        Ok(Expr::from_bool(all_distinct))
     }
 
-    pub fn eval_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+    fn eval_pair(name: &str, args: &[Expr], env: &mut Environment) -> Result<(Expr, Expr), LispError> {
         if args.len() != 2 {
-            return Err(LispError::new("`eq` expects exactly two arguments"));
+            return Err(LispError::new(&format!("`{}` expects exactly two arguments", name)));
         }
-    
         let left = Evaluator::eval(&args[0], env)?;
         let right = Evaluator::eval(&args[1], env)?;
-    
-        match_comparison!(&left, &right, {
-            (Expr::Number(l), Expr::Number(r)) => l == r,
-            (Expr::Float(l), Expr::Float(r)) => (l - r).abs() < f64::EPSILON,
-            (Expr::Number(l), Expr::Float(r)) => ((*l as f64) - r).abs() < f64::EPSILON,
-            (Expr::Float(l), Expr::Number(r)) => (l - (*r as f64)).abs() < f64::EPSILON,
-            (Expr::Symbol(_), Expr::Symbol(_)) => {
-                let left = Evaluator::eval(&left, env);
-                let right = Evaluator::eval(&right, env);
-                left == right
-            },
-            (Expr::List(l), Expr::List(r)) => std::ptr::eq(l, r),
-        }, Ok(Expr::List(vec![])))
+        Ok((left, right))
     }
-    
+
+    pub fn eval_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (left, right) = Comparison::eval_pair("eq", args, env)?;
+        Ok(Expr::from_bool(Comparison::is_eq(&left, &right)))
+    }
+
     pub fn eval_not_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 2 {
-            return Err(LispError::new("`ne` expects exactly two arguments"));
-        }
-    
-        let left = Evaluator::eval(&args[0], env)?;
-        let right = Evaluator::eval(&args[1], env)?;
-    
-        match_comparison!(&left, &right, {
-            (Expr::Number(l), Expr::Number(r)) => l != r,
-            (Expr::Float(l), Expr::Float(r)) => (l - r).abs() >= f64::EPSILON,
-            (Expr::Number(l), Expr::Float(r)) => ((*l as f64) - r).abs() >= f64::EPSILON,
-            (Expr::Float(l), Expr::Number(r)) => (l - (*r as f64)).abs() >= f64::EPSILON,
-            (Expr::Symbol(_), Expr::Symbol(_)) => {
-                let left = Evaluator::eval(&left, env);
-                let right = Evaluator::eval(&right, env);
-                left != right
-            },
-            (Expr::List(l), Expr::List(r)) => !std::ptr::eq(l, r),
-        }, Ok(Expr::Symbol("t".to_string())))
+        let (left, right) = Comparison::eval_pair("ne", args, env)?;
+        Ok(Expr::from_bool(!Comparison::is_eq(&left, &right)))
+    }
+
+    pub fn eval_eql(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (left, right) = Comparison::eval_pair("eql", args, env)?;
+        Ok(Expr::from_bool(Comparison::is_eql(&left, &right)))
+    }
+
+    pub fn eval_structural_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (left, right) = Comparison::eval_pair("equal", args, env)?;
+        Ok(Expr::from_bool(Comparison::is_equal(&left, &right)))
+    }
+
+    pub fn eval_equalp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (left, right) = Comparison::eval_pair("equalp", args, env)?;
+        Ok(Expr::from_bool(Comparison::is_equalp(&left, &right)))
     }
-                    
 }
 
 pub fn register_comparison_operators() {
@@ -148,13 +218,19 @@ pub fn register_comparison_operators() {
     OperatorRegistry::register("lt", Comparison::eval_less);
     OperatorRegistry::register("<=", Comparison::eval_less_equal);
     OperatorRegistry::register("lte", Comparison::eval_less_equal);
+    OperatorRegistry::register("=", Comparison::eval_numeric_equal);
+    OperatorRegistry::register("/=", Comparison::eval_numeric_not_equal);
     OperatorRegistry::register("eq", Comparison::eval_equal);
     OperatorRegistry::register("ne", Comparison::eval_not_equal);
+    OperatorRegistry::register("eql", Comparison::eval_eql);
+    OperatorRegistry::register("equal", Comparison::eval_structural_equal);
+    OperatorRegistry::register("equalp", Comparison::eval_equalp);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operator::test_util::eval_str;
     use crate::environment::Environment;
     use crate::expression::Expr;
 
@@ -225,9 +301,13 @@ mod tests {
         let result = Comparison::eval_greater(&[Expr::Symbol("a".to_string()), Expr::Number(3)], &mut env);
         assert_eq!(result, Err(LispError::new("`>` arguments must be numbers")));
 
-        // 不足的参数数量
+        // 单个参数总是成立
         let result = Comparison::eval_greater(&[Expr::Number(5)], &mut env);
-        assert_eq!(result, Err(LispError::new("`>` expects exactly two arguments")));
+        assert_eq!(result, Ok(Expr::t()));
+
+        // 不足的参数数量
+        let result = Comparison::eval_greater(&[], &mut env);
+        assert_eq!(result, Err(LispError::new("`>` expects at least one argument")));
     }
 
     #[test]
@@ -280,9 +360,13 @@ mod tests {
         let result = Comparison::eval_greater_equal(&[Expr::Symbol("a".to_string()), Expr::Number(3)], &mut env);
         assert_eq!(result, Err(LispError::new("`>=` arguments must be numbers")));
 
-        // 不足的参数数量
+        // 单个参数总是成立
         let result = Comparison::eval_greater_equal(&[Expr::Number(5)], &mut env);
-        assert_eq!(result, Err(LispError::new("`>=` expects exactly two arguments")));
+        assert_eq!(result, Ok(Expr::t()));
+
+        // 不足的参数数量
+        let result = Comparison::eval_greater_equal(&[], &mut env);
+        assert_eq!(result, Err(LispError::new("`>=` expects at least one argument")));
     }
 
     #[test]
@@ -335,9 +419,13 @@ mod tests {
         let result = Comparison::eval_less(&[Expr::Symbol("a".to_string()), Expr::Number(3)], &mut env);
         assert_eq!(result, Err(LispError::new("`<` arguments must be numbers")));
 
-        // 不足的参数数量
+        // 单个参数总是成立
         let result = Comparison::eval_less(&[Expr::Number(5)], &mut env);
-        assert_eq!(result, Err(LispError::new("`<` expects exactly two arguments")));
+        assert_eq!(result, Ok(Expr::t()));
+
+        // 不足的参数数量
+        let result = Comparison::eval_less(&[], &mut env);
+        assert_eq!(result, Err(LispError::new("`<` expects at least one argument")));
     }
 
     #[test]
@@ -390,9 +478,13 @@ mod tests {
         let result = Comparison::eval_less_equal(&[Expr::Symbol("a".to_string()), Expr::Number(3)], &mut env);
         assert_eq!(result, Err(LispError::new("`<=` arguments must be numbers")));
 
-        // 不足的参数数量
+        // 单个参数总是成立
         let result = Comparison::eval_less_equal(&[Expr::Number(5)], &mut env);
-        assert_eq!(result, Err(LispError::new("`<=` expects exactly two arguments")));
+        assert_eq!(result, Ok(Expr::t()));
+
+        // 不足的参数数量
+        let result = Comparison::eval_less_equal(&[], &mut env);
+        assert_eq!(result, Err(LispError::new("`<=` expects at least one argument")));
     }
 
     #[test]
@@ -476,16 +568,20 @@ mod tests {
     fn test_equal_operator_with_floats() {
         let mut env = setup_environment();
 
-        // 浮点数相等
+        // 浮点数没有同一性，值相同也不 eq；数值相等用 = 判断
         let result = Comparison::eval_equal(&[Expr::Float(3.0), Expr::Float(3.0)], &mut env);
+        assert_eq!(result, Ok(Expr::List(vec![])));
+        let result = Comparison::eval_numeric_equal(&[Expr::Float(3.0), Expr::Float(3.0)], &mut env);
         assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
 
         // 浮点数不相等
         let result = Comparison::eval_equal(&[Expr::Float(3.0), Expr::Float(3.1)], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
-        // 浮点数与整数
+        // 浮点数与整数类型不同，不 eq，但数值相等
         let result = Comparison::eval_equal(&[Expr::Float(3.0), Expr::Number(3)], &mut env);
+        assert_eq!(result, Ok(Expr::List(vec![])));
+        let result = Comparison::eval_numeric_equal(&[Expr::Float(3.0), Expr::Number(3)], &mut env);
         assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
 
         // 浮点数与整数
@@ -526,12 +622,14 @@ mod tests {
         let result = Comparison::eval_not_equal(&[Expr::Float(3.0), Expr::Float(3.1)], &mut env);
         assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
 
-        // 浮点数相等
+        // 值相同的浮点数也不 eq，数值不等用 /= 判断
         let result = Comparison::eval_not_equal(&[Expr::Float(3.0), Expr::Float(3.0)], &mut env);
+        assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
+        let result = Comparison::eval_numeric_not_equal(&[Expr::Float(3.0), Expr::Float(3.0)], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
         // 浮点数与整数
-        let result = Comparison::eval_not_equal(&[Expr::Float(3.0), Expr::Number(3)], &mut env);
+        let result = Comparison::eval_numeric_not_equal(&[Expr::Float(3.0), Expr::Number(3)], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
         // 浮点数与整数
@@ -563,4 +661,92 @@ mod tests {
         let result = Comparison::eval_not_equal(&[Expr::Symbol("a".to_string()), Expr::Number(3)], &mut env);
         assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
     }
+
+    #[test]
+    fn test_comparison_with_bigints() {
+        let mut env = setup_environment();
+        let big = |text: &str| Expr::from_bigint(crate::bignum::BigInt::parse(text, 10).unwrap());
+
+        let result = Comparison::eval_greater(&[big("18446744073709551616"), Expr::Number(i64::MAX)], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+
+        let result = Comparison::eval_less(&[big("-18446744073709551616"), Expr::Float(-1.0)], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+
+        let result = Comparison::eval_less_equal(&[big("18446744073709551616"), big("18446744073709551615")], &mut env);
+        assert_eq!(result, Ok(Expr::nil()));
+
+        let result = Comparison::eval_numeric_equal(&[big("18446744073709551616"), big("18446744073709551616")], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+    }
+
+    #[test]
+    fn test_comparison_with_ratios() {
+        let mut env = setup_environment();
+        let ratio = |numerator: i64, denominator: i64| {
+            Expr::from_ratio(
+                crate::ratio::Ratio::new(
+                    crate::bignum::BigInt::from_i64(numerator),
+                    crate::bignum::BigInt::from_i64(denominator),
+                )
+                .unwrap(),
+            )
+        };
+
+        let result = Comparison::eval_less(&[ratio(1, 3), ratio(1, 2)], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+
+        let result = Comparison::eval_greater(&[ratio(1, 3), Expr::Float(0.3)], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+
+        let result = Comparison::eval_greater_equal(&[ratio(-1, 2), Expr::Number(0)], &mut env);
+        assert_eq!(result, Ok(Expr::nil()));
+
+        let result = Comparison::eval_numeric_equal(&[ratio(2, 4), ratio(1, 2)], &mut env);
+        assert_eq!(result, Ok(Expr::t()));
+    }
+
+    #[test]
+    fn test_variadic_numeric_comparison() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(< 1 2 5/2 3.0 4)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(< 1 3 2)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(<= 1 1 2)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(> 3 2 2)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(>= 3 2 2 1/2)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(= 1 1.0 2/2)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(= 1/3 0.3333)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(= #C(1 2) #C(1.0 2.0))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(/= 1 2 3)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(/= 1 2 1.0)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(= 7)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(= 1 \"a\")", &mut env), Err(LispError::new("`=` arguments must be numbers")));
+        assert_eq!(eval_str("(< 1 #C(1 1))", &mut env), Err(LispError::new("`<` arguments must be numbers")));
+    }
+
+    #[test]
+    fn test_equality_hierarchy() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(eq 'a 'a)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(eq nil 'nil)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(eq 1 1)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(eq 1 1.0)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(eq 1.5 1.5)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(eq '(1 2) '(1 2))", &mut env), Ok(Expr::nil()));
+
+        assert_eq!(eval_str("(eql 1.5 1.5)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(eql 1/2 2/4)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(eql 1 1.0)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(eql 0.0 -0.0)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(eql \"ab\" \"ab\")", &mut env), Ok(Expr::nil()));
+
+        assert_eq!(eval_str("(equal \"ab\" \"ab\")", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equal '(1 (2 \"x\")) '(1 (2 \"x\")))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equal \"ab\" \"AB\")", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(equal '(1) '(1.0))", &mut env), Ok(Expr::nil()));
+
+        assert_eq!(eval_str("(equalp \"ab\" \"AB\")", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equalp '(1 \"A\") '(1.0 \"a\"))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equalp '(1 2) '(1 3))", &mut env), Ok(Expr::nil()));
+    }
 }
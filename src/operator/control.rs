@@ -21,22 +21,17 @@ impl Control {
             match condition {
                 Expr::List(pair) => {
                     if pair.len() == 1 {
-                        // 如果子句只有一个元素，直接返回该元素的值
-                        return Evaluator::eval(&pair[0], env);
+                        // 如果子句只有一个元素，测试值为真时直接返回该值
+                        let value = Evaluator::eval(&pair[0], env)?;
+                        if value.is_truthy() {
+                            return Ok(value);
+                        }
                     } else if pair.len() == 2 {
                         let test = &pair[0];
                         let result = &pair[1];
 
-                        let test_value = match Evaluator::eval(test, env) {
-                            Ok(Expr::Symbol(s)) if s == "t" || s == "T" => true,  // 支持真值符号 t 或 T
-                            Ok(Expr::Number(n)) if n != 0 => true,   // 非零数值作为真值
-                            Ok(Expr::List(list)) if !list.is_empty() => true, // 非空列表为真
-                            Ok(Expr::Symbol(s)) if s == "nil" => false, // nil 为假值
-                            Ok(_) => false,
-                            Err(_) => false,
-                        };
-
-                        if test_value {
+                        // 测试中的错误直接向上传播，而不是被当作假值
+                        if Evaluator::eval(test, env)?.is_truthy() {
                             return Evaluator::eval(result, env);
                         }
                     } else {
@@ -50,24 +45,62 @@ impl Control {
     }
 
 
-    pub fn eval_not(args: &[Expr], _env: &mut Environment) -> Result<Expr, LispError> {
+    pub fn eval_not(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
         if args.len() != 1 {
             return Err(LispError::new("not expects exactly one argument"));
         }
 
-        // 直接识别假值，不调用 eval_tree
-        let is_false = match &args[0] {
-            Expr::Symbol(ref s) if s == "nil" => true,  // nil 为假
-            Expr::Number(n) if *n == 0 => true,          // 0 为假
-            Expr::List(ref list) if list.is_empty() => true, // 空列表为假
-            _ => false,
+        let value = Evaluator::eval(&args[0], env)?;
+        Ok(Expr::from_bool(!value.is_truthy()))
+    }
+
+    // (values a b ...) 把全部参数作为多值返回，主值是第一个值
+    pub fn eval_values(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = args
+            .iter()
+            .map(|arg| Evaluator::eval(arg, env))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(env.set_values(values))
+    }
+
+    pub fn eval_multiple_value_list(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("multiple-value-list expects exactly one argument"));
+        }
+        Ok(Expr::List(Control::eval_multiple_values(&args[0], env)?))
+    }
+
+    // (multiple-value-bind (var ...) form body ...)：缺少的值绑定为 nil，多余的值被丢弃
+    pub fn eval_multiple_value_bind(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new("multiple-value-bind requires a variable list and a values form"));
+        }
+        let vars = match &args[0] {
+            Expr::List(vars) => vars,
+            _ => return Err(LispError::new("multiple-value-bind: first argument must be a list of variables")),
         };
+        let mut values = Control::eval_multiple_values(&args[1], env)?.into_iter();
 
-        if is_false {
-            Ok(Expr::Symbol("t".to_string())) // 返回真值 t
-        } else {
-            Ok(Expr::List(vec![])) // 返回假值 nil
+        let mut local_env = env.clone();
+        for var in vars {
+            match var {
+                Expr::Symbol(name) if !var.is_keyword() => local_env.set_symbol(name.clone(), values.next().unwrap_or_else(Expr::nil)),
+                _ => return Err(LispError::new(&format!("multiple-value-bind: variable must be a symbol, got {}", var))),
+            }
         }
+
+        let mut result = Expr::nil();
+        for form in &args[2..] {
+            result = Evaluator::eval(form, &mut local_env)?;
+        }
+        Ok(result)
+    }
+
+    // 求值 form 并收集它的全部值；只返回单个值的形式得到只含主值的列表
+    pub fn eval_multiple_values(form: &Expr, env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        let depth = env.form_depth() + 1;
+        let primary = Evaluator::eval(form, env)?;
+        Ok(env.take_values(depth).unwrap_or_else(|| vec![primary]))
     }
 
     pub fn eval_gensym(_: &[Expr], _: &mut Environment) -> Result<Expr, LispError> {
@@ -81,11 +114,15 @@ pub fn register_control_operators() {
     OperatorRegistry::register("cond", Control::eval_cond);
     OperatorRegistry::register("not", Control::eval_not);
     OperatorRegistry::register("gensym", Control::eval_gensym);
+    OperatorRegistry::register("values", Control::eval_values);
+    OperatorRegistry::register("multiple-value-list", Control::eval_multiple_value_list);
+    OperatorRegistry::register("multiple-value-bind", Control::eval_multiple_value_bind);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operator::test_util::eval_str;
     use crate::environment::Environment;
     use crate::expression::Expr;
     use crate::evaluator::Evaluator;
@@ -222,13 +259,18 @@ mod tests {
         let result = Control::eval_not(&[Expr::Number(1)], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
+        // 0 不再是假值：只有 nil 为假
         let result = Control::eval_not(&[Expr::Number(0)], &mut env);
-        assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
+        assert_eq!(result, Ok(Expr::List(vec![])));
 
         let result = Control::eval_not(&[Expr::List(vec![])], &mut env);
         assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
 
-        let result = Control::eval_not(&[Expr::List(vec![Expr::Number(1)])], &mut env);
+        // not 会先求值参数，所以列表需要 quote
+        let result = Control::eval_not(&[Expr::List(vec![
+            Expr::Symbol("quote".to_string()),
+            Expr::List(vec![Expr::Number(1)]),
+        ])], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
         let result = Control::eval_not(&[Expr::Symbol("t".to_string())], &mut env);
@@ -237,14 +279,22 @@ mod tests {
         let result = Control::eval_not(&[Expr::Symbol("T".to_string())], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
 
+        let result = Control::eval_not(&[Expr::Symbol("nil".to_string())], &mut env);
+        assert_eq!(result, Ok(Expr::Symbol("t".to_string())));
+
         // Testing nested lists with not
-        let result = Control::eval_not(&[
+        let result = Control::eval_not(&[Expr::List(vec![
+            Expr::Symbol("quote".to_string()),
             Expr::List(vec![
                 Expr::List(vec![Expr::Number(1)]),
                 Expr::Number(2)
-            ])
-        ], &mut env);
+            ]),
+        ])], &mut env);
         assert_eq!(result, Ok(Expr::List(vec![])));
+
+        // 参数求值出错时不再被吞掉
+        let result = Control::eval_not(&[Expr::Symbol("undefined".to_string())], &mut env);
+        assert_eq!(result, Err(LispError::new("Undefined symbol: undefined")));
     }
 
     #[test]
@@ -353,5 +403,97 @@ mod tests {
         let seed_expr = Expr::Symbol("seed".to_string());
         let seed_result = Evaluator::eval(&seed_expr, &mut env).unwrap();
         assert_eq!(seed_result, result); // 确保 result 和 seed_result 相同
-    }    
+    }
+
+    #[test]
+    fn test_cond_uses_single_truthiness_rule() {
+        let mut env = setup_environment();
+
+        // (cond (0 'zero) (t 'other)) 中 0 为真
+        let expr = Expr::List(vec![
+            Expr::Symbol("cond".to_string()),
+            Expr::List(vec![
+                Expr::Number(0),
+                Expr::List(vec![Expr::Symbol("quote".to_string()), Expr::Symbol("zero".to_string())]),
+            ]),
+            Expr::List(vec![Expr::Symbol("t".to_string()), Expr::Number(1)]),
+        ]);
+        assert_eq!(Evaluator::eval(&expr, &mut env), Ok(Expr::Symbol("zero".to_string())));
+
+        // 任何非 nil 的符号都是真值
+        let expr = Expr::List(vec![
+            Expr::Symbol("cond".to_string()),
+            Expr::List(vec![
+                Expr::List(vec![Expr::Symbol("quote".to_string()), Expr::Symbol("yes".to_string())]),
+                Expr::Number(1),
+            ]),
+        ]);
+        assert_eq!(Evaluator::eval(&expr, &mut env), Ok(Expr::Number(1)));
+
+        // 单元素子句的值为 nil 时继续检查后面的子句
+        let expr = Expr::List(vec![
+            Expr::Symbol("cond".to_string()),
+            Expr::List(vec![Expr::Symbol("nil".to_string())]),
+            Expr::List(vec![Expr::Number(2)]),
+        ]);
+        assert_eq!(Evaluator::eval(&expr, &mut env), Ok(Expr::Number(2)));
+
+        // 测试形式的错误会传播出来
+        let expr = Expr::List(vec![
+            Expr::Symbol("cond".to_string()),
+            Expr::List(vec![Expr::Symbol("undefined".to_string()), Expr::Number(1)]),
+        ]);
+        assert_eq!(Evaluator::eval(&expr, &mut env), Err(LispError::new("Undefined symbol: undefined")));
+    }
+
+    #[test]
+    fn test_t_and_upper_t_are_the_same_value() {
+        let mut env = Environment::initialize();
+        assert_eq!(Evaluator::eval(&Expr::Symbol("T".to_string()), &mut env), Ok(Expr::t()));
+        assert_eq!(Evaluator::eval(&Expr::Symbol("t".to_string()), &mut env), Ok(Expr::t()));
+    }
+
+    #[test]
+    fn test_values_and_multiple_value_list() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(values 1 2)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(values)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(multiple-value-list (values 1 2 3))", &mut env).unwrap().to_string(), "(1 2 3)");
+        assert_eq!(eval_str("(multiple-value-list (values))", &mut env).unwrap().to_string(), "()");
+        assert_eq!(eval_str("(multiple-value-list 5)", &mut env).unwrap().to_string(), "(5)");
+        // 只有主值参与普通的计算
+        assert_eq!(eval_str("(+ 10 (values 1 2))", &mut env), Ok(Expr::Number(11)));
+    }
+
+    #[test]
+    fn test_multiple_values_pass_through_progn_and_functions() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(multiple-value-list (progn 0 (values 1 2)))", &mut env).unwrap().to_string(), "(1 2)");
+        eval_str("(defun two-values (x) (values x (+ x 1)))", &mut env).unwrap();
+        assert_eq!(eval_str("(multiple-value-list (two-values 5))", &mut env).unwrap().to_string(), "(5 6)");
+        assert_eq!(
+            eval_str("(multiple-value-list (cond ((values nil 1) 'no) (t (values 'a 'b))))", &mut env).unwrap().to_string(),
+            "(a b)"
+        );
+    }
+
+    #[test]
+    fn test_multiple_values_do_not_leak() {
+        let mut env = Environment::initialize();
+        // 操作符只使用子形式的主值，因此多值不会泄漏到外层
+        assert_eq!(eval_str("(multiple-value-list (+ 0 (values 1 2)))", &mut env).unwrap().to_string(), "(1)");
+        assert_eq!(eval_str("(multiple-value-list (progn (values 1 2) 3))", &mut env).unwrap().to_string(), "(3)");
+    }
+
+    #[test]
+    fn test_multiple_value_bind() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(multiple-value-bind (a b) (values 1 2) (+ a b))", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_str("(multiple-value-bind (a b c) (values 1 2) c)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(multiple-value-bind (a) 7 a)", &mut env), Ok(Expr::Number(7)));
+        assert_eq!(
+            eval_str("(multiple-value-bind (1) 7 1)", &mut env),
+            Err(LispError::new("multiple-value-bind: variable must be a symbol, got 1"))
+        );
+    }
 }
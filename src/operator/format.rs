@@ -0,0 +1,909 @@
+// operator/format.rs
+
+use crate::operator::OperatorRegistry;
+use crate::operator::arithmetic::Arithmetic;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::printer::{PrintOptions, Printer};
+use crate::stream::OutputStream;
+use crate::character;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// 列宽、重复次数和小数位数等参数的上限
+const MAX_WIDTH: usize = 1 << 16;
+
+// 指令的前缀参数：整数、'c 形式的字符、V（从实参中取）或 #（剩余实参的个数）
+#[derive(Debug, Clone, Copy)]
+enum Param {
+    Int(i64),
+    Char(char),
+    NextArg,
+    Remaining,
+}
+
+// 取值之后的前缀参数
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Int(i64),
+    Char(char),
+}
+
+#[derive(Debug)]
+struct Directive {
+    // '~' 在控制串中的字符下标，用于错误信息
+    index: usize,
+    kind: char,
+    params: Vec<Option<Param>>,
+    colon: bool,
+    at: bool,
+    // ~{ 的循环体
+    body: Vec<Node>,
+    // ~[ 的各个子句；default_clause 表示最后一个子句由 ~:; 引出
+    clauses: Vec<Vec<Node>>,
+    default_clause: bool,
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Directive(Directive),
+}
+
+// 处理完一段指令后的去向：继续，或者被 ~^ 提前结束
+#[derive(Debug, PartialEq)]
+enum Flow {
+    Continue,
+    Escape,
+}
+
+// 把控制串解析成指令树；~{ 和 ~[ 的配对错误在这里就能发现
+struct ControlParser<'a> {
+    control: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> ControlParser<'a> {
+    fn new(control: &'a str) -> Self {
+        ControlParser { control, chars: control.chars().collect(), pos: 0 }
+    }
+
+    fn error(&self, index: usize, message: &str) -> LispError {
+        Format::error(self.control, index, message)
+    }
+
+    fn parse(&mut self) -> Result<Vec<Node>, LispError> {
+        let (nodes, terminator) = self.parse_until(&[])?;
+        match terminator {
+            Some(directive) => Err(self.error(directive.index, &format!("~{} without a matching opening directive", directive.kind))),
+            None => Ok(nodes),
+        }
+    }
+
+    // 解析到 terminators 中的某个指令为止，返回之前的节点和该结束指令；到达控制串末尾时结束指令为 None
+    fn parse_until(&mut self, terminators: &[char]) -> Result<(Vec<Node>, Option<Directive>), LispError> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while self.pos < self.chars.len() {
+            let ch = self.chars[self.pos];
+            if ch != '~' {
+                text.push(ch);
+                self.pos += 1;
+                continue;
+            }
+            let mut directive = self.parse_directive()?;
+            match directive.kind {
+                // ~ 加换行：忽略换行和下一行开头的空白；: 保留空白，@ 保留换行
+                '\n' => {
+                    if directive.at {
+                        text.push('\n');
+                    }
+                    if !directive.colon {
+                        while self.pos < self.chars.len() && self.chars[self.pos] != '\n' && self.chars[self.pos].is_whitespace() {
+                            self.pos += 1;
+                        }
+                    }
+                    continue;
+                }
+                kind if terminators.contains(&kind) => {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(text));
+                    }
+                    return Ok((nodes, Some(directive)));
+                }
+                '}' | ']' | ';' => {
+                    return Err(self.error(directive.index, &format!("~{} without a matching opening directive", directive.kind)));
+                }
+                '{' => {
+                    let (body, end) = self.parse_until(&['}'])?;
+                    if end.is_none() {
+                        return Err(self.error(directive.index, "~{ has no matching ~}"));
+                    }
+                    directive.body = body;
+                }
+                '[' => {
+                    loop {
+                        let (clause, end) = self.parse_until(&[';', ']'])?;
+                        directive.clauses.push(clause);
+                        match end {
+                            Some(end) if end.kind == ';' => {
+                                if directive.default_clause {
+                                    return Err(self.error(end.index, "the default clause ~:; must be the last one"));
+                                }
+                                directive.default_clause = end.colon;
+                            }
+                            Some(_) => break,
+                            None => return Err(self.error(directive.index, "~[ has no matching ~]")),
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if !text.is_empty() {
+                nodes.push(Node::Text(std::mem::take(&mut text)));
+            }
+            nodes.push(Node::Directive(directive));
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok((nodes, None))
+    }
+
+    fn parse_directive(&mut self) -> Result<Directive, LispError> {
+        let index = self.pos;
+        self.pos += 1; // Skip '~'
+        let mut params = Vec::new();
+        loop {
+            let param = self.parse_param(index)?;
+            if self.chars.get(self.pos) == Some(&',') {
+                params.push(param);
+                self.pos += 1;
+                continue;
+            }
+            if param.is_some() {
+                params.push(param);
+            }
+            break;
+        }
+        let mut colon = false;
+        let mut at = false;
+        loop {
+            match self.chars.get(self.pos) {
+                Some(':') if !colon => colon = true,
+                Some('@') if !at => at = true,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let kind = match self.chars.get(self.pos) {
+            Some(&ch) => ch.to_ascii_lowercase(),
+            None => return Err(self.error(index, "control string ends in the middle of a directive")),
+        };
+        self.pos += 1;
+        if !"asdbxofe%&~{}[];^*c\n".contains(kind) {
+            return Err(self.error(index, &format!("unknown directive ~{}", kind)));
+        }
+        Ok(Directive { index, kind, params, colon, at, body: Vec::new(), clauses: Vec::new(), default_clause: false })
+    }
+
+    fn parse_param(&mut self, index: usize) -> Result<Option<Param>, LispError> {
+        match self.chars.get(self.pos) {
+            Some('\'') => match self.chars.get(self.pos + 1) {
+                Some(&ch) => {
+                    self.pos += 2;
+                    Ok(Some(Param::Char(ch)))
+                }
+                None => Err(self.error(index, "control string ends in the middle of a directive")),
+            },
+            Some('v') | Some('V') => {
+                self.pos += 1;
+                Ok(Some(Param::NextArg))
+            }
+            Some('#') => {
+                self.pos += 1;
+                Ok(Some(Param::Remaining))
+            }
+            Some(&ch) if ch.is_ascii_digit() || ch == '+' || ch == '-' => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.chars.get(self.pos).is_some_and(|ch| ch.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                let digits: String = self.chars[start..self.pos].iter().collect();
+                digits
+                    .parse::<i64>()
+                    .map(|n| Some(Param::Int(n)))
+                    .map_err(|_| self.error(start, &format!("invalid parameter {}", digits)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+// 格式化过程中的实参列表；~{ 的每次迭代有自己的一份
+struct Args {
+    items: Vec<Expr>,
+    pos: usize,
+}
+
+impl Args {
+    fn new(items: Vec<Expr>) -> Self {
+        Args { items, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.items.len() - self.pos
+    }
+}
+
+struct Formatter<'a> {
+    control: &'a str,
+    options: PrintOptions,
+    output: String,
+    // 输出开始前目的地是否位于行首，~& 据此决定是否换行
+    started_at_line_start: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn error(&self, directive: &Directive, message: &str) -> LispError {
+        Format::error(self.control, directive.index, message)
+    }
+
+    fn at_line_start(&self) -> bool {
+        match self.output.chars().last() {
+            Some(ch) => ch == '\n',
+            None => self.started_at_line_start,
+        }
+    }
+
+    fn next_arg(&self, directive: &Directive, args: &mut Args) -> Result<Expr, LispError> {
+        match args.items.get(args.pos) {
+            Some(arg) => {
+                args.pos += 1;
+                Ok(arg.clone())
+            }
+            None => Err(self.error(directive, &format!("not enough arguments for ~{}", directive.kind))),
+        }
+    }
+
+    // 按顺序求出全部前缀参数的值；V 参数会消耗实参
+    fn resolve_params(&self, directive: &Directive, args: &mut Args) -> Result<Vec<Option<Value>>, LispError> {
+        let mut values = Vec::new();
+        for param in &directive.params {
+            values.push(match param {
+                None => None,
+                Some(Param::Int(n)) => Some(Value::Int(*n)),
+                Some(Param::Char(ch)) => Some(Value::Char(*ch)),
+                Some(Param::Remaining) => Some(Value::Int(args.remaining() as i64)),
+                Some(Param::NextArg) => match self.next_arg(directive, args)? {
+                    Expr::Number(n) => Some(Value::Int(n)),
+                    Expr::Char(ch) => Some(Value::Char(ch)),
+                    value if !value.is_truthy() => None,
+                    other => return Err(self.error(directive, &format!("V parameter must be an integer or character, got {}", other))),
+                },
+            });
+        }
+        Ok(values)
+    }
+
+    fn int_param(&self, directive: &Directive, values: &[Option<Value>], i: usize) -> Result<Option<i64>, LispError> {
+        match values.get(i).copied().flatten() {
+            None => Ok(None),
+            Some(Value::Int(n)) => Ok(Some(n)),
+            Some(Value::Char(_)) => Err(self.error(directive, &format!("parameter {} of ~{} must be an integer", i + 1, directive.kind))),
+        }
+    }
+
+    // 非负的整数参数，省略时取 default
+    fn count_param(&self, directive: &Directive, values: &[Option<Value>], i: usize, default: usize) -> Result<usize, LispError> {
+        match self.int_param(directive, values, i)? {
+            None => Ok(default),
+            Some(n) if n >= 0 => Ok(n as usize),
+            Some(n) => Err(self.error(directive, &format!("parameter {} of ~{} must not be negative, got {}", i + 1, directive.kind, n))),
+        }
+    }
+
+    // 决定输出长度的参数（列宽、重复次数、小数位数）不能超过 MAX_WIDTH，否则一个参数就能让 format 耗尽内存
+    fn size_param(&self, directive: &Directive, values: &[Option<Value>], i: usize, default: usize) -> Result<usize, LispError> {
+        let n = self.count_param(directive, values, i, default)?;
+        if n > MAX_WIDTH {
+            return Err(self.width_error(directive, i, n as i64));
+        }
+        Ok(n)
+    }
+
+    // ~F 和 ~E 的宽度与小数位数可以省略，负数按 0 处理
+    fn width_param(&self, directive: &Directive, values: &[Option<Value>], i: usize) -> Result<Option<usize>, LispError> {
+        match self.int_param(directive, values, i)? {
+            Some(n) if n > MAX_WIDTH as i64 => Err(self.width_error(directive, i, n)),
+            n => Ok(n.map(|n| n.max(0) as usize)),
+        }
+    }
+
+    fn width_error(&self, directive: &Directive, i: usize, n: i64) -> LispError {
+        self.error(directive, &format!("parameter {} of ~{} must not exceed {}, got {}", i + 1, directive.kind, MAX_WIDTH, n))
+    }
+
+    fn char_param(&self, directive: &Directive, values: &[Option<Value>], i: usize, default: char) -> Result<char, LispError> {
+        match values.get(i).copied().flatten() {
+            None => Ok(default),
+            Some(Value::Char(ch)) => Ok(ch),
+            Some(Value::Int(_)) => Err(self.error(directive, &format!("parameter {} of ~{} must be a character", i + 1, directive.kind))),
+        }
+    }
+
+    fn run(&mut self, nodes: &[Node], args: &mut Args) -> Result<Flow, LispError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.output.push_str(text),
+                Node::Directive(directive) => {
+                    if self.directive(directive, args)? == Flow::Escape {
+                        return Ok(Flow::Escape);
+                    }
+                }
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn directive(&mut self, directive: &Directive, args: &mut Args) -> Result<Flow, LispError> {
+        let values = self.resolve_params(directive, args)?;
+        match directive.kind {
+            'a' | 's' => {
+                let arg = self.next_arg(directive, args)?;
+                let text = if directive.kind == 'a' {
+                    Printer::princ(&arg, &self.options)
+                } else {
+                    Printer::print(&arg, &self.options)
+                };
+                let mincol = self.size_param(directive, &values, 0, 0)?;
+                let colinc = self.size_param(directive, &values, 1, 1)?.max(1);
+                let minpad = self.size_param(directive, &values, 2, 0)?;
+                let padchar = self.char_param(directive, &values, 3, ' ')?;
+                self.output.push_str(&Format::pad(&text, mincol, colinc, minpad, padchar, directive.at));
+            }
+            'd' => self.integer(directive, &values, args, 10)?,
+            'b' => self.integer(directive, &values, args, 2)?,
+            'o' => self.integer(directive, &values, args, 8)?,
+            'x' => self.integer(directive, &values, args, 16)?,
+            'f' => self.fixed(directive, &values, args)?,
+            'e' => self.exponential(directive, &values, args)?,
+            'c' => match self.next_arg(directive, args)? {
+                Expr::Char(ch) if directive.at => self.output.push_str(&Expr::Char(ch).to_string()),
+                Expr::Char(ch) if directive.colon => self.output.push_str(&character::name_of(ch)),
+                Expr::Char(ch) => self.output.push(ch),
+                other => return Err(self.error(directive, &format!("~c requires a character, got {}", other))),
+            },
+            '%' => {
+                let count = self.size_param(directive, &values, 0, 1)?;
+                self.output.push_str(&"\n".repeat(count));
+            }
+            '&' => {
+                let count = self.size_param(directive, &values, 0, 1)?;
+                if count > 0 {
+                    if !self.at_line_start() {
+                        self.output.push('\n');
+                    }
+                    self.output.push_str(&"\n".repeat(count - 1));
+                }
+            }
+            '~' => {
+                let count = self.size_param(directive, &values, 0, 1)?;
+                self.output.push_str(&"~".repeat(count));
+            }
+            '*' => {
+                let target = if directive.at {
+                    self.count_param(directive, &values, 0, 0)?
+                } else if directive.colon {
+                    let count = self.count_param(directive, &values, 0, 1)?;
+                    args.pos.checked_sub(count).ok_or_else(|| self.error(directive, "~:* moved before the first argument"))?
+                } else {
+                    args.pos + self.count_param(directive, &values, 0, 1)?
+                };
+                if target > args.items.len() {
+                    return Err(self.error(directive, "~* moved past the last argument"));
+                }
+                args.pos = target;
+            }
+            '^' => {
+                let done = match self.int_param(directive, &values, 0)? {
+                    Some(n) => n == 0,
+                    None => args.remaining() == 0,
+                };
+                if done {
+                    return Ok(Flow::Escape);
+                }
+            }
+            '{' => self.iteration(directive, &values, args)?,
+            '[' => return self.conditional(directive, &values, args),
+            _ => return Err(self.error(directive, &format!("unknown directive ~{}", directive.kind))),
+        }
+        Ok(Flow::Continue)
+    }
+
+    // ~mincol,padchar,commachar,comma-intervalD：@ 总是输出符号，: 按位分组
+    fn integer(&mut self, directive: &Directive, values: &[Option<Value>], args: &mut Args, radix: u32) -> Result<(), LispError> {
+        let arg = self.next_arg(directive, args)?;
+        let mincol = self.size_param(directive, values, 0, 0)?;
+        let padchar = self.char_param(directive, values, 1, ' ')?;
+        let commachar = self.char_param(directive, values, 2, ',')?;
+        let interval = self.count_param(directive, values, 3, 3)?.max(1);
+        let text = match Arithmetic::to_bigint(&arg).filter(|_| matches!(arg, Expr::Number(_) | Expr::BigInt(_))) {
+            Some(n) => {
+                let digits = n.abs().to_string_radix(radix);
+                let digits = if directive.colon { Format::group_digits(&digits, commachar, interval) } else { digits };
+                let sign = if n.is_negative() { "-" } else if directive.at { "+" } else { "" };
+                format!("{}{}", sign, digits)
+            }
+            // 不是整数时按 ~A 输出，但仍然使用指定的进制
+            None => Printer::princ(&arg, &PrintOptions { base: radix, radix: false, ..self.options.clone() }),
+        };
+        self.output.push_str(&Format::pad(&text, mincol, 1, 0, padchar, true));
+        Ok(())
+    }
+
+    fn real_argument(&self, directive: &Directive, args: &mut Args) -> Result<Result<f64, Expr>, LispError> {
+        let arg = self.next_arg(directive, args)?;
+        Ok(match &arg {
+            Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_) => {
+                Arithmetic::to_f64(&arg).filter(|x| x.is_finite()).ok_or(arg)
+            }
+            _ => Err(arg),
+        })
+    }
+
+    // 符号、宽度和溢出字符对 ~F 和 ~E 的处理相同
+    fn finish_number(&mut self, directive: &Directive, negative: bool, body: String, width: Option<usize>, overflowchar: Option<char>, padchar: char) {
+        let sign = if negative { "-" } else if directive.at { "+" } else { "" };
+        let text = format!("{}{}", sign, body);
+        match (width, overflowchar) {
+            (Some(width), Some(overflow)) if text.chars().count() > width => {
+                self.output.push_str(&overflow.to_string().repeat(width));
+            }
+            (Some(width), _) => self.output.push_str(&Format::pad(&text, width, 1, 0, padchar, true)),
+            (None, _) => self.output.push_str(&text),
+        }
+    }
+
+    // ~w,d,k,overflowchar,padcharF
+    fn fixed(&mut self, directive: &Directive, values: &[Option<Value>], args: &mut Args) -> Result<(), LispError> {
+        let width = self.width_param(directive, values, 0)?;
+        let decimals = self.width_param(directive, values, 1)?;
+        let scale = self.int_param(directive, values, 2)?.unwrap_or(0);
+        let overflowchar = values.get(3).copied().flatten().map(|_| self.char_param(directive, values, 3, ' ')).transpose()?;
+        let padchar = self.char_param(directive, values, 4, ' ')?;
+        let value = match self.real_argument(directive, args)? {
+            Ok(value) => value * 10f64.powi(scale as i32),
+            Err(other) => {
+                let text = Printer::princ(&other, &self.options);
+                self.output.push_str(&Format::pad(&text, width.unwrap_or(0), 1, 0, padchar, true));
+                return Ok(());
+            }
+        };
+        let magnitude = value.abs();
+        let body = match (decimals, width) {
+            (Some(decimals), _) => format!("{:.*}", decimals, magnitude),
+            // 只给出宽度时，小数位数取能放下的最大值，至少一位
+            (None, Some(width)) => {
+                let integer_digits = format!("{:.0}", magnitude.trunc()).len();
+                let sign = usize::from(value < 0.0 || directive.at);
+                let decimals = width.saturating_sub(integer_digits + 1 + sign).max(1);
+                let text = format!("{:.*}", decimals, magnitude);
+                // 去掉多余的尾随零，但保留小数点后的一位
+                let trimmed = text.trim_end_matches('0');
+                if trimmed.ends_with('.') { format!("{}0", trimmed) } else { trimmed.to_string() }
+            }
+            (None, None) => {
+                let text = format!("{}", magnitude);
+                if text.contains('.') { text } else { format!("{}.0", text) }
+            }
+        };
+        self.finish_number(directive, value < 0.0, body, width, overflowchar, padchar);
+        Ok(())
+    }
+
+    // ~w,d,e,k,overflowchar,padcharE：尾数在小数点前有一位数字，指数总是带符号，如 1.5e+3
+    fn exponential(&mut self, directive: &Directive, values: &[Option<Value>], args: &mut Args) -> Result<(), LispError> {
+        let width = self.width_param(directive, values, 0)?;
+        let decimals = self.width_param(directive, values, 1)?;
+        let exponent_digits = self.size_param(directive, values, 2, 1)?;
+        let overflowchar = values.get(4).copied().flatten().map(|_| self.char_param(directive, values, 4, ' ')).transpose()?;
+        let padchar = self.char_param(directive, values, 5, ' ')?;
+        let value = match self.real_argument(directive, args)? {
+            Ok(value) => value,
+            Err(other) => {
+                let text = Printer::princ(&other, &self.options);
+                self.output.push_str(&Format::pad(&text, width.unwrap_or(0), 1, 0, padchar, true));
+                return Ok(());
+            }
+        };
+        let text = match decimals {
+            Some(decimals) => format!("{:.*e}", decimals, value.abs()),
+            None => format!("{:e}", value.abs()),
+        };
+        let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+        let mantissa = if mantissa.contains('.') { mantissa.to_string() } else { format!("{}.0", mantissa) };
+        let exponent: i64 = exponent.parse().unwrap_or(0);
+        let body = format!(
+            "{}e{}{:0>width$}",
+            mantissa,
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs(),
+            width = exponent_digits
+        );
+        self.finish_number(directive, value < 0.0, body, width, overflowchar, padchar);
+        Ok(())
+    }
+
+    // ~{...~}：对列表的每个元素重复处理循环体；: 表示元素本身是一组实参，@ 表示直接使用剩余的实参
+    fn iteration(&mut self, directive: &Directive, values: &[Option<Value>], args: &mut Args) -> Result<(), LispError> {
+        let max_iterations = self.count_param(directive, values, 0, usize::MAX)?;
+        let mut own_args;
+        let items: &mut Args = if directive.at {
+            args
+        } else {
+            match self.next_arg(directive, args)? {
+                Expr::List(items) => {
+                    own_args = Args::new(items);
+                    &mut own_args
+                }
+                other => return Err(self.error(directive, &format!("~{{ requires a list argument, got {}", other))),
+            }
+        };
+        let mut iterations = 0;
+        while iterations < max_iterations && items.remaining() > 0 {
+            iterations += 1;
+            if directive.colon {
+                let sublist = match self.next_arg(directive, items)? {
+                    Expr::List(sublist) => sublist,
+                    other => return Err(self.error(directive, &format!("~:{{ requires a list of lists, got element {}", other))),
+                };
+                // ~^ 只结束这一次迭代
+                self.run(&directive.body, &mut Args::new(sublist))?;
+            } else {
+                let before = items.pos;
+                if self.run(&directive.body, items)? == Flow::Escape {
+                    break;
+                }
+                // 循环体不消耗实参时会无限循环，只执行一次
+                if items.pos == before {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // ~[...~;...~]：按实参（或前缀参数）的值选择子句；~:[假~;真~] 按真假选择；~@[...~] 在实参为真时处理唯一的子句
+    fn conditional(&mut self, directive: &Directive, values: &[Option<Value>], args: &mut Args) -> Result<Flow, LispError> {
+        if directive.at {
+            if directive.clauses.len() != 1 {
+                return Err(self.error(directive, "~@[ must have exactly one clause"));
+            }
+            let test = args.items.get(args.pos).cloned().ok_or_else(|| self.error(directive, "not enough arguments for ~["))?;
+            if test.is_truthy() {
+                return self.run(&directive.clauses[0], args);
+            }
+            args.pos += 1;
+            return Ok(Flow::Continue);
+        }
+        if directive.colon {
+            if directive.clauses.len() != 2 {
+                return Err(self.error(directive, "~:[ must have exactly two clauses"));
+            }
+            let test = self.next_arg(directive, args)?;
+            let clause = if test.is_truthy() { 1 } else { 0 };
+            return self.run(&directive.clauses[clause], args);
+        }
+        let index = match self.int_param(directive, values, 0)? {
+            Some(n) => n,
+            None => match self.next_arg(directive, args)? {
+                Expr::Number(n) => n,
+                other => return Err(self.error(directive, &format!("~[ requires an integer argument, got {}", other))),
+            },
+        };
+        let clause = usize::try_from(index)
+            .ok()
+            .filter(|&i| i < directive.clauses.len() && !(directive.default_clause && i == directive.clauses.len() - 1))
+            .or_else(|| directive.default_clause.then(|| directive.clauses.len() - 1));
+        match clause {
+            Some(clause) => self.run(&directive.clauses[clause], args),
+            None => Ok(Flow::Continue),
+        }
+    }
+}
+
+pub struct Format;
+
+impl Format {
+    // 格式错误指出出错指令在控制串中的位置
+    fn error(control: &str, index: usize, message: &str) -> LispError {
+        LispError::new(&format!("format: {} at index {} of control string \"{}\"", message, index, control))
+    }
+
+    // ~A 的填充规则：至少填充 minpad 个字符，之后每次增加 colinc 个，直到宽度不小于 mincol
+    fn pad(text: &str, mincol: usize, colinc: usize, minpad: usize, padchar: char, left: bool) -> String {
+        let length = text.chars().count();
+        let mut padding = minpad;
+        while length + padding < mincol {
+            padding += colinc;
+        }
+        let fill = padchar.to_string().repeat(padding);
+        if left {
+            format!("{}{}", fill, text)
+        } else {
+            format!("{}{}", text, fill)
+        }
+    }
+
+    fn group_digits(digits: &str, separator: char, interval: usize) -> String {
+        let chars: Vec<char> = digits.chars().collect();
+        let mut result = String::new();
+        for (i, ch) in chars.iter().enumerate() {
+            if i > 0 && (chars.len() - i).is_multiple_of(interval) {
+                result.push(separator);
+            }
+            result.push(*ch);
+        }
+        result
+    }
+
+    // 按控制串格式化实参；started_at_line_start 是目的地当前是否位于行首
+    pub fn format_to_string(control: &str, args: Vec<Expr>, options: PrintOptions, started_at_line_start: bool) -> Result<String, LispError> {
+        let nodes = ControlParser::new(control).parse()?;
+        let mut formatter = Formatter { control, options, output: String::new(), started_at_line_start };
+        formatter.run(&nodes, &mut Args::new(args))?;
+        Ok(formatter.output)
+    }
+
+    // (format destination control-string args...)：destination 为 nil 时返回字符串，为 t 时写到 *standard-output*，也可以是一个流
+    pub fn eval_format(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new("format expects a destination and a control string"));
+        }
+        let destination = Evaluator::eval(&args[0], env)?;
+        let control = match Evaluator::eval(&args[1], env)? {
+            Expr::Str(control) => control,
+            other => return Err(LispError::new(&format!("format: control string must be a string, got {}", other))),
+        };
+        let values = args[2..].iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        let options = PrintOptions::from_env(env);
+
+        let stream = match &destination {
+            value if !value.is_truthy() => {
+                return Ok(Expr::Str(Format::format_to_string(&control, values, options, true)?));
+            }
+            Expr::Stream(stream) => stream.clone(),
+            value if *value == Expr::t() => match env.get_symbol("*standard-output*") {
+                Some(Expr::Stream(stream)) => stream.clone(),
+                _ => return Err(LispError::new("format: *standard-output* is not a stream")),
+            },
+            other => return Err(LispError::new(&format!("format: destination must be nil, t or a stream, got {}", other))),
+        };
+        let at_line_start = stream.borrow().at_line_start();
+        let text = Format::format_to_string(&control, values, options, at_line_start)?;
+        stream.borrow_mut().write_str(&text);
+        Ok(Expr::nil())
+    }
+
+    pub fn eval_make_string_output_stream(args: &[Expr], _env: &mut Environment) -> Result<Expr, LispError> {
+        if !args.is_empty() {
+            return Err(LispError::new("make-string-output-stream expects no arguments"));
+        }
+        Ok(Expr::Stream(Rc::new(RefCell::new(OutputStream::string()))))
+    }
+
+    // 取走字符串输出流中累积的内容
+    pub fn eval_get_output_stream_string(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("get-output-stream-string expects exactly one argument"));
+        }
+        match Evaluator::eval(&args[0], env)? {
+            Expr::Stream(stream) => stream
+                .borrow_mut()
+                .take_string()
+                .map(Expr::Str)
+                .ok_or_else(|| LispError::new("get-output-stream-string: argument must be a string output stream")),
+            other => Err(LispError::new(&format!("get-output-stream-string: argument must be a stream, got {}", other))),
+        }
+    }
+
+    // (with-output-to-string (var) body...)：把 var 绑定到新的字符串输出流，返回主体写入的全部内容
+    pub fn eval_with_output_to_string(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let var = match args.first() {
+            Some(Expr::List(spec)) if spec.len() == 1 => match &spec[0] {
+                Expr::Symbol(name) => name.clone(),
+                other => return Err(LispError::new(&format!("with-output-to-string: variable must be a symbol, got {}", other))),
+            },
+            _ => return Err(LispError::new("with-output-to-string requires a (var) specification")),
+        };
+        let stream = Rc::new(RefCell::new(OutputStream::string()));
+        let mut local_env = env.clone();
+        local_env.set_symbol(var, Expr::Stream(stream.clone()));
+        for form in &args[1..] {
+            Evaluator::eval(form, &mut local_env)?;
+        }
+        let text = stream.borrow_mut().take_string().unwrap_or_default();
+        Ok(Expr::Str(text))
+    }
+}
+
+pub fn register_format_operators() {
+    OperatorRegistry::register("format", Format::eval_format);
+    OperatorRegistry::register("make-string-output-stream", Format::eval_make_string_output_stream);
+    OperatorRegistry::register("get-output-stream-string", Format::eval_get_output_stream_string);
+    OperatorRegistry::register("with-output-to-string", Format::eval_with_output_to_string);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::eval_str;
+
+    // 用 nil 作为目的地，返回格式化得到的字符串
+    fn format(input: &str) -> String {
+        let mut env = Environment::initialize();
+        match eval_str(input, &mut env) {
+            Ok(Expr::Str(s)) => s,
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    fn format_error(input: &str) -> LispError {
+        let mut env = Environment::initialize();
+        eval_str(input, &mut env).unwrap_err()
+    }
+
+    #[test]
+    fn test_aesthetic_and_standard() {
+        assert_eq!(format("(format nil \"Hello, ~a!\" \"world\")"), "Hello, world!");
+        assert_eq!(format("(format nil \"~s\" \"world\")"), "\"world\"");
+        assert_eq!(format("(format nil \"~a ~s\" #\\x #\\x)"), "x #\\x");
+        assert_eq!(format("(format nil \"~a\" '(1 \"two\" 3/4))"), "(1 two 3/4)");
+        assert_eq!(format("(format nil \"[~5a]\" \"ab\")"), "[ab   ]");
+        assert_eq!(format("(format nil \"[~5@a]\" \"ab\")"), "[   ab]");
+        assert_eq!(format("(format nil \"[~5,,,'*a]\" \"ab\")"), "[ab***]");
+        assert_eq!(format("(format nil \"[~4,3a]\" \"ab\")"), "[ab   ]");
+        assert_eq!(format("(format nil \"[~v@a]\" 4 1)"), "[   1]");
+        assert_eq!(format("(format nil \"~~ ~3~\")"), "~ ~~~");
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(format("(format nil \"~d\" 42)"), "42");
+        assert_eq!(format("(format nil \"~@d ~@d\" 42 -42)"), "+42 -42");
+        assert_eq!(format("(format nil \"~5d|~5,'0d\" 42 42)"), "   42|00042");
+        assert_eq!(format("(format nil \"~:d\" 1234567)"), "1,234,567");
+        assert_eq!(format("(format nil \"~,,'.,4:d\" 1234567)"), "123.4567");
+        assert_eq!(format("(format nil \"~b ~o ~x\" 5 8 255)"), "101 10 FF");
+        assert_eq!(format("(format nil \"~x\" -255)"), "-FF");
+        assert_eq!(format("(format nil \"~d\" 123456789012345678901234567890)"), "123456789012345678901234567890");
+        assert_eq!(format("(format nil \"~d\" \"n/a\")"), "n/a");
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(format("(format nil \"~f\" 3.5)"), "3.5");
+        assert_eq!(format("(format nil \"~f\" 1500)"), "1500.0");
+        assert_eq!(format("(format nil \"~,2f\" 3.14159)"), "3.14");
+        assert_eq!(format("(format nil \"~8,2f|\" -3.14159)"), "   -3.14|");
+        assert_eq!(format("(format nil \"~5f\" 3.14159)"), "3.142");
+        assert_eq!(format("(format nil \"~,1@f\" 2)"), "+2.0");
+        assert_eq!(format("(format nil \"~,2f\" 1/3)"), "0.33");
+        assert_eq!(format("(format nil \"~3,1,,'*f\" 1234.5)"), "***");
+        assert_eq!(format("(format nil \"~e\" 1500.0)"), "1.5e+3");
+        assert_eq!(format("(format nil \"~,2e\" 0.000123)"), "1.23e-4");
+        assert_eq!(format("(format nil \"~,1,2e\" -1500)"), "-1.5e+03");
+        assert_eq!(format("(format nil \"~10,2e|\" 1500)"), "   1.50e+3|");
+    }
+
+    #[test]
+    fn test_newlines() {
+        assert_eq!(format("(format nil \"a~%b~2%c\")"), "a\nb\n\nc");
+        assert_eq!(format("(format nil \"~&a~&~&b~%~&c\")"), "a\nb\nc");
+        assert_eq!(format("(format nil \"a~&~2&b\")"), "a\n\nb");
+        assert_eq!(format("(format nil \"one ~\n          two\")"), "one two");
+    }
+
+    #[test]
+    fn test_iteration() {
+        assert_eq!(format("(format nil \"~{~a~^, ~}\" '(1 2 3))"), "1, 2, 3");
+        assert_eq!(format("(format nil \"~{~a=~a~^ ~}\" '(a 1 b 2))"), "a=1 b=2");
+        assert_eq!(format("(format nil \"~:{<~a ~a>~}\" '((a 1) (b 2)))"), "<a 1><b 2>");
+        assert_eq!(format("(format nil \"~@{~a~^-~}\" 1 2 3)"), "1-2-3");
+        assert_eq!(format("(format nil \"~2{~a~}\" '(1 2 3))"), "12");
+        assert_eq!(format("(format nil \"~{~a~}\" '())"), "");
+        assert_eq!(format("(format nil \"~a~^ and ~a\" 1)"), "1");
+    }
+
+    #[test]
+    fn test_conditionals_and_jumps() {
+        assert_eq!(format("(format nil \"~[zero~;one~;two~]\" 1)"), "one");
+        assert_eq!(format("(format nil \"~[zero~;one~:;many~]\" 7)"), "many");
+        assert_eq!(format("(format nil \"~[zero~;one~]\" 5)"), "");
+        assert_eq!(format("(format nil \"~1[zero~;one~]\")"), "one");
+        assert_eq!(format("(format nil \"~:[no~;yes~]\" nil)"), "no");
+        assert_eq!(format("(format nil \"~:[no~;yes~]\" 0)"), "yes");
+        assert_eq!(format("(format nil \"~@[x=~a~]|\" 5)"), "x=5|");
+        assert_eq!(format("(format nil \"~@[x=~a~]|\" nil)"), "|");
+        assert_eq!(format("(format nil \"~a ~* ~a\" 1 2 3)"), "1  3");
+        assert_eq!(format("(format nil \"~a ~:*~a\" 1)"), "1 1");
+        assert_eq!(format("(format nil \"~a ~a ~0@*~a\" 1 2)"), "1 2 1");
+    }
+
+    #[test]
+    fn test_destinations() {
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(with-output-to-string (s) (format s \"a~%\") (format s \"~&b~d\" 1))", &mut env),
+            Ok(Expr::Str("a\nb1".to_string()))
+        );
+        eval_str("(setf out (make-string-output-stream))", &mut env).unwrap();
+        assert_eq!(eval_str("(format out \"x=~a\" 1)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(get-output-stream-string out)", &mut env), Ok(Expr::Str("x=1".to_string())));
+        assert_eq!(eval_str("(get-output-stream-string out)", &mut env), Ok(Expr::Str(String::new())));
+
+        // format t 写到 *standard-output*，把它绑定到字符串流即可捕获输出
+        eval_str("(setf *standard-output* (make-string-output-stream))", &mut env).unwrap();
+        assert_eq!(eval_str("(format t \"~a\" 42)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(get-output-stream-string *standard-output*)", &mut env), Ok(Expr::Str("42".to_string())));
+
+        assert_eq!(
+            eval_str("(format 3 \"x\")", &mut env),
+            Err(LispError::new("format: destination must be nil, t or a stream, got 3"))
+        );
+    }
+
+    #[test]
+    fn test_errors_point_into_control_string() {
+        assert_eq!(
+            format_error("(format nil \"abc ~q\")"),
+            LispError::new("format: unknown directive ~q at index 4 of control string \"abc ~q\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~a ~a\" 1)"),
+            LispError::new("format: not enough arguments for ~a at index 3 of control string \"~a ~a\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"x ~{~a\" '(1))"),
+            LispError::new("format: ~{ has no matching ~} at index 2 of control string \"x ~{~a\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~a~]\" 1)"),
+            LispError::new("format: ~] without a matching opening directive at index 2 of control string \"~a~]\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~5\")"),
+            LispError::new("format: control string ends in the middle of a directive at index 0 of control string \"~5\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~{~a~}\" 5)"),
+            LispError::new("format: ~{ requires a list argument, got 5 at index 0 of control string \"~{~a~}\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~c\" \"a\")"),
+            LispError::new("format: ~c requires a character, got \"a\" at index 0 of control string \"~c\"")
+        );
+
+        // 过大的列宽和重复次数会耗尽内存，直接报错
+        assert_eq!(
+            format_error("(format nil \"x ~100000000000d\" 1)"),
+            LispError::new("format: parameter 1 of ~d must not exceed 65536, got 100000000000 at index 2 of control string \"x ~100000000000d\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~v%\" 100000000)"),
+            LispError::new("format: parameter 1 of ~% must not exceed 65536, got 100000000 at index 0 of control string \"~v%\"")
+        );
+        assert_eq!(
+            format_error("(format nil \"~,100000000f\" 1.5)"),
+            LispError::new("format: parameter 2 of ~f must not exceed 65536, got 100000000 at index 0 of control string \"~,100000000f\"")
+        );
+        for control in ["~1000000a", "~1,1000000a", "~1000000~", "~1000000&", "~1000000e", "~,,1000000e"] {
+            assert!(format_error(&format!("(format nil \"{}\" 1)", control)).to_string().contains("must not exceed 65536"));
+        }
+    }
+}
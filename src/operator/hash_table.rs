@@ -0,0 +1,262 @@
+// operator/hash_table.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::lambda::{eval_keyword_arguments, Lambda};
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::hash_table::{HashTable, HashTest};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct HashTableOps;
+
+impl HashTableOps {
+    fn table_argument(name: &str, value: &Expr) -> Result<Rc<RefCell<HashTable>>, LispError> {
+        match value {
+            Expr::HashTable(table) => Ok(table.clone()),
+            other => Err(LispError::new(&format!("{}: {} is not a hash table", name, other))),
+        }
+    }
+
+    // (make-hash-table &key test size)：test 默认为 eql，size 只是为了兼容而接受
+    pub fn eval_make_hash_table(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let options = eval_keyword_arguments("make-hash-table", args, env, &["test", "size"])?;
+        let mut test = HashTest::Eql;
+        for (keyword, value) in options {
+            if keyword == "test" {
+                test = match &value {
+                    Expr::Symbol(name) => HashTest::from_name(name),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    LispError::new(&format!("make-hash-table: :test must be one of eq, eql, equal or equalp, got {}", value))
+                })?;
+            }
+        }
+        Ok(Expr::HashTable(Rc::new(RefCell::new(HashTable::new(test)))))
+    }
+
+    // (gethash key table [default])：返回两个值，值（找不到时为 default）和是否找到
+    pub fn eval_gethash(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("gethash", args, 2, 3)?;
+        let key = Evaluator::eval(&args[0], env)?;
+        let table = HashTableOps::table_argument("gethash", &Evaluator::eval(&args[1], env)?)?;
+        let default = match args.get(2) {
+            Some(arg) => Evaluator::eval(arg, env)?,
+            None => Expr::nil(),
+        };
+        let found = table.borrow().get(&key).cloned();
+        Ok(match found {
+            Some(value) => env.set_values(vec![value, Expr::t()]),
+            None => env.set_values(vec![default, Expr::nil()]),
+        })
+    }
+
+    // (setf (gethash key table [default]) value)
+    pub fn setf_gethash(args: &[Expr], value: Expr, _env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf gethash)", args, 2, 3)?;
+        let table = HashTableOps::table_argument("(setf gethash)", &args[1])?;
+        table.borrow_mut().insert(args[0].clone(), value.clone());
+        Ok(value)
+    }
+
+    // (remhash key table)：删除了条目时返回 t
+    pub fn eval_remhash(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("remhash", args, 2, 2)?;
+        let key = Evaluator::eval(&args[0], env)?;
+        let table = HashTableOps::table_argument("remhash", &Evaluator::eval(&args[1], env)?)?;
+        let removed = table.borrow_mut().remove(&key);
+        Ok(Expr::from_bool(removed))
+    }
+
+    pub fn eval_clrhash(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("clrhash", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        HashTableOps::table_argument("clrhash", &value)?.borrow_mut().clear();
+        Ok(value)
+    }
+
+    // (maphash function table)：按插入顺序对每个条目调用 (function key value)，返回 nil。
+    // 遍历的是调用前条目的快照，函数中修改哈希表不会影响这次遍历
+    pub fn eval_maphash(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("maphash", args, 2, 2)?;
+        let function = Evaluator::eval(&args[0], env)?;
+        let table = HashTableOps::table_argument("maphash", &Evaluator::eval(&args[1], env)?)?;
+        let entries = table.borrow().entries().cloned().collect::<Vec<_>>();
+        for (key, value) in entries {
+            Lambda::funcall(&function, vec![key, value], env)?;
+        }
+        Ok(Expr::nil())
+    }
+
+    pub fn eval_hash_table_count(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("hash-table-count", args, 1, 1)?;
+        let table = HashTableOps::table_argument("hash-table-count", &Evaluator::eval(&args[0], env)?)?;
+        let count = table.borrow().len();
+        Ok(Expr::Number(count as i64))
+    }
+
+    pub fn eval_hash_table_test(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("hash-table-test", args, 1, 1)?;
+        let table = HashTableOps::table_argument("hash-table-test", &Evaluator::eval(&args[0], env)?)?;
+        let test = table.borrow().test();
+        Ok(Expr::Symbol(test.name().to_string()))
+    }
+
+    pub fn eval_hash_table_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("hash-table-p", args, 1, 1)?;
+        Ok(Expr::from_bool(matches!(Evaluator::eval(&args[0], env)?, Expr::HashTable(_))))
+    }
+
+    // 按插入顺序返回全部键
+    pub fn eval_hash_table_keys(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("hash-table-keys", args, 1, 1)?;
+        let table = HashTableOps::table_argument("hash-table-keys", &Evaluator::eval(&args[0], env)?)?;
+        let keys = table.borrow().entries().map(|(key, _)| key.clone()).collect();
+        Ok(Expr::List(keys))
+    }
+
+    // 按插入顺序返回全部值
+    pub fn eval_hash_table_values(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("hash-table-values", args, 1, 1)?;
+        let table = HashTableOps::table_argument("hash-table-values", &Evaluator::eval(&args[0], env)?)?;
+        let values = table.borrow().entries().map(|(_, value)| value.clone()).collect();
+        Ok(Expr::List(values))
+    }
+}
+
+pub fn register_hash_table_operators() {
+    OperatorRegistry::register("make-hash-table", HashTableOps::eval_make_hash_table);
+    OperatorRegistry::register("gethash", HashTableOps::eval_gethash);
+    OperatorRegistry::register("remhash", HashTableOps::eval_remhash);
+    OperatorRegistry::register("clrhash", HashTableOps::eval_clrhash);
+    OperatorRegistry::register("maphash", HashTableOps::eval_maphash);
+    OperatorRegistry::register("hash-table-count", HashTableOps::eval_hash_table_count);
+    OperatorRegistry::register("hash-table-test", HashTableOps::eval_hash_table_test);
+    OperatorRegistry::register("hash-table-p", HashTableOps::eval_hash_table_p);
+    OperatorRegistry::register("hash-table-keys", HashTableOps::eval_hash_table_keys);
+    OperatorRegistry::register("hash-table-values", HashTableOps::eval_hash_table_values);
+    OperatorRegistry::register_setf("gethash", HashTableOps::setf_gethash);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    #[test]
+    fn test_gethash_and_setf() {
+        let mut env = Environment::initialize();
+        eval_str("(setf table (make-hash-table :test 'equal))", &mut env).unwrap();
+        assert_eq!(eval_to_string("table", &mut env), "#<hash-table :test equal :count 0>");
+        assert_eq!(eval_str("(setf (gethash \"one\" table) 1)", &mut env), Ok(Expr::Number(1)));
+        eval_str("(setf (gethash '(a b) table) 'list)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(multiple-value-list (gethash \"one\" table))", &mut env), "(1 t)");
+        assert_eq!(eval_to_string("(multiple-value-list (gethash '(a b) table))", &mut env), "(list t)");
+        assert_eq!(eval_to_string("(multiple-value-list (gethash \"two\" table))", &mut env), "(() ())");
+        assert_eq!(eval_to_string("(multiple-value-list (gethash \"two\" table 0))", &mut env), "(0 ())");
+        // 值为 nil 的条目也能通过第二个值与缺失区分开
+        eval_str("(setf (gethash 'empty table) nil)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(multiple-value-list (gethash 'empty table))", &mut env), "(() t)");
+        assert_eq!(eval_str("(hash-table-count table)", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_to_string("table", &mut env), "#<hash-table :test equal :count 3>");
+    }
+
+    #[test]
+    fn test_tests_decide_key_identity() {
+        let mut env = Environment::initialize();
+        eval_str("(setf eql-table (make-hash-table))", &mut env).unwrap();
+        eval_str("(setf (gethash \"k\" eql-table) 1)", &mut env).unwrap();
+        eval_str("(setf (gethash 1 eql-table) 'int)", &mut env).unwrap();
+        assert_eq!(eval_str("(gethash \"k\" eql-table)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(gethash 1.0 eql-table)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("(hash-table-test eql-table)", &mut env), "eql");
+
+        eval_str("(setf loose (make-hash-table :test 'equalp))", &mut env).unwrap();
+        eval_str("(setf (gethash \"Key\" loose) 1)", &mut env).unwrap();
+        eval_str("(setf (gethash 2 loose) 'two)", &mut env).unwrap();
+        assert_eq!(eval_str("(gethash \"KEY\" loose)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_to_string("(gethash 2.0 loose)", &mut env), "two");
+        eval_str("(setf (gethash \"kEY\" loose) 3)", &mut env).unwrap();
+        assert_eq!(eval_str("(hash-table-count loose)", &mut env), Ok(Expr::Number(2)));
+    }
+
+    #[test]
+    fn test_variable_keys_find_their_entries() {
+        let mut env = Environment::initialize();
+        eval_str("(setf h (make-hash-table :test 'equal))", &mut env).unwrap();
+        eval_str("(setf s \"k\")", &mut env).unwrap();
+        eval_str("(setf (gethash s h) 1)", &mut env).unwrap();
+        eval_str("(setf (gethash s h) 2)", &mut env).unwrap();
+        assert_eq!(eval_str("(hash-table-count h)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(gethash s h)", &mut env), Ok(Expr::Number(2)));
+
+        // eq 表只认同一个对象：数组有同一性，字符串在求值时被复制，每次都是新的键
+        eval_str("(setf hk (make-hash-table :test 'eq))", &mut env).unwrap();
+        eval_str("(setf key (vector 1 2))", &mut env).unwrap();
+        eval_str("(setf (gethash key hk) 'found)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(gethash key hk)", &mut env), "found");
+        assert_eq!(eval_str("(gethash (vector 1 2) hk)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(remhash key hk)", &mut env), Ok(Expr::t()));
+        eval_str("(setf (gethash s hk) 1)", &mut env).unwrap();
+        assert_eq!(eval_str("(gethash s hk)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(hash-table-count hk)", &mut env), Ok(Expr::Number(1)));
+    }
+
+    #[test]
+    fn test_equalp_table_can_be_its_own_key() {
+        let mut env = Environment::initialize();
+        eval_str("(setf p (make-hash-table :test 'equalp))", &mut env).unwrap();
+        eval_str("(setf (gethash p p) 5)", &mut env).unwrap();
+        eval_str("(setf (gethash p p) 6)", &mut env).unwrap();
+        assert_eq!(eval_str("(hash-table-count p)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(gethash p p)", &mut env), Ok(Expr::Number(6)));
+
+        // 键是包含该表的数组，或者是另一个表
+        eval_str("(setf a (vector p))", &mut env).unwrap();
+        eval_str("(setf (gethash a p) 'array)", &mut env).unwrap();
+        eval_str("(setf (gethash (make-hash-table :test 'equalp) p) 'empty)", &mut env).unwrap();
+        assert_eq!(eval_str("(hash-table-count p)", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_to_string("(gethash a p)", &mut env), "array");
+        assert_eq!(eval_str("(remhash p p)", &mut env), Ok(Expr::t()));
+    }
+
+    #[test]
+    fn test_remhash_clrhash_and_iteration() {
+        let mut env = Environment::initialize();
+        eval_str("(setf table (make-hash-table))", &mut env).unwrap();
+        eval_str("(setf (gethash 'a table) 1)", &mut env).unwrap();
+        eval_str("(setf (gethash 'b table) 2)", &mut env).unwrap();
+        eval_str("(setf (gethash 'c table) 3)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(hash-table-keys table)", &mut env), "(a b c)");
+        assert_eq!(eval_to_string("(hash-table-values table)", &mut env), "(1 2 3)");
+        assert_eq!(eval_str("(remhash 'b table)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(remhash 'b table)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("(hash-table-keys table)", &mut env), "(a c)");
+
+        // maphash 调用的函数可以修改共享的哈希表
+        eval_str("(setf doubled (make-hash-table))", &mut env).unwrap();
+        eval_str("(maphash (lambda (k v) (setf (gethash k doubled) (* v 2))) table)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(hash-table-values doubled)", &mut env), "(2 6)");
+        eval_str("(defun show (k v) (setf (gethash v doubled) k))", &mut env).unwrap();
+        assert_eq!(eval_str("(maphash 'show table)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("(gethash 3 doubled)", &mut env), "c");
+
+        assert_eq!(eval_to_string("(clrhash table)", &mut env), "#<hash-table :test eql :count 0>");
+        assert_eq!(eval_str("(hash-table-p table)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(hash-table-p '(1))", &mut env), Ok(Expr::nil()));
+    }
+
+    #[test]
+    fn test_hash_table_errors() {
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(make-hash-table :test 'string=)", &mut env),
+            Err(LispError::new("make-hash-table: :test must be one of eq, eql, equal or equalp, got string="))
+        );
+        assert_eq!(eval_str("(gethash 'a '(1))", &mut env), Err(LispError::new("gethash: (1) is not a hash table")));
+        assert_eq!(eval_str("(gethash 'a)", &mut env), Err(LispError::new("gethash expects 2 to 3 arguments")));
+    }
+}
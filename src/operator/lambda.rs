@@ -3,8 +3,276 @@ use crate::operator::OperatorRegistry;
 use crate::environment::Environment;
 use crate::exception::LispError;
 use crate::expression::Expr;
+use crate::package;
 use crate::Evaluator;
 
+// &optional 和 &key 形参：变量名、默认值形式和可选的 supplied-p 变量
+struct OptionalParam {
+    name: String,
+    default: Expr,
+    supplied: Option<String>,
+}
+
+struct KeyParam {
+    keyword: String,
+    param: OptionalParam,
+}
+
+// 解析后的普通 lambda 列表：
+// (req... &optional opt... &rest r &key key... &allow-other-keys &aux aux...)
+struct LambdaList {
+    shape: String,
+    required: Vec<String>,
+    optional: Vec<OptionalParam>,
+    rest: Option<String>,
+    key: Vec<KeyParam>,
+    has_key: bool,
+    allow_other_keys: bool,
+    aux: Vec<(String, Expr)>,
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Section {
+    Required,
+    Optional,
+    Rest,
+    Key,
+    AllowOtherKeys,
+    Aux,
+}
+
+// 关键字实参的名字（不含冒号）
+fn keyword_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Symbol(s) if expr.is_keyword() => Some(&s[1..]),
+        _ => None,
+    }
+}
+
+// 内置操作符的关键字实参：按出现顺序返回 (不含冒号的名字, 求值后的值)。
+// 关键字位置上可以直接写 :start，也可以写求值得到关键字的形式。
+pub fn eval_keyword_arguments(name: &str, args: &[Expr], env: &mut Environment, allowed: &[&str]) -> Result<Vec<(String, Expr)>, LispError> {
+    if !args.len().is_multiple_of(2) {
+        return Err(LispError::new(&format!("{}: odd number of keyword arguments", name)));
+    }
+    let mut pairs = Vec::new();
+    for pair in args.chunks(2) {
+        let keyword = match keyword_name(&pair[0]) {
+            Some(keyword) => keyword.to_string(),
+            None => {
+                let value = Evaluator::eval(&pair[0], env)?;
+                keyword_name(&value)
+                    .map(str::to_string)
+                    .ok_or_else(|| LispError::new(&format!("{}: {} is not a keyword", name, value)))?
+            }
+        };
+        if !allowed.contains(&keyword.as_str()) {
+            return Err(LispError::new(&format!("{}: unknown keyword argument :{}", name, keyword)));
+        }
+        pairs.push((keyword, Evaluator::eval(&pair[1], env)?));
+    }
+    Ok(pairs)
+}
+
+fn param_name(expr: &Expr) -> Result<String, LispError> {
+    match expr {
+        Expr::Symbol(s) if !s.starts_with('&') && keyword_name(expr).is_none() => Ok(s.clone()),
+        _ => Err(LispError::new(&format!("Invalid parameter name: {}", expr))),
+    }
+}
+
+impl OptionalParam {
+    // var | (var) | (var default) | (var default supplied-p)
+    fn parse(spec: &Expr) -> Result<(Expr, OptionalParam), LispError> {
+        match spec {
+            Expr::List(parts) if !parts.is_empty() && parts.len() <= 3 => {
+                let supplied = match parts.get(2) {
+                    Some(p) => Some(param_name(p)?),
+                    None => None,
+                };
+                let default = parts.get(1).cloned().unwrap_or(Expr::List(vec![]));
+                Ok((parts[0].clone(), OptionalParam { name: String::new(), default, supplied }))
+            }
+            Expr::Symbol(_) => Ok((spec.clone(), OptionalParam { name: String::new(), default: Expr::List(vec![]), supplied: None })),
+            _ => Err(LispError::new(&format!("Invalid parameter specifier: {}", spec))),
+        }
+    }
+
+    fn bind(&self, value: Option<Expr>, env: &mut Environment) -> Result<(), LispError> {
+        let supplied = value.is_some();
+        let value = match value {
+            Some(value) => value,
+            // 默认值在已绑定前面形参的局部环境中求值
+            None => Evaluator::eval(&self.default, env)?,
+        };
+        env.set_symbol(self.name.clone(), value);
+        if let Some(flag) = &self.supplied {
+            env.set_symbol(flag.clone(), Expr::from_bool(supplied));
+        }
+        Ok(())
+    }
+}
+
+impl LambdaList {
+    fn parse(params: &[Expr]) -> Result<LambdaList, LispError> {
+        let mut list = LambdaList {
+            shape: Expr::List(params.to_vec()).to_string(),
+            required: Vec::new(),
+            optional: Vec::new(),
+            rest: None,
+            key: Vec::new(),
+            has_key: false,
+            allow_other_keys: false,
+            aux: Vec::new(),
+        };
+        let mut section = Section::Required;
+
+        for param in params {
+            if let Expr::Symbol(s) = param {
+                let next = match s.as_str() {
+                    "&optional" => Some(Section::Optional),
+                    "&rest" | "&body" => Some(Section::Rest),
+                    "&key" => Some(Section::Key),
+                    "&allow-other-keys" => Some(Section::AllowOtherKeys),
+                    "&aux" => Some(Section::Aux),
+                    _ if s.starts_with('&') => {
+                        return Err(LispError::new(&format!("Unknown lambda list keyword: {}", s)));
+                    }
+                    _ => None,
+                };
+                if let Some(next) = next {
+                    if next <= section || (section == Section::Rest && list.rest.is_none()) {
+                        return Err(LispError::new(&format!("Misplaced {} in lambda list {}", s, list.shape)));
+                    }
+                    if next == Section::AllowOtherKeys && section != Section::Key {
+                        return Err(LispError::new("&allow-other-keys must follow &key"));
+                    }
+                    list.has_key |= next == Section::Key;
+                    list.allow_other_keys |= next == Section::AllowOtherKeys;
+                    section = next;
+                    continue;
+                }
+            }
+
+            match section {
+                Section::Required => list.required.push(param_name(param)?),
+                Section::Optional => {
+                    let (name, mut optional) = OptionalParam::parse(param)?;
+                    optional.name = param_name(&name)?;
+                    list.optional.push(optional);
+                }
+                Section::Rest => {
+                    if list.rest.is_some() {
+                        return Err(LispError::new("&rest accepts exactly one variable"));
+                    }
+                    list.rest = Some(param_name(param)?);
+                }
+                Section::Key => {
+                    let (name, mut optional) = OptionalParam::parse(param)?;
+                    // ((:keyword var) default) 允许关键字与变量名不同
+                    let (keyword, var) = match &name {
+                        Expr::List(pair) if pair.len() == 2 => match keyword_name(&pair[0]) {
+                            Some(keyword) => (keyword.to_string(), param_name(&pair[1])?),
+                            None => return Err(LispError::new(&format!("Invalid &key specifier: {}", param))),
+                        },
+                        _ => {
+                            let var = param_name(&name)?;
+                            (package::symbol_name(&var).to_string(), var)
+                        }
+                    };
+                    optional.name = var;
+                    list.key.push(KeyParam { keyword, param: optional });
+                }
+                Section::AllowOtherKeys => {
+                    return Err(LispError::new("&allow-other-keys must be followed by &aux or nothing"));
+                }
+                Section::Aux => match param {
+                    Expr::List(parts) if !parts.is_empty() && parts.len() <= 2 => {
+                        let init = parts.get(1).cloned().unwrap_or(Expr::List(vec![]));
+                        list.aux.push((param_name(&parts[0])?, init));
+                    }
+                    _ => list.aux.push((param_name(param)?, Expr::List(vec![]))),
+                },
+            }
+        }
+
+        if section == Section::Rest && list.rest.is_none() {
+            return Err(LispError::new("&rest must be followed by a variable"));
+        }
+        Ok(list)
+    }
+
+    fn arity_error(&self, name: &str, count: usize) -> LispError {
+        let min = self.required.len();
+        let expected = if self.rest.is_some() || self.has_key {
+            format!("at least {}", min)
+        } else if self.optional.is_empty() {
+            format!("{}", min)
+        } else {
+            format!("{} to {}", min, min + self.optional.len())
+        };
+        LispError::new(&format!(
+            "{}: expected {} argument(s) for lambda list {}, got {}",
+            name, expected, self.shape, count
+        ))
+    }
+
+    fn bind(&self, name: &str, values: Vec<Expr>, env: &mut Environment) -> Result<(), LispError> {
+        let positional = self.required.len() + self.optional.len();
+        if values.len() < self.required.len()
+            || (values.len() > positional && self.rest.is_none() && !self.has_key)
+        {
+            return Err(self.arity_error(name, values.len()));
+        }
+
+        let mut values = values.into_iter();
+        for param in &self.required {
+            env.set_symbol(param.clone(), values.next().unwrap());
+        }
+        for param in &self.optional {
+            param.bind(values.next(), env)?;
+        }
+
+        let remaining: Vec<Expr> = values.collect();
+        if let Some(rest) = &self.rest {
+            env.set_symbol(rest.clone(), Expr::List(remaining.clone()));
+        }
+
+        if self.has_key {
+            if !remaining.len().is_multiple_of(2) {
+                return Err(LispError::new(&format!("{}: odd number of keyword arguments", name)));
+            }
+            let pairs: Vec<(&Expr, &Expr)> = remaining.chunks(2).map(|pair| (&pair[0], &pair[1])).collect();
+            let allow_other_keys = self.allow_other_keys
+                || pairs.iter().any(|(k, v)| keyword_name(k) == Some("allow-other-keys") && v.is_truthy());
+            for (keyword, _) in &pairs {
+                let key = keyword_name(keyword)
+                    .ok_or_else(|| LispError::new(&format!("{}: {} is not a keyword", name, keyword)))?;
+                if !allow_other_keys && key != "allow-other-keys" && !self.key.iter().any(|k| k.keyword == key) {
+                    return Err(LispError::new(&format!(
+                        "{}: unknown keyword argument :{} for lambda list {}",
+                        name, key, self.shape
+                    )));
+                }
+            }
+            for key in &self.key {
+                // 同一个关键字出现多次时以最左边的为准
+                let value = pairs
+                    .iter()
+                    .find(|(k, _)| keyword_name(k) == Some(key.keyword.as_str()))
+                    .map(|(_, v)| (*v).clone());
+                key.param.bind(value, env)?;
+            }
+        }
+
+        for (var, init) in &self.aux {
+            let value = Evaluator::eval(init, env)?;
+            env.set_symbol(var.clone(), value);
+        }
+        Ok(())
+    }
+}
+
 pub struct Lambda;
 
 impl Lambda {
@@ -18,18 +286,40 @@ impl Lambda {
             _ => return Err(LispError::new("lambda: first argument must be a list of parameters")),
         };
 
-        // 使用 progn 将多个表达式作为函数体
-        let body = if args.len() == 2 {
-            args[1].clone()
-        } else {
-            Expr::List(vec![Expr::Symbol("progn".to_string())].into_iter().chain(args[1..].iter().cloned()).collect())
-        };
+        let (_, body) = Lambda::parse_body(&args[1..]);
 
         // 返回一个 lambda 表达式，即匿名函数
         Ok(Expr::List(vec![Expr::Symbol("lambda".to_string()), Expr::List(params), body]))
     }
 
-    fn eval_progn(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+    // 拆分函数体：开头的文档字符串和 declare 形式被剥离，多个形式用 progn 包裹。
+    // 只有后面还有 declare 以外的形式时，开头的字符串才算文档字符串，否则它是函数的返回值。
+    pub fn parse_body(forms: &[Expr]) -> (Option<String>, Expr) {
+        let is_declaration = |form: &Expr| matches!(form, Expr::List(form) if form.first() == Some(&Expr::Symbol("declare".to_string())));
+        let mut docstring = None;
+        let mut index = 0;
+        while index < forms.len() {
+            match &forms[index] {
+                Expr::Str(doc) if docstring.is_none() && forms[index + 1..].iter().any(|form| !is_declaration(form)) => {
+                    docstring = Some(doc.clone())
+                }
+                form if is_declaration(form) => {}
+                _ => break,
+            }
+            index += 1;
+        }
+
+        let forms: Vec<Expr> = forms[index..].iter().filter(|form| !is_declaration(form)).cloned().collect();
+        let body = match forms.as_slice() {
+            [] => Expr::List(vec![]),
+            [form] => form.clone(),
+            // 使用 progn 将多个表达式作为函数体
+            _ => Expr::List(vec![Expr::Symbol("progn".to_string())].into_iter().chain(forms).collect()),
+        };
+        (docstring, body)
+    }
+
+    pub fn eval_progn(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
         let mut result = Expr::List(vec![]);
         for arg in args {
             result = Evaluator::eval(arg, env)?;
@@ -48,26 +338,161 @@ impl Lambda {
             return Err(LispError::new("Invalid parameter list"));
         };
 
-        if params.len() != args.len() {
-            return Err(LispError::new("Argument count does not match parameter count"));
-        }
+        let values = Lambda::eval_arguments(args, env)?;
+        Lambda::apply("lambda", params, &lambda_parts[1], values, env)
+    }
 
+    // (destructuring-bind lambda-list expression body...)：按 lambda 列表拆开 expression 的值，
+    // lambda 列表可以嵌套，也可以带点尾部，例如 ((key . value) &rest more) 或 (&key name age)
+    pub fn eval_destructuring_bind(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new("destructuring-bind requires a lambda list and an expression"));
+        }
+        let value = Evaluator::eval(&args[1], env)?;
         let mut local_env = env.clone();
-        for (param, arg) in params.iter().zip(args.iter()) {
-            if let Expr::Symbol(s) = param {
-                let value = Evaluator::eval(arg, &mut local_env)?;
-                local_env.set_symbol(s.clone(), value);
-            } else {
-                return Err(LispError::new("Invalid parameter name"));
+        Lambda::destructure(&args[0], value, &mut local_env)?;
+        let (_, body) = Lambda::parse_body(&args[2..]);
+        Evaluator::eval(&body, &mut local_env)
+    }
+
+    // 把模式改写成普通 lambda 列表：点尾部变成 &rest，必需形参位置上的嵌套模式换成占位变量
+    fn destructuring_params(pattern: &Expr) -> (Vec<Expr>, Vec<(String, Expr)>) {
+        let mut params = Vec::new();
+        let mut rest = pattern;
+        loop {
+            match rest {
+                Expr::List(items) => {
+                    params.extend(items.iter().cloned());
+                    break;
+                }
+                Expr::DottedPair(car, cdr) => {
+                    params.push((**car).clone());
+                    rest = cdr;
+                }
+                tail => {
+                    params.push(Expr::Symbol("&rest".to_string()));
+                    params.push(tail.clone());
+                    break;
+                }
+            }
+        }
+        let mut nested = Vec::new();
+        for (index, param) in params.iter_mut().enumerate() {
+            match param {
+                Expr::Symbol(s) if s.starts_with('&') => break,
+                Expr::List(_) | Expr::DottedPair(_, _) => {
+                    let placeholder = format!("%destructure-{}", index);
+                    nested.push((placeholder.clone(), std::mem::replace(param, Expr::Symbol(placeholder))));
+                }
+                _ => {}
+            }
+        }
+        (params, nested)
+    }
+
+    // 列表的元素和点尾部；正规列表的尾部是 nil，不是列表时返回 None
+    fn split_list(value: &Expr) -> Option<(Vec<Expr>, Expr)> {
+        let mut items = Vec::new();
+        let mut rest = value;
+        loop {
+            match rest {
+                Expr::List(list) => {
+                    items.extend(list.iter().cloned());
+                    return Some((items, Expr::nil()));
+                }
+                Expr::DottedPair(car, cdr) => {
+                    items.push((**car).clone());
+                    rest = cdr;
+                }
+                tail if tail.is_null() => return Some((items, Expr::nil())),
+                _ if items.is_empty() => return None,
+                tail => return Some((items, tail.clone())),
             }
         }
+    }
+
+    fn destructure(pattern: &Expr, value: Expr, env: &mut Environment) -> Result<(), LispError> {
+        if let (Expr::Symbol(_), false) = (pattern, pattern.is_null()) {
+            env.set_symbol(param_name(pattern)?, value);
+            return Ok(());
+        }
+        let pattern = if pattern.is_null() { Expr::nil() } else { pattern.clone() };
+        let mismatch = || LispError::new(&format!("destructuring-bind: {} does not match the lambda list {}", value, pattern));
+        let (items, tail) = Lambda::split_list(&value).ok_or_else(mismatch)?;
+        let (params, nested) = Lambda::destructuring_params(&pattern);
+        let mut lambda_list = LambdaList::parse(&params)?;
+        lambda_list.shape = pattern.to_string();
+        lambda_list.bind("destructuring-bind", items.clone(), env)?;
+        // 值的点尾部只能由 &rest 变量接收
+        if !tail.is_null() {
+            let rest = lambda_list.rest.as_ref().filter(|_| !lambda_list.has_key).ok_or_else(mismatch)?;
+            let positional = (lambda_list.required.len() + lambda_list.optional.len()).min(items.len());
+            let rest_value = items[positional..].iter().rev().fold(tail, |cdr, car| Expr::cons(car.clone(), cdr));
+            env.set_symbol(rest.clone(), rest_value);
+        }
+        // 先取出全部占位变量的值，再逐个拆开嵌套模式，避免内层的占位变量覆盖外层的
+        let nested: Vec<(Expr, Expr)> = nested
+            .into_iter()
+            .map(|(placeholder, pattern)| (pattern, env.remove_symbol(&placeholder).unwrap_or_else(Expr::nil)))
+            .collect();
+        for (pattern, value) in nested {
+            Lambda::destructure(&pattern, value, env)?;
+        }
+        Ok(())
+    }
+
+    // 在调用者环境中依次求值实参
+    fn eval_arguments(args: &[Expr], env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        args.iter().map(|arg| Evaluator::eval(arg, env)).collect()
+    }
+
+    // 按 lambda 列表绑定已求值的实参，然后在局部环境中求值函数体
+    pub fn apply(name: &str, params: &[Expr], body: &Expr, values: Vec<Expr>, env: &mut Environment) -> Result<Expr, LispError> {
+        let lambda_list = LambdaList::parse(params)?;
+        let mut local_env = env.clone();
+        lambda_list.bind(name, values, &mut local_env)?;
+        // 函数体中的错误在定义它的顶层形式里查找源码位置
+        let entered = env.enter_function(name);
+        let result = Evaluator::eval(body, &mut local_env);
+        if entered {
+            env.leave_function();
+        }
+        result
+    }
 
-        Evaluator::eval(&lambda_parts[1], &mut local_env)
+    // 调用一个函数对象：lambda 表达式、用户定义的函数名或内置操作符名；values 是已经求值的实参
+    pub fn funcall(function: &Expr, values: Vec<Expr>, env: &mut Environment) -> Result<Expr, LispError> {
+        match function {
+            Expr::List(parts) if parts.len() == 3 && parts[0] == Expr::Symbol("lambda".to_string()) => match &parts[1] {
+                Expr::List(params) => Lambda::apply("lambda", params, &parts[2], values, env),
+                _ => Err(LispError::new("Invalid parameter list")),
+            },
+            Expr::Symbol(name) => {
+                if let Some(Expr::List(parts)) = env.get_function(name).cloned() {
+                    return match parts.get(1) {
+                        Some(Expr::List(params)) if parts.len() == 3 => Lambda::apply(name, params, &parts[2], values, env),
+                        _ => Err(LispError::new("Invalid function definition")),
+                    };
+                }
+                match OperatorRegistry::get(name) {
+                    // 内置操作符接收未求值的实参，把已求值的实参包在 quote 里传过去
+                    Some(operator_fn) => {
+                        let quoted: Vec<Expr> = values
+                            .into_iter()
+                            .map(|value| Expr::List(vec![Expr::Symbol("quote".to_string()), value]))
+                            .collect();
+                        operator_fn(&quoted, env)
+                    }
+                    None => Err(LispError::new(&format!("Undefined function: {}", name))),
+                }
+            }
+            other => Err(LispError::new(&format!("{} is not a function", other))),
+        }
     }
 
     pub fn eval_defun(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
-        if args.len() != 3 {
-            return Err(LispError::new("defun requires exactly 3 arguments: name, params, body"));
+        if args.len() < 2 {
+            return Err(LispError::new("defun requires at least 2 arguments: name, params"));
         }
     
         let func_name = match &args[0] {
@@ -80,7 +505,11 @@ impl Lambda {
             _ => return Err(LispError::new("defun: second argument must be a list")),
         };
     
-        let body = args[2].clone();
+        let (docstring, body) = Lambda::parse_body(&args[2..]);
+        match docstring {
+            Some(doc) => env.set_documentation(&func_name, "function", doc),
+            None => env.remove_documentation(&func_name, "function"),
+        }
     
         // 使用 set_function 将函数存储在函数符号表中
         env.set_function(
@@ -94,6 +523,7 @@ impl Lambda {
     pub fn eval_function_call(func_name: &str, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
         let function = env
             .get_function(func_name)
+            .cloned()
             .ok_or_else(|| LispError::new(&format!("Undefined function: {}", func_name)))?;
     
         if let Expr::List(list) = function {
@@ -107,36 +537,100 @@ impl Lambda {
                 return Err(LispError::new("Invalid parameter list"));
             };
     
-            if params.len() != args.len() {
-                return Err(LispError::new("Argument count does not match parameter count"));
-            }
-    
-            let mut local_env = env.clone();
-            for (param, arg) in params.iter().zip(args.iter()) {
-                if let Expr::Symbol(s) = param {
-                    let value = Evaluator::eval(arg, &mut local_env)?;
-                    local_env.set_symbol(s.clone(), value);
-                } else {
-                    return Err(LispError::new("Invalid parameter name"));
-                }
-            }
-    
-            Evaluator::eval(&list[2], &mut local_env)
+            let values = Lambda::eval_arguments(args, env)?;
+            Lambda::apply(func_name, params, &list[2], values, env)
         } else {
             Err(LispError::new("Function is not defined correctly"))
         }
-    }    
+    }
+
+    // (documentation 'name 'function)：返回文档字符串，没有时返回 nil
+    pub fn eval_documentation(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("documentation requires exactly two arguments: name, doc-type"));
+        }
+
+        let name = match Evaluator::eval(&args[0], env)? {
+            Expr::Symbol(s) => s,
+            other => return Err(LispError::new(&format!("documentation: {} is not a symbol", other))),
+        };
+        let doc_type = match Evaluator::eval(&args[1], env)? {
+            Expr::Symbol(s) => s,
+            other => return Err(LispError::new(&format!("documentation: {} is not a documentation type", other))),
+        };
+
+        Ok(env
+            .get_documentation(&name, &doc_type)
+            .map(|doc| Expr::Str(doc.clone()))
+            .unwrap_or(Expr::List(vec![])))
+    }
+
+    // (describe 'name)：返回描述符号当前绑定的字符串
+    pub fn eval_describe(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("describe requires exactly one argument"));
+        }
+
+        let object = Evaluator::eval(&args[0], env)?;
+        let name = match &object {
+            Expr::Symbol(s) => s.clone(),
+            other => return Ok(Expr::Str(format!("{} is a {}", other, Lambda::describe_kind(other)))),
+        };
+
+        let mut lines = vec![format!("{} is a symbol", name)];
+        if let Some(value) = env.get_symbol(&name) {
+            lines.push(format!("  Value: {}", value));
+        }
+        if let Some(Expr::List(function)) = env.get_function(&name) {
+            lines.push(format!("  Function lambda list: {}", function[1]));
+        } else if OperatorRegistry::get(&name).is_some() {
+            lines.push("  Function: built-in operator".to_string());
+        }
+        if let Some(Expr::Macro(params, _)) = env.get_macro(&name) {
+            lines.push(format!("  Macro lambda list: {}", Expr::List(params.clone())));
+        }
+        if let Some(doc) = env.get_documentation(&name, "function") {
+            lines.push(format!("  Documentation: {}", doc));
+        }
+        Ok(Expr::Str(lines.join("\n")))
+    }
+
+    fn describe_kind(object: &Expr) -> &'static str {
+        match object {
+            Expr::Number(_) | Expr::BigInt(_) => "integer",
+            Expr::Ratio(_) => "ratio",
+            Expr::Complex(_, _) => "complex",
+            Expr::Float(_) => "float",
+            Expr::Char(_) => "character",
+            Expr::Str(_) => "string",
+            Expr::List(_) => "list",
+            Expr::DottedPair(_, _) => "cons",
+            Expr::Symbol(_) => "symbol",
+            Expr::Macro(_, _) => "macro",
+            Expr::RandomState(_) => "random-state",
+            Expr::Stream(_) => "stream",
+            Expr::Array(array) if array.borrow().rank() == 1 => "vector",
+            Expr::Array(_) => "array",
+            Expr::HashTable(_) => "hash-table",
+            Expr::Struct(_) => "structure",
+            Expr::Instance(_) => "instance",
+        }
+    }
 }
 
 pub fn register_lambda_operators() {
     OperatorRegistry::register("defun", Lambda::eval_defun);
     OperatorRegistry::register("lambda", Lambda::eval_lambda);
     OperatorRegistry::register("progn", Lambda::eval_progn);
+    OperatorRegistry::register("destructuring-bind", Lambda::eval_destructuring_bind);
+    OperatorRegistry::register("documentation", Lambda::eval_documentation);
+    OperatorRegistry::register("describe", Lambda::eval_describe);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operator::test_util::eval_str;
     use crate::environment::Environment;
     use crate::expression::Expr;
 
@@ -397,4 +891,185 @@ mod tests {
         let result = Evaluator::eval(&anon_func_call, &mut env);
         assert_eq!(result, Ok(Expr::Number(11))); // 应返回11
     }
+
+    #[test]
+    fn test_optional_parameters() {
+        let mut env = setup_environment();
+        eval_str("(defun opt (a &optional (b (+ a 1)) (c 10 c-p)) (list a b c c-p))", &mut env).unwrap();
+        env.set_function("list".to_string(), Expr::List(vec![
+            Expr::Symbol("lambda".to_string()),
+            Expr::List(vec![Expr::Symbol("&rest".to_string()), Expr::Symbol("xs".to_string())]),
+            Expr::Symbol("xs".to_string()),
+        ]));
+
+        // 默认值可以引用前面的形参
+        assert_eq!(eval_str("(opt 1)", &mut env).unwrap().to_string(), "(1 2 10 ())");
+        assert_eq!(eval_str("(opt 1 5 6)", &mut env).unwrap().to_string(), "(1 5 6 t)");
+    }
+
+    #[test]
+    fn test_rest_parameter() {
+        let mut env = setup_environment();
+        eval_str("(defun collect (first &rest others) others)", &mut env).unwrap();
+        assert_eq!(eval_str("(collect 1)", &mut env), Ok(Expr::List(vec![])));
+        assert_eq!(
+            eval_str("(collect 1 2 (+ 1 2))", &mut env),
+            Ok(Expr::List(vec![Expr::Number(2), Expr::Number(3)]))
+        );
+        assert_eq!(eval_str("((lambda (&rest xs) xs) 4 5)", &mut env).unwrap().to_string(), "(4 5)");
+    }
+
+    #[test]
+    fn test_key_parameters() {
+        let mut env = setup_environment();
+        eval_str("(defun area (&key (width 2) (height width h-p)) (cond (h-p (* width height)) (t (- 0 (* width height)))))", &mut env).unwrap();
+        assert_eq!(eval_str("(area)", &mut env), Ok(Expr::Number(-4)));
+        assert_eq!(eval_str("(area ':height 5 ':width 3)", &mut env), Ok(Expr::Number(15)));
+        // 关键字求值为自身，调用时不需要引用
+        assert_eq!(eval_str("(area :height 5 :width 3)", &mut env), Ok(Expr::Number(15)));
+
+        let err = eval_str("(area ':depth 1)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("area: unknown keyword argument :depth for lambda list (&key (width 2) (height width h-p))"));
+        let err = eval_str("(area ':width)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("area: odd number of keyword arguments"));
+
+        // &allow-other-keys 以及调用时的 :allow-other-keys
+        eval_str("(defun loose (&key ((:size s) 1) &allow-other-keys) s)", &mut env).unwrap();
+        assert_eq!(eval_str("(loose ':size 7 ':color 1)", &mut env), Ok(Expr::Number(7)));
+        assert_eq!(eval_str("(area ':width 1 ':depth 9 ':allow-other-keys t)", &mut env), Ok(Expr::Number(-1)));
+    }
+
+    #[test]
+    fn test_aux_parameters() {
+        let mut env = setup_environment();
+        eval_str("(defun scaled (x &aux (double (* x 2)) unused) (+ double x))", &mut env).unwrap();
+        assert_eq!(eval_str("(scaled 4)", &mut env), Ok(Expr::Number(12)));
+        assert!(eval_str("(scaled 4 5)", &mut env).is_err());
+    }
+
+    #[test]
+    fn test_arity_errors_name_function_and_shape() {
+        let mut env = setup_environment();
+        eval_str("(defun pair (a b &optional c) a)", &mut env).unwrap();
+
+        let err = eval_str("(pair 1)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("pair: expected 2 to 3 argument(s) for lambda list (a b &optional c), got 1"));
+        let err = eval_str("(pair 1 2 3 4)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("pair: expected 2 to 3 argument(s) for lambda list (a b &optional c), got 4"));
+
+        let err = eval_str("((lambda (x) x))", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("lambda: expected 1 argument(s) for lambda list (x), got 0"));
+    }
+
+    #[test]
+    fn test_invalid_lambda_lists() {
+        let mut env = setup_environment();
+        assert_eq!(
+            eval_str("((lambda (&rest) 1))", &mut env),
+            Err(LispError::new("&rest must be followed by a variable"))
+        );
+        assert_eq!(
+            eval_str("((lambda (&key a &optional b) 1))", &mut env),
+            Err(LispError::new("Misplaced &optional in lambda list (&key a &optional b)"))
+        );
+        assert_eq!(
+            eval_str("((lambda (&whole w) 1))", &mut env),
+            Err(LispError::new("Unknown lambda list keyword: &whole"))
+        );
+    }
+
+    #[test]
+    fn test_arguments_evaluated_in_caller_environment() {
+        let mut env = setup_environment();
+        env.set_symbol("a".to_string(), Expr::Number(100));
+        eval_str("(defun second-arg (a b) b)", &mut env).unwrap();
+        // 实参 a 应该取调用者的值，而不是刚绑定的形参
+        assert_eq!(eval_str("(second-arg 1 a)", &mut env), Ok(Expr::Number(100)));
+    }
+
+    #[test]
+    fn test_defun_with_multiple_body_forms() {
+        let mut env = setup_environment();
+        eval_str("(defun twice (x) (setf y (+ x x)) (setf y (+ y 1)) y)", &mut env).unwrap();
+        assert_eq!(eval_str("(twice 4)", &mut env), Ok(Expr::Number(9)));
+
+        // 空函数体返回 nil
+        eval_str("(defun nothing ())", &mut env).unwrap();
+        assert_eq!(eval_str("(nothing)", &mut env), Ok(Expr::List(vec![])));
+    }
+
+    #[test]
+    fn test_defun_docstring_and_declarations() {
+        let mut env = setup_environment();
+        let input = "(defun square (x) \"Return X squared.\" (declare (type number x)) (declare (ignorable x)) (* x x))";
+        assert_eq!(eval_str(input, &mut env), Ok(Expr::Symbol("square".to_string())));
+        assert_eq!(eval_str("(square 5)", &mut env), Ok(Expr::Number(25)));
+        assert_eq!(
+            eval_str("(documentation 'square 'function)", &mut env),
+            Ok(Expr::Str("Return X squared.".to_string()))
+        );
+
+        let description = eval_str("(describe 'square)", &mut env).unwrap();
+        assert_eq!(
+            description,
+            Expr::Str("square is a symbol\n  Function lambda list: (x)\n  Documentation: Return X squared.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_defun_lone_string_is_body() {
+        let mut env = setup_environment();
+        eval_str("(defun greeting () \"hello\")", &mut env).unwrap();
+        assert_eq!(eval_str("(greeting)", &mut env), Ok(Expr::Str("hello".to_string())));
+        assert_eq!(eval_str("(documentation 'greeting 'function)", &mut env), Ok(Expr::List(vec![])));
+
+        // 重新定义时去掉旧的文档字符串
+        eval_str("(defun square (x) \"doc\" (* x x))", &mut env).unwrap();
+        eval_str("(defun square (x) (* x x))", &mut env).unwrap();
+        assert_eq!(eval_str("(documentation 'square 'function)", &mut env), Ok(Expr::List(vec![])));
+    }
+
+    #[test]
+    fn test_lambda_strips_docstring() {
+        let mut env = setup_environment();
+        assert_eq!(eval_str("((lambda (x) \"doc\" (declare (ignore y)) (+ x 1)) 1)", &mut env), Ok(Expr::Number(2)));
+        // 后面只有 declare 时，字符串是返回值而不是文档字符串
+        assert_eq!(eval_str("((lambda () \"doc\" (declare (ignore y))))", &mut env), Ok(Expr::Str("doc".to_string())));
+        eval_str("(defun answer () \"only\" (declare (optimize speed)))", &mut env).unwrap();
+        assert_eq!(eval_str("(answer)", &mut env), Ok(Expr::Str("only".to_string())));
+        assert_eq!(eval_str("(documentation 'answer 'function)", &mut env), Ok(Expr::List(vec![])));
+    }
+
+    #[test]
+    fn test_destructuring_bind() {
+        let mut env = setup_environment();
+        let eval = |input: &str, env: &mut Environment| eval_str(input, env).unwrap().to_string();
+        assert_eq!(eval("(destructuring-bind (a (b c) &optional (d 4)) '(1 (2 3)) (+ a b c d))", &mut env), "10");
+        assert_eq!(eval("(destructuring-bind (a . rest) '(1 2 3) rest)", &mut env), "(2 3)");
+        assert_eq!(eval("(destructuring-bind (a b . c) '(1 2 . 3) (cons c a))", &mut env), "(3 . 1)");
+        assert_eq!(eval("(destructuring-bind (a &rest b) '(1 . 2) b)", &mut env), "2");
+
+        // 属性表和关联表
+        assert_eq!(eval("(destructuring-bind (&key name (age 0)) '(:name \"ann\") (cons name age))", &mut env), "(\"ann\" . 0)");
+        assert_eq!(
+            eval("(destructuring-bind ((k1 . v1) (k2 . v2)) '((a . 1) (b . 2)) (cons (cons k2 k1) (+ v1 v2)))", &mut env),
+            "((b . a) . 3)"
+        );
+        assert_eq!(eval("(destructuring-bind ((a (b)) (c)) '((1 (2)) (3)) (+ a b c))", &mut env), "6");
+        assert_eq!(eval("(multiple-value-list (destructuring-bind (a) '(1) (values a 2)))", &mut env), "(1 2)");
+        assert_eq!(eval("(multiple-value-list (destructuring-bind (a) (values '(1) 2) a))", &mut env), "(1)");
+
+        assert_eq!(
+            eval_str("(destructuring-bind ((k . v)) '((a . 1) (b . 2)) k)", &mut env),
+            Err(LispError::new("destructuring-bind: expected 1 argument(s) for lambda list ((k . v)), got 2"))
+        );
+        assert_eq!(
+            eval_str("(destructuring-bind (a b) '(1 . 2) a)", &mut env),
+            Err(LispError::new("destructuring-bind: expected 2 argument(s) for lambda list (a b), got 1"))
+        );
+        assert_eq!(
+            eval_str("(destructuring-bind ((a) b) '(5 6) a)", &mut env),
+            Err(LispError::new("destructuring-bind: 5 does not match the lambda list (a)"))
+        );
+    }
 }
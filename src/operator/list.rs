@@ -15,14 +15,7 @@ impl ListOps {
     
         let first = Evaluator::eval(&args[0], env)?;
         let second = Evaluator::eval(&args[1], env)?;
-    
-        match second {
-            Expr::List(mut list) => {
-                list.insert(0, first);
-                Ok(Expr::List(list))
-            }
-            _ => Ok(Expr::DottedPair(Box::new(first), Box::new(second))),
-        }
+        Ok(Expr::cons(first, second))
     }
     
     pub fn eval_car(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
@@ -35,6 +28,7 @@ impl ListOps {
         match list {
             Expr::List(ref list) if !list.is_empty() => Ok(list[0].clone()),
             Expr::List(_) => Ok(Expr::List(vec![])),  // 返回空列表而不是错误
+            Expr::DottedPair(car, _) => Ok(*car),
             _ => Err(LispError::new("car: argument must be a list")),
         }
     }
@@ -49,6 +43,7 @@ impl ListOps {
         match list {
             Expr::List(ref list) if list.len() > 1 => Ok(Expr::List(list[1..].to_vec())),
             Expr::List(_) => Ok(Expr::List(vec![])),  // 返回空列表而不是错误
+            Expr::DottedPair(_, cdr) => Ok(*cdr),
             _ => Err(LispError::new("cdr: argument must be a list")),
         }
     }
@@ -58,11 +53,14 @@ impl ListOps {
             return Err(LispError::new("length requires exactly one argument"));
         }
 
-        let list_expr = Evaluator::eval(&args[0], env)?;
-        if let Expr::List(list) = list_expr {
-            return Ok(Expr::Number(list.len() as i64));
+        match Evaluator::eval(&args[0], env)? {
+            Expr::List(list) => Ok(Expr::Number(list.len() as i64)),
+            // 字符串的长度按字符计，而不是按字节
+            Expr::Str(s) => Ok(Expr::Number(s.chars().count() as i64)),
+            // 向量的长度是填充指针之前的元素个数
+            Expr::Array(array) if array.borrow().rank() == 1 => Ok(Expr::Number(array.borrow().active_elements().len() as i64)),
+            _ => Err(LispError::new("length: argument is not a sequence")),
         }
-        Err(LispError::new("length: argument is not a list"))
     }
 
     pub fn eval_quote(args: &[Expr], _env: &mut Environment) -> Result<Expr, LispError> {
@@ -273,7 +271,7 @@ mod tests {
         let result = Evaluator::eval(&expr, &mut env);
         assert!(result.is_err());
         if let Err(err) = result {
-            assert_eq!(err.to_string(), "length: argument is not a list");
+            assert_eq!(err.to_string(), "length: argument is not a sequence");
         }
     }
 
@@ -493,6 +491,21 @@ mod tests {
         );
     }
     
+    #[test]
+    fn test_car_and_cdr_of_dotted_lists() {
+        let mut env = setup_environment();
+        let eval_str = |input: &str, env: &mut Environment| Evaluator::eval(&crate::parser::Parser::read(input, env)?, env);
+        assert_eq!(eval_str("(car '(a . b))", &mut env), Ok(Expr::Symbol("a".to_string())));
+        assert_eq!(eval_str("(cdr '(a . b))", &mut env), Ok(Expr::Symbol("b".to_string())));
+        assert_eq!(eval_str("(cdr '(1 2 . 3))", &mut env).unwrap().to_string(), "(2 . 3)");
+        assert_eq!(eval_str("(car (cdr '(1 2 . 3)))", &mut env), Ok(Expr::Number(2)));
+        assert_eq!(eval_str("(consp '(1 . 2))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(cons 1 (cons 2 3))", &mut env).unwrap().to_string(), "(1 2 . 3)");
+        assert_eq!(eval_str("(cons 1 'nil)", &mut env), Ok(Expr::List(vec![Expr::Number(1)])));
+        assert_eq!(eval_str("(equal '(1 2 . 3) (cons 1 (cons 2 3)))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(car 5)", &mut env), Err(LispError::new("car: argument must be a list")));
+    }
+
     #[test]
     fn test_unquote_outside_quasiquote() {
         let mut env = setup_environment();
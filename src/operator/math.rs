@@ -0,0 +1,942 @@
+// operator/math.rs
+
+use crate::operator::OperatorRegistry;
+use crate::operator::arithmetic::Arithmetic;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::bignum::BigInt;
+use crate::ratio::Ratio;
+use std::cmp::Ordering;
+
+// 精确的 expt 结果最多这么多位，超过时报错而不是长时间计算
+const MAX_EXPT_BITS: usize = 1 << 20;
+
+pub struct Math;
+
+impl Math {
+    // 求值唯一的参数，并确认它是数值
+    fn number_argument(name: &str, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new(&format!("{} expects exactly one argument", name)));
+        }
+        Math::check_number(name, Evaluator::eval(&args[0], env)?)
+    }
+
+    fn check_number(name: &str, value: Expr) -> Result<Expr, LispError> {
+        match Arithmetic::complex_parts(&value) {
+            Some(_) => Ok(value),
+            None => Err(LispError::new(&format!("{}: argument must be a number, got {}", name, value))),
+        }
+    }
+
+    // 以浮点数表示的 (实部, 虚部)
+    fn float_parts(value: &Expr) -> (f64, f64) {
+        let (re, im) = Arithmetic::complex_parts(value).unwrap_or((Expr::Number(0), Expr::Number(0)));
+        (Arithmetic::to_f64(&re).unwrap_or(0.0), Arithmetic::to_f64(&im).unwrap_or(0.0))
+    }
+
+    fn float_complex(re: f64, im: f64) -> Expr {
+        Expr::Complex(Box::new(Expr::Float(re)), Box::new(Expr::Float(im)))
+    }
+
+    pub fn eval_complex(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(LispError::new("complex expects one or two arguments"));
+        }
+        let re = Evaluator::eval(&args[0], env)?;
+        let im = match args.get(1) {
+            Some(arg) => Evaluator::eval(arg, env)?,
+            // 省略虚部时使用与实部同类型的 0
+            None if matches!(re, Expr::Float(_)) => Expr::Float(0.0),
+            None => Expr::Number(0),
+        };
+        Arithmetic::make_complex(re, im)
+    }
+
+    pub fn eval_realpart(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("realpart", args, env)?;
+        match value {
+            Expr::Complex(re, _) => Ok(*re),
+            _ => Ok(value),
+        }
+    }
+
+    pub fn eval_imagpart(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("imagpart", args, env)?;
+        match value {
+            Expr::Complex(_, im) => Ok(*im),
+            Expr::Float(_) => Ok(Expr::Float(0.0)),
+            _ => Ok(Expr::Number(0)),
+        }
+    }
+
+    pub fn eval_conjugate(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("conjugate", args, env)?;
+        match value {
+            Expr::Complex(re, im) => Arithmetic::make_complex(*re, Arithmetic::subtract(&Expr::Number(0), &im)?),
+            _ => Ok(value),
+        }
+    }
+
+    // 实数的绝对值保持精确类型；复数的绝对值是它的模
+    pub fn eval_abs(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("abs", args, env)?;
+        match value {
+            Expr::Complex(_, _) => {
+                let (re, im) = Math::float_parts(&value);
+                Ok(Expr::Float(re.hypot(im)))
+            }
+            Expr::Float(f) => Ok(Expr::Float(f.abs())),
+            _ if Math::is_negative(&value) => {
+                Arithmetic::subtract(&Expr::Number(0), &value)
+            }
+            _ => Ok(value),
+        }
+    }
+
+    pub fn eval_phase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("phase", args, env)?;
+        let (re, im) = Math::float_parts(&value);
+        Ok(Expr::Float(im.atan2(re)))
+    }
+
+    // 负实数的平方根是纯虚数
+    pub fn eval_sqrt(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("sqrt", args, env, |x| x >= 0.0, f64::sqrt, Complex::sqrt)
+    }
+
+    pub fn eval_exp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("exp", args, env, |_| true, f64::exp, Complex::exp)
+    }
+
+    // (log z) 是自然对数，(log z base) 是以 base 为底的对数；负数和复数的对数是复数
+    pub fn eval_log(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(LispError::new("log expects one or two arguments"));
+        }
+        let value = Math::check_number("log", Evaluator::eval(&args[0], env)?)?;
+        let result = Math::natural_log(&value)?;
+        match args.get(1) {
+            Some(arg) => {
+                let base = Math::check_number("log", Evaluator::eval(arg, env)?)?;
+                Arithmetic::divide(&result, &Math::natural_log(&base)?)
+            }
+            None => Ok(result),
+        }
+    }
+
+    fn natural_log(value: &Expr) -> Result<Expr, LispError> {
+        let (re, im) = Math::float_parts(value);
+        if re == 0.0 && im == 0.0 {
+            return Err(LispError::new("log: logarithm of zero"));
+        }
+        if !matches!(value, Expr::Complex(_, _)) && re > 0.0 {
+            return Ok(Expr::Float(re.ln()));
+        }
+        let log = Complex(re, im).ln();
+        Ok(Math::float_complex(log.0, log.1))
+    }
+
+    fn real_argument(name: &str, value: Expr) -> Result<Expr, LispError> {
+        match Arithmetic::to_f64(&value) {
+            Some(_) => Ok(value),
+            None => Err(LispError::new(&format!("{}: argument must be a real number, got {}", name, value))),
+        }
+    }
+
+    fn integer_argument(name: &str, value: Expr) -> Result<BigInt, LispError> {
+        Arithmetic::to_bigint(&value)
+            .ok_or_else(|| LispError::new(&format!("{}: argument must be an integer, got {}", name, value)))
+    }
+
+    fn eval_arguments(args: &[Expr], env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        args.iter().map(|arg| Evaluator::eval(arg, env)).collect()
+    }
+
+    fn is_negative(value: &Expr) -> bool {
+        Arithmetic::compare(value, &Expr::Number(0)) == Some(Ordering::Less)
+    }
+
+    // (floor number [divisor]) 等：返回商和余数两个值，number = 商 * divisor + 余数
+    fn eval_rounding(name: &str, mode: Rounding, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(LispError::new(&format!("{} expects one or two arguments", name)));
+        }
+        let number = Math::real_argument(name, Evaluator::eval(&args[0], env)?)?;
+        let divisor = match args.get(1) {
+            Some(arg) => Math::real_argument(name, Evaluator::eval(arg, env)?)?,
+            None => Expr::Number(1),
+        };
+        let (quotient, remainder) = Math::divide_rounding(name, &number, &divisor, mode)?;
+        Ok(env.set_values(vec![quotient, remainder]))
+    }
+
+    fn divide_rounding(name: &str, number: &Expr, divisor: &Expr, mode: Rounding) -> Result<(Expr, Expr), LispError> {
+        if matches!(number, Expr::Float(_)) || matches!(divisor, Expr::Float(_)) {
+            let (n, d) = (Arithmetic::to_f64(number).unwrap_or(0.0), Arithmetic::to_f64(divisor).unwrap_or(0.0));
+            if d == 0.0 {
+                return Err(LispError::new("Division by zero"));
+            }
+            let quotient = mode.round_f64(n / d);
+            let integer = BigInt::from_f64(quotient)
+                .ok_or_else(|| LispError::new(&format!("{}: result {} is not a finite number", name, quotient)))?;
+            return Ok((Expr::from_bigint(integer), Expr::Float(n - quotient * d)));
+        }
+        let (Some(n), Some(d)) = (Arithmetic::to_ratio(number), Arithmetic::to_ratio(divisor)) else {
+            return Err(LispError::new("Invalid number"));
+        };
+        let ratio = n.div(&d).ok_or_else(|| LispError::new("Division by zero"))?;
+        let quotient = Expr::from_bigint(mode.round_ratio(&ratio));
+        let remainder = Arithmetic::subtract(number, &Arithmetic::multiply(&quotient, divisor)?)?;
+        Ok((quotient, remainder))
+    }
+
+    pub fn eval_floor(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_rounding("floor", Rounding::Floor, args, env)
+    }
+
+    pub fn eval_ceiling(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_rounding("ceiling", Rounding::Ceiling, args, env)
+    }
+
+    pub fn eval_truncate(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_rounding("truncate", Rounding::Truncate, args, env)
+    }
+
+    pub fn eval_round(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_rounding("round", Rounding::Round, args, env)
+    }
+
+    // mod 的结果与除数同号，rem 的结果与被除数同号
+    pub fn eval_mod(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_remainder("mod", Rounding::Floor, args, env)
+    }
+
+    pub fn eval_rem(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_remainder("rem", Rounding::Truncate, args, env)
+    }
+
+    fn eval_remainder(name: &str, mode: Rounding, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new(&format!("{} expects exactly two arguments", name)));
+        }
+        let number = Math::real_argument(name, Evaluator::eval(&args[0], env)?)?;
+        let divisor = Math::real_argument(name, Evaluator::eval(&args[1], env)?)?;
+        Ok(Math::divide_rounding(name, &number, &divisor, mode)?.1)
+    }
+
+    pub fn eval_min(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_extremum("min", Ordering::Less, args, env)
+    }
+
+    pub fn eval_max(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_extremum("max", Ordering::Greater, args, env)
+    }
+
+    fn eval_extremum(name: &str, wanted: Ordering, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() {
+            return Err(LispError::new(&format!("{} requires at least one argument", name)));
+        }
+        let mut best = Math::real_argument(name, Evaluator::eval(&args[0], env)?)?;
+        for arg in &args[1..] {
+            let value = Math::real_argument(name, Evaluator::eval(arg, env)?)?;
+            if Arithmetic::compare(&value, &best) == Some(wanted) {
+                best = value;
+            }
+        }
+        Ok(best)
+    }
+
+    // 整数次幂保持精确（包括分数和复数的底数）；其它情况按浮点数或复数计算
+    pub fn eval_expt(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("expt expects exactly two arguments"));
+        }
+        let base = Math::check_number("expt", Evaluator::eval(&args[0], env)?)?;
+        let power = Math::check_number("expt", Evaluator::eval(&args[1], env)?)?;
+
+        if let Expr::Number(exponent) = power {
+            let too_large = Math::exact_bits(&base).is_some_and(|bits| bits.saturating_mul(exponent.unsigned_abs() as usize) > MAX_EXPT_BITS);
+            if too_large {
+                return Err(LispError::new(&format!("expt: exponent {} is too large", power)));
+            }
+            let mut result = Expr::Number(1);
+            let mut square = base;
+            let mut remaining = exponent.unsigned_abs();
+            while remaining > 0 {
+                if remaining & 1 == 1 {
+                    result = Arithmetic::multiply(&result, &square)?;
+                }
+                remaining >>= 1;
+                if remaining > 0 {
+                    square = Arithmetic::multiply(&square, &square)?;
+                }
+            }
+            return if exponent < 0 { Arithmetic::divide(&Expr::Number(1), &result) } else { Ok(result) };
+        }
+        if matches!(power, Expr::BigInt(_)) {
+            return Err(LispError::new(&format!("expt: exponent {} is too large", power)));
+        }
+
+        let (base_re, base_im) = Math::float_parts(&base);
+        if base_re == 0.0 && base_im == 0.0 {
+            return match Arithmetic::compare(&power, &Expr::Number(0)) {
+                Some(Ordering::Greater) => Ok(Expr::Float(0.0)),
+                _ => Err(LispError::new("expt: zero cannot be raised to a non-positive power")),
+            };
+        }
+        let is_real = |value: &Expr| !matches!(value, Expr::Complex(_, _));
+        if is_real(&base) && is_real(&power) && base_re > 0.0 {
+            return Ok(Expr::Float(base_re.powf(Math::float_parts(&power).0)));
+        }
+        // base^power = exp(power * log(base))
+        let exponent = Arithmetic::multiply(&power, &Math::natural_log(&base)?)?;
+        let (re, im) = Math::float_parts(&exponent);
+        let result = Complex(re, im).exp();
+        Ok(Math::float_complex(result.0, result.1))
+    }
+
+    // 精确数值的位数：有理数取分子和分母中较长的，复数取实部和虚部中较长的；-1、0 和 1 的任意次幂都不会变长，按 0 位计算。
+    // 浮点数的幂不会变长，返回 None
+    fn exact_bits(value: &Expr) -> Option<usize> {
+        let integer_bits = |n: &BigInt| if n.abs() <= BigInt::from_i64(1) { 0 } else { n.integer_length() };
+        match value {
+            Expr::Number(n) => Some(integer_bits(&BigInt::from_i64(*n))),
+            Expr::BigInt(n) => Some(integer_bits(n)),
+            Expr::Ratio(r) => Some(integer_bits(r.numerator()).max(integer_bits(r.denominator()))),
+            Expr::Complex(re, im) => Some(Math::exact_bits(re)?.max(Math::exact_bits(im)?)),
+            _ => None,
+        }
+    }
+
+    // 非负整数的整数平方根，牛顿迭代
+    pub fn eval_isqrt(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("isqrt", args, env)?;
+        let n = Math::integer_argument("isqrt", value.clone())?;
+        if n.is_negative() {
+            return Err(LispError::new(&format!("isqrt: argument must be a non-negative integer, got {}", value)));
+        }
+        if n.is_zero() {
+            return Ok(Expr::Number(0));
+        }
+        let two = BigInt::from_i64(2);
+        let mut x = n.clone();
+        loop {
+            let (quotient, _) = n.div_rem(&x).expect("x is positive");
+            let (next, _) = x.add(&quotient).div_rem(&two).expect("two is not zero");
+            if next >= x {
+                return Ok(Expr::from_bigint(x));
+            }
+            x = next;
+        }
+    }
+
+    fn eval_float_function(
+        name: &str,
+        args: &[Expr],
+        env: &mut Environment,
+        in_domain: fn(f64) -> bool,
+        real: fn(f64) -> f64,
+        complex: fn(Complex) -> Complex,
+    ) -> Result<Expr, LispError> {
+        let value = Math::number_argument(name, args, env)?;
+        Ok(Math::float_function(&value, in_domain, real, complex))
+    }
+
+    // 对已经求值的数值计算：实数参数在定义域内时用实函数，否则用复函数
+    fn float_function(value: &Expr, in_domain: fn(f64) -> bool, real: fn(f64) -> f64, complex: fn(Complex) -> Complex) -> Expr {
+        let (re, im) = Math::float_parts(value);
+        if !matches!(value, Expr::Complex(_, _)) && in_domain(re) {
+            return Expr::Float(real(re));
+        }
+        let result = complex(Complex(re, im));
+        Math::float_complex(result.0, result.1)
+    }
+
+    pub fn eval_sin(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("sin", args, env, |_| true, f64::sin, Complex::sin)
+    }
+
+    pub fn eval_cos(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("cos", args, env, |_| true, f64::cos, Complex::cos)
+    }
+
+    pub fn eval_tan(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("tan", args, env, |_| true, f64::tan, |z| z.sin().div(z.cos()))
+    }
+
+    pub fn eval_asin(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("asin", args, env, |x| x.abs() <= 1.0, f64::asin, Complex::asin)
+    }
+
+    pub fn eval_acos(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        // acos z = π/2 - asin z
+        Math::eval_float_function("acos", args, env, |x| x.abs() <= 1.0, f64::acos, |z| {
+            Complex(std::f64::consts::FRAC_PI_2, 0.0).sub(z.asin())
+        })
+    }
+
+    // (atan y) 或 (atan y x)，后者按 x、y 的符号确定象限
+    pub fn eval_atan(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() == 2 {
+            let y = Math::real_argument("atan", Evaluator::eval(&args[0], env)?)?;
+            let x = Math::real_argument("atan", Evaluator::eval(&args[1], env)?)?;
+            let (y, x) = (Math::float_parts(&y).0, Math::float_parts(&x).0);
+            return Ok(Expr::Float(y.atan2(x)));
+        }
+        // atan z = (log(1+iz) - log(1-iz)) / 2i
+        Math::eval_float_function("atan", args, env, |_| true, f64::atan, |z| {
+            let iz = Complex(-z.1, z.0);
+            let one = Complex(1.0, 0.0);
+            one.add(iz).ln().sub(one.sub(iz).ln()).div(Complex(0.0, 2.0))
+        })
+    }
+
+    pub fn eval_sinh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("sinh", args, env, |_| true, f64::sinh, |Complex(a, b)| {
+            Complex(a.sinh() * b.cos(), a.cosh() * b.sin())
+        })
+    }
+
+    pub fn eval_cosh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("cosh", args, env, |_| true, f64::cosh, |Complex(a, b)| {
+            Complex(a.cosh() * b.cos(), a.sinh() * b.sin())
+        })
+    }
+
+    pub fn eval_tanh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Math::eval_float_function("tanh", args, env, |_| true, f64::tanh, |Complex(a, b)| {
+            Complex(a.sinh() * b.cos(), a.cosh() * b.sin()).div(Complex(a.cosh() * b.cos(), a.sinh() * b.sin()))
+        })
+    }
+
+    pub fn eval_asinh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        // asinh z = log(z + sqrt(1 + z²))
+        Math::eval_float_function("asinh", args, env, |_| true, f64::asinh, |z| {
+            z.add(Complex(1.0, 0.0).add(z.mul(z)).sqrt()).ln()
+        })
+    }
+
+    pub fn eval_acosh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        // acosh z = 2 log(sqrt((z+1)/2) + sqrt((z-1)/2))
+        Math::eval_float_function("acosh", args, env, |x| x >= 1.0, f64::acosh, |z| {
+            let half = Complex(0.5, 0.0);
+            let root = z.add(Complex(1.0, 0.0)).mul(half).sqrt().add(z.sub(Complex(1.0, 0.0)).mul(half).sqrt());
+            root.ln().mul(Complex(2.0, 0.0))
+        })
+    }
+
+    pub fn eval_atanh(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("atanh", args, env)?;
+        let (re, im) = Math::float_parts(&value);
+        if im == 0.0 && re.abs() == 1.0 {
+            return Err(LispError::new(&format!("atanh: argument must not be {}", value)));
+        }
+        // 实轴上 |x| > 1 的部分是分支切割：x > 1 时与第一象限连续，虚部为 +π/2；x < -1 时与第三象限连续，虚部为 -π/2
+        if !matches!(value, Expr::Complex(_, _)) && re.abs() > 1.0 {
+            let real_part = 0.5 * ((re + 1.0) / (re - 1.0)).ln();
+            return Ok(Math::float_complex(real_part, std::f64::consts::FRAC_PI_2.copysign(re)));
+        }
+        // atanh z = (log(1+z) - log(1-z)) / 2
+        Ok(Math::float_function(&value, |x| x.abs() < 1.0, f64::atanh, |z| {
+            let one = Complex(1.0, 0.0);
+            one.add(z).ln().sub(one.sub(z).ln()).mul(Complex(0.5, 0.0))
+        }))
+    }
+
+    pub fn eval_gcd(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let mut result = BigInt::from_i64(0);
+        for value in Math::eval_arguments(args, env)? {
+            result = result.gcd(&Math::integer_argument("gcd", value)?);
+        }
+        Ok(Expr::from_bigint(result))
+    }
+
+    pub fn eval_lcm(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let mut result = BigInt::from_i64(1);
+        for value in Math::eval_arguments(args, env)? {
+            let n = Math::integer_argument("lcm", value)?;
+            if n.is_zero() || result.is_zero() {
+                result = BigInt::from_i64(0);
+                continue;
+            }
+            let (quotient, _) = result.mul(&n).abs().div_rem(&result.gcd(&n)).expect("gcd of non-zero integers is non-zero");
+            result = quotient;
+        }
+        Ok(Expr::from_bigint(result))
+    }
+
+    // 有理数返回 -1、0、1，浮点数返回 -1.0、0.0、1.0，复数返回同方向的单位复数
+    pub fn eval_signum(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("signum", args, env)?;
+        match value {
+            Expr::Float(f) if f == 0.0 => Ok(Expr::Float(f)),
+            Expr::Float(f) => Ok(Expr::Float(f.signum())),
+            Expr::Complex(_, _) => {
+                let (re, im) = Math::float_parts(&value);
+                let modulus = re.hypot(im);
+                Ok(Math::float_complex(re / modulus, im / modulus))
+            }
+            _ => match Arithmetic::compare(&value, &Expr::Number(0)) {
+                Some(Ordering::Less) => Ok(Expr::Number(-1)),
+                Some(Ordering::Greater) => Ok(Expr::Number(1)),
+                _ => Ok(Expr::Number(0)),
+            },
+        }
+    }
+
+    pub fn eval_one_plus(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("1+", args, env)?;
+        Arithmetic::add(&value, &Expr::Number(1))
+    }
+
+    pub fn eval_one_minus(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("1-", args, env)?;
+        Arithmetic::subtract(&value, &Expr::Number(1))
+    }
+
+    pub fn eval_zerop(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("zerop", args, env)?;
+        Ok(Expr::from_bool(Math::float_parts(&value) == (0.0, 0.0)))
+    }
+
+    pub fn eval_plusp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("plusp", args, env)?;
+        let value = Math::real_argument("plusp", value)?;
+        Ok(Expr::from_bool(Arithmetic::compare(&value, &Expr::Number(0)) == Some(Ordering::Greater)))
+    }
+
+    pub fn eval_minusp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("minusp", args, env)?;
+        let value = Math::real_argument("minusp", value)?;
+        Ok(Expr::from_bool(Math::is_negative(&value)))
+    }
+
+    pub fn eval_evenp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("evenp", args, env)?;
+        Ok(Expr::from_bool(Math::integer_argument("evenp", value)?.is_even()))
+    }
+
+    pub fn eval_oddp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = Math::number_argument("oddp", args, env)?;
+        Ok(Expr::from_bool(!Math::integer_argument("oddp", value)?.is_even()))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Rounding {
+    Floor,
+    Ceiling,
+    Truncate,
+    // 取最近的整数，恰好在中间时取偶数
+    Round,
+}
+
+impl Rounding {
+    fn round_f64(self, value: f64) -> f64 {
+        match self {
+            Rounding::Floor => value.floor(),
+            Rounding::Ceiling => value.ceil(),
+            Rounding::Truncate => value.trunc(),
+            Rounding::Round => value.round_ties_even(),
+        }
+    }
+
+    fn round_ratio(self, value: &Ratio) -> BigInt {
+        let (truncated, remainder) = value
+            .numerator()
+            .div_rem(value.denominator())
+            .expect("ratio denominator is never zero");
+        if remainder.is_zero() {
+            return truncated;
+        }
+        // 余数与分子同号；向远离零的方向进一
+        let away = if remainder.is_negative() { truncated.sub(&BigInt::from_i64(1)) } else { truncated.add(&BigInt::from_i64(1)) };
+        match self {
+            Rounding::Truncate => truncated,
+            Rounding::Floor if remainder.is_negative() => away,
+            Rounding::Ceiling if !remainder.is_negative() => away,
+            Rounding::Floor | Rounding::Ceiling => truncated,
+            Rounding::Round => match remainder.abs().mul(&BigInt::from_i64(2)).cmp(value.denominator()) {
+                Ordering::Less => truncated,
+                Ordering::Greater => away,
+                Ordering::Equal if truncated.is_even() => truncated,
+                Ordering::Equal => away,
+            },
+        }
+    }
+}
+
+// 超越函数在复数域上的浮点计算
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Complex(f64, f64);
+
+impl Complex {
+    fn add(self, other: Complex) -> Complex {
+        Complex(self.0 + other.0, self.1 + other.1)
+    }
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex(self.0 - other.0, self.1 - other.1)
+    }
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex(self.0 * other.0 - self.1 * other.1, self.0 * other.1 + self.1 * other.0)
+    }
+
+    fn div(self, other: Complex) -> Complex {
+        let norm = other.0 * other.0 + other.1 * other.1;
+        Complex(
+            (self.0 * other.0 + self.1 * other.1) / norm,
+            (self.1 * other.0 - self.0 * other.1) / norm,
+        )
+    }
+
+    // 主平方根：sqrt((|z|+a)/2) + sign(b)·sqrt((|z|-a)/2)·i
+    fn sqrt(self) -> Complex {
+        let modulus = self.0.hypot(self.1);
+        Complex(((modulus + self.0) / 2.0).sqrt(), ((modulus - self.0) / 2.0).sqrt().copysign(self.1))
+    }
+
+    fn exp(self) -> Complex {
+        let scale = self.0.exp();
+        Complex(scale * self.1.cos(), scale * self.1.sin())
+    }
+
+    fn ln(self) -> Complex {
+        Complex(self.0.hypot(self.1).ln(), self.1.atan2(self.0))
+    }
+
+    fn sin(self) -> Complex {
+        Complex(self.0.sin() * self.1.cosh(), self.0.cos() * self.1.sinh())
+    }
+
+    fn cos(self) -> Complex {
+        Complex(self.0.cos() * self.1.cosh(), -self.0.sin() * self.1.sinh())
+    }
+
+    // asin z = -i log(iz + sqrt(1 - z²))
+    fn asin(self) -> Complex {
+        let iz = Complex(-self.1, self.0);
+        let root = Complex(1.0, 0.0).sub(self.mul(self)).sqrt();
+        let log = iz.add(root).ln();
+        Complex(log.1, -log.0)
+    }
+}
+
+
+pub fn register_math_operators() {
+    OperatorRegistry::register("complex", Math::eval_complex);
+    OperatorRegistry::register("realpart", Math::eval_realpart);
+    OperatorRegistry::register("imagpart", Math::eval_imagpart);
+    OperatorRegistry::register("conjugate", Math::eval_conjugate);
+    OperatorRegistry::register("abs", Math::eval_abs);
+    OperatorRegistry::register("phase", Math::eval_phase);
+    OperatorRegistry::register("sqrt", Math::eval_sqrt);
+    OperatorRegistry::register("exp", Math::eval_exp);
+    OperatorRegistry::register("log", Math::eval_log);
+    OperatorRegistry::register("mod", Math::eval_mod);
+    OperatorRegistry::register("rem", Math::eval_rem);
+    OperatorRegistry::register("min", Math::eval_min);
+    OperatorRegistry::register("max", Math::eval_max);
+    OperatorRegistry::register("expt", Math::eval_expt);
+    OperatorRegistry::register("isqrt", Math::eval_isqrt);
+    OperatorRegistry::register("sin", Math::eval_sin);
+    OperatorRegistry::register("cos", Math::eval_cos);
+    OperatorRegistry::register("tan", Math::eval_tan);
+    OperatorRegistry::register("asin", Math::eval_asin);
+    OperatorRegistry::register("acos", Math::eval_acos);
+    OperatorRegistry::register("atan", Math::eval_atan);
+    OperatorRegistry::register("sinh", Math::eval_sinh);
+    OperatorRegistry::register("cosh", Math::eval_cosh);
+    OperatorRegistry::register("tanh", Math::eval_tanh);
+    OperatorRegistry::register("asinh", Math::eval_asinh);
+    OperatorRegistry::register("acosh", Math::eval_acosh);
+    OperatorRegistry::register("atanh", Math::eval_atanh);
+    OperatorRegistry::register("floor", Math::eval_floor);
+    OperatorRegistry::register("ceiling", Math::eval_ceiling);
+    OperatorRegistry::register("round", Math::eval_round);
+    OperatorRegistry::register("truncate", Math::eval_truncate);
+    OperatorRegistry::register("gcd", Math::eval_gcd);
+    OperatorRegistry::register("lcm", Math::eval_lcm);
+    OperatorRegistry::register("signum", Math::eval_signum);
+    OperatorRegistry::register("1+", Math::eval_one_plus);
+    OperatorRegistry::register("1-", Math::eval_one_minus);
+    OperatorRegistry::register("zerop", Math::eval_zerop);
+    OperatorRegistry::register("plusp", Math::eval_plusp);
+    OperatorRegistry::register("minusp", Math::eval_minusp);
+    OperatorRegistry::register("evenp", Math::eval_evenp);
+    OperatorRegistry::register("oddp", Math::eval_oddp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_in_new_env};
+    use crate::environment::Environment;
+
+    #[test]
+    fn test_complex_constructor_and_parts() {
+        assert_eq!(eval_in_new_env("(complex 1 2)"), "#C(1 2)");
+        assert_eq!(eval_in_new_env("(complex 1/2 -3)"), "#C(1/2 -3)");
+        assert_eq!(eval_in_new_env("(complex 5 0)"), "5");
+        assert_eq!(eval_in_new_env("(complex 1.5 2)"), "#C(1.5 2.0)");
+        assert_eq!(eval_in_new_env("(realpart #C(3 4))"), "3");
+        assert_eq!(eval_in_new_env("(imagpart #C(3 4))"), "4");
+        assert_eq!(eval_in_new_env("(imagpart 7)"), "0");
+        assert_eq!(eval_in_new_env("(conjugate #C(3 4))"), "#C(3 -4)");
+        assert_eq!(eval_in_new_env("(conjugate 3)"), "3");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(complex \"a\" 1)", &mut env),
+            Err(LispError::new("complex: parts must be real numbers"))
+        );
+        assert_eq!(
+            eval_str("(realpart 'x)", &mut env),
+            Err(LispError::new("realpart: argument must be a number, got x"))
+        );
+    }
+
+    #[test]
+    fn test_complex_arithmetic() {
+        assert_eq!(eval_in_new_env("(+ #C(1 2) #C(3 -2))"), "4");
+        assert_eq!(eval_in_new_env("(+ #C(1 2) 1/2)"), "#C(3/2 2)");
+        assert_eq!(eval_in_new_env("(- #C(1 2) #C(1 1))"), "#C(0 1)");
+        assert_eq!(eval_in_new_env("(* #C(0 1) #C(0 1))"), "-1");
+        assert_eq!(eval_in_new_env("(* #C(1 2) #C(3 4))"), "#C(-5 10)");
+        assert_eq!(eval_in_new_env("(/ #C(1 2) #C(3 4))"), "#C(11/25 2/25)");
+        assert_eq!(eval_in_new_env("(/ 1 #C(0 1))"), "#C(0 -1)");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(/ #C(1 2) 0)", &mut env), Err(LispError::new("Division by zero")));
+    }
+
+    #[test]
+    fn test_abs_and_phase() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(abs -5)", &mut env).unwrap(), Expr::Number(5));
+        assert_eq!(eval_str("(abs -1/2)", &mut env).unwrap().to_string(), "1/2");
+        assert_eq!(eval_str("(abs -2.5)", &mut env).unwrap(), Expr::Float(2.5));
+        assert_eq!(eval_str("(abs #C(3 4))", &mut env).unwrap(), Expr::Float(5.0));
+        assert_eq!(
+            eval_str("(abs -9223372036854775808)", &mut env).unwrap().to_string(),
+            "9223372036854775808"
+        );
+        assert_eq!(eval_str("(phase 1)", &mut env).unwrap(), Expr::Float(0.0));
+        assert_eq!(eval_str("(phase -1)", &mut env).unwrap(), Expr::Float(std::f64::consts::PI));
+        assert_eq!(eval_str("(phase #C(0 1))", &mut env).unwrap(), Expr::Float(std::f64::consts::FRAC_PI_2));
+    }
+
+    #[test]
+    fn test_sqrt_exp_log_go_complex_when_required() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(sqrt 4)", &mut env).unwrap(), Expr::Float(2.0));
+        assert_eq!(eval_str("(sqrt -1)", &mut env).unwrap(), Math::float_complex(0.0, 1.0));
+        assert_eq!(eval_str("(sqrt -4)", &mut env).unwrap(), Math::float_complex(0.0, 2.0));
+        assert_eq!(eval_str("(sqrt #C(3 4))", &mut env).unwrap(), Math::float_complex(2.0, 1.0));
+        assert_eq!(eval_str("(sqrt #C(3 -4))", &mut env).unwrap(), Math::float_complex(2.0, -1.0));
+
+        assert_eq!(eval_str("(exp 0)", &mut env).unwrap(), Expr::Float(1.0));
+        let Expr::Complex(re, im) = eval_str("(exp #C(0 3.141592653589793))", &mut env).unwrap() else {
+            panic!("exp of a complex number should be complex");
+        };
+        assert_eq!(*re, Expr::Float(-1.0));
+        assert!(matches!(*im, Expr::Float(f) if f.abs() < 1e-15));
+
+        assert_eq!(eval_str("(log 1)", &mut env).unwrap(), Expr::Float(0.0));
+        assert_eq!(eval_str("(log 8 2)", &mut env).unwrap(), Expr::Float(3.0));
+        assert_eq!(eval_str("(log -1)", &mut env).unwrap(), Math::float_complex(0.0, std::f64::consts::PI));
+        assert_eq!(eval_str("(log 0)", &mut env), Err(LispError::new("log: logarithm of zero")));
+    }
+
+    #[test]
+    fn test_floor_family_returns_quotient_and_remainder() {
+        assert_eq!(eval_in_new_env("(multiple-value-list (floor 7 2))"), "(3 1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (floor -7 2))"), "(-4 1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (ceiling 7 2))"), "(4 -1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (truncate -7 2))"), "(-3 -1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (round 5 2))"), "(2 1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (round 7 2))"), "(4 -1)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (round -5/2))"), "(-2 -1/2)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (floor 7/2))"), "(3 1/2)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (floor 2.5))"), "(2 0.5)");
+        assert_eq!(eval_in_new_env("(multiple-value-list (truncate -2.5))"), "(-2 -0.5)");
+        assert_eq!(eval_in_new_env("(floor 100000000000000000000.0)"), "100000000000000000000");
+        // 只使用主值时就是商
+        assert_eq!(eval_in_new_env("(+ (floor 7 2) 1)"), "4");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(floor 1 0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(eval_str("(floor 1.5 0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(
+            eval_str("(floor #C(1 2))", &mut env),
+            Err(LispError::new("floor: argument must be a real number, got #C(1 2)"))
+        );
+    }
+
+    #[test]
+    fn test_mod_and_rem() {
+        assert_eq!(eval_in_new_env("(mod 7 3)"), "1");
+        assert_eq!(eval_in_new_env("(mod -7 3)"), "2");
+        assert_eq!(eval_in_new_env("(mod 7 -3)"), "-2");
+        assert_eq!(eval_in_new_env("(rem -7 3)"), "-1");
+        assert_eq!(eval_in_new_env("(rem 7 -3)"), "1");
+        assert_eq!(eval_in_new_env("(mod 5.5 2)"), "1.5");
+        assert_eq!(eval_in_new_env("(mod 18446744073709551617 10)"), "7");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(mod 1 0)", &mut env), Err(LispError::new("Division by zero")));
+        assert_eq!(eval_str("(rem 1)", &mut env), Err(LispError::new("rem expects exactly two arguments")));
+    }
+
+    #[test]
+    fn test_min_and_max() {
+        assert_eq!(eval_in_new_env("(min 3 1 2)"), "1");
+        assert_eq!(eval_in_new_env("(max 3 1/2 2.5)"), "3");
+        assert_eq!(eval_in_new_env("(min 1/3 0.5)"), "1/3");
+        assert_eq!(eval_in_new_env("(max -1)"), "-1");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(max)", &mut env), Err(LispError::new("max requires at least one argument")));
+        assert_eq!(
+            eval_str("(min 1 \"a\")", &mut env),
+            Err(LispError::new("min: argument must be a real number, got \"a\""))
+        );
+    }
+
+    #[test]
+    fn test_expt() {
+        assert_eq!(eval_in_new_env("(expt 2 10)"), "1024");
+        assert_eq!(eval_in_new_env("(expt 2 100)"), "1267650600228229401496703205376");
+        assert_eq!(eval_in_new_env("(expt 2 -2)"), "1/4");
+        assert_eq!(eval_in_new_env("(expt 2/3 3)"), "8/27");
+        assert_eq!(eval_in_new_env("(expt #C(0 1) 2)"), "-1");
+        assert_eq!(eval_in_new_env("(expt 0 0)"), "1");
+        assert_eq!(eval_in_new_env("(expt 1.5 2)"), "2.25");
+        assert_eq!(eval_in_new_env("(expt 4 0.5)"), "2.0");
+        assert_eq!(eval_in_new_env("(expt 0 0.5)"), "0.0");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(expt 0 -1)", &mut env), Err(LispError::new("Division by zero")));
+        let Expr::Complex(re, im) = eval_str("(expt -8 1/3)", &mut env).unwrap() else {
+            panic!("a negative base with a fractional power should be complex");
+        };
+        assert!(matches!(*re, Expr::Float(f) if (f - 1.0).abs() < 1e-12));
+        assert!(matches!(*im, Expr::Float(f) if (f - 3f64.sqrt()).abs() < 1e-12));
+
+        // 精确结果过大时报错，而 -1、0、1 和浮点数的幂不受限制
+        assert_eq!(eval_str("(expt 10 1000000000)", &mut env), Err(LispError::new("expt: exponent 1000000000 is too large")));
+        assert_eq!(eval_str("(expt 1/3 -1000000000)", &mut env), Err(LispError::new("expt: exponent -1000000000 is too large")));
+        assert_eq!(eval_str("(expt -1 1000000001)", &mut env), Ok(Expr::Number(-1)));
+        assert_eq!(eval_str("(expt 1.0 1000000000)", &mut env), Ok(Expr::Float(1.0)));
+        assert_eq!(eval_in_new_env("(integer-length (expt 2 100000))"), "100001");
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(eval_in_new_env("(isqrt 0)"), "0");
+        assert_eq!(eval_in_new_env("(isqrt 15)"), "3");
+        assert_eq!(eval_in_new_env("(isqrt 16)"), "4");
+        assert_eq!(eval_in_new_env("(isqrt 100000000000000000000000000000000000000)"), "10000000000000000000");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(isqrt -1)", &mut env),
+            Err(LispError::new("isqrt: argument must be a non-negative integer, got -1"))
+        );
+        assert_eq!(eval_str("(isqrt 2.0)", &mut env), Err(LispError::new("isqrt: argument must be an integer, got 2.0")));
+    }
+
+    #[test]
+    fn test_trig_and_hyperbolic() {
+        let mut env = Environment::initialize();
+        let float = |input: &str, env: &mut Environment| match eval_str(input, env).unwrap() {
+            Expr::Float(f) => f,
+            other => panic!("expected a float from {}, got {}", input, other),
+        };
+        assert_eq!(float("(sin 0)", &mut env), 0.0);
+        assert_eq!(float("(cos 0)", &mut env), 1.0);
+        assert!((float("(tan 1)", &mut env) - 1f64.tan()).abs() < 1e-15);
+        assert!((float("(asin 1)", &mut env) - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        assert_eq!(float("(acos 1)", &mut env), 0.0);
+        assert!((float("(atan 1 1)", &mut env) - std::f64::consts::FRAC_PI_4).abs() < 1e-15);
+        assert!((float("(atan -1 -1)", &mut env) + 3.0 * std::f64::consts::FRAC_PI_4).abs() < 1e-15);
+        assert_eq!(float("(sinh 0)", &mut env), 0.0);
+        assert_eq!(float("(cosh 0)", &mut env), 1.0);
+        assert_eq!(float("(tanh 0)", &mut env), 0.0);
+        assert_eq!(float("(asinh 0)", &mut env), 0.0);
+        assert_eq!(float("(acosh 1)", &mut env), 0.0);
+        assert!((float("(atanh 1/2)", &mut env) - 0.5f64.atanh()).abs() < 1e-15);
+        // 参数只求值一次
+        eval_str("(setf calls 0)", &mut env).unwrap();
+        eval_str("(atanh (progn (setf calls (+ calls 1)) 1/2))", &mut env).unwrap();
+        assert_eq!(eval_str("calls", &mut env), Ok(Expr::Number(1)));
+        // 实轴上的分支切割：x > 1 时虚部为 +π/2，x < -1 时为 -π/2
+        for (input, sign) in [("(atanh 2)", 1.0), ("(atanh -2)", -1.0)] {
+            let Expr::Complex(re, im) = eval_str(input, &mut env).unwrap() else {
+                panic!("{} should be complex", input);
+            };
+            assert!(matches!(*re, Expr::Float(f) if (f - sign * 0.5 * 3f64.ln()).abs() < 1e-15));
+            assert!(matches!(*im, Expr::Float(f) if f == sign * std::f64::consts::FRAC_PI_2));
+        }
+
+        // 超出实数定义域时返回复数
+        let Expr::Complex(re, im) = eval_str("(asin 2)", &mut env).unwrap() else {
+            panic!("asin of 2 should be complex");
+        };
+        assert!(matches!(*re, Expr::Float(f) if (f - std::f64::consts::FRAC_PI_2).abs() < 1e-12));
+        assert!(matches!(*im, Expr::Float(f) if (f.abs() - 1.3169578969248166).abs() < 1e-12));
+        assert!(matches!(eval_str("(acosh 0)", &mut env), Ok(Expr::Complex(_, _))));
+        assert!(matches!(eval_str("(sin #C(0 1))", &mut env), Ok(Expr::Complex(_, _))));
+
+        assert_eq!(eval_str("(atanh 1)", &mut env), Err(LispError::new("atanh: argument must not be 1")));
+        assert_eq!(
+            eval_str("(sin 'x)", &mut env),
+            Err(LispError::new("sin: argument must be a number, got x"))
+        );
+    }
+
+    #[test]
+    fn test_gcd_lcm_signum() {
+        assert_eq!(eval_in_new_env("(gcd)"), "0");
+        assert_eq!(eval_in_new_env("(gcd 12 -18 8)"), "2");
+        assert_eq!(eval_in_new_env("(lcm)"), "1");
+        assert_eq!(eval_in_new_env("(lcm 4 6 -10)"), "60");
+        assert_eq!(eval_in_new_env("(lcm 4 0)"), "0");
+        assert_eq!(eval_in_new_env("(signum -5)"), "-1");
+        assert_eq!(eval_in_new_env("(signum 0)"), "0");
+        assert_eq!(eval_in_new_env("(signum 1/2)"), "1");
+        assert_eq!(eval_in_new_env("(signum -2.5)"), "-1.0");
+        assert_eq!(eval_in_new_env("(signum #C(3 4))"), "#C(0.6 0.8)");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(gcd 1.5)", &mut env), Err(LispError::new("gcd: argument must be an integer, got 1.5")));
+    }
+
+    #[test]
+    fn test_increment_and_predicates() {
+        assert_eq!(eval_in_new_env("(1+ 41)"), "42");
+        assert_eq!(eval_in_new_env("(1- 1/2)"), "-1/2");
+        assert_eq!(eval_in_new_env("(1+ 9223372036854775807)"), "9223372036854775808");
+        assert_eq!(eval_in_new_env("(zerop 0)"), "t");
+        assert_eq!(eval_in_new_env("(zerop 0.0)"), "t");
+        assert_eq!(eval_in_new_env("(zerop 1/2)"), "()");
+        assert_eq!(eval_in_new_env("(plusp 1/2)"), "t");
+        assert_eq!(eval_in_new_env("(plusp 0)"), "()");
+        assert_eq!(eval_in_new_env("(minusp -0.5)"), "t");
+        assert_eq!(eval_in_new_env("(evenp 18446744073709551616)"), "t");
+        assert_eq!(eval_in_new_env("(oddp -3)"), "t");
+        assert_eq!(eval_in_new_env("(oddp 4)"), "()");
+
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(evenp 2.0)", &mut env), Err(LispError::new("evenp: argument must be an integer, got 2.0")));
+        assert_eq!(
+            eval_str("(plusp #C(1 1))", &mut env),
+            Err(LispError::new("plusp: argument must be a real number, got #C(1 1)"))
+        );
+        assert_eq!(eval_str("(1+ 'a)", &mut env), Err(LispError::new("1+: argument must be a number, got a")));
+    }
+}
@@ -1,11 +1,27 @@
 // operator/mod.rs
 
+pub mod alist;
 pub mod arithmetic;
+pub mod array;
+pub mod bitwise;
+pub mod character;
+pub mod clos;
 pub mod list;
 pub mod comparison;
 pub mod control;
+pub mod format;
+pub mod hash_table;
 pub mod set;
+pub mod string;
+pub mod structure;
+pub mod symbol;
+pub mod types;
 pub mod lambda;
+pub mod math;
+pub mod package;
+pub mod random;
+#[cfg(test)]
+pub mod test_util;
 
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -17,9 +33,18 @@ use lazy_static::lazy_static;
 // 定义操作符函数类型
 type OperatorFn = fn(&[Expr], &mut Environment) -> Result<Expr, LispError>;
 
+// 定义 setf 位置函数类型，例如 (setf (aref v 0) x)：参数是求值后的位置参数和新值，返回新值
+type SetfFn = fn(&[Expr], Expr, &mut Environment) -> Result<Expr, LispError>;
+
+// 定义需要改写位置本身的 setf 函数类型，例如 (setf (getf plist :k) v) 要把新的属性表写回 plist：
+// 参数是未求值的位置参数和新值形式，返回新值
+type SetfFormFn = fn(&[Expr], &Expr, &mut Environment) -> Result<Expr, LispError>;
+
 // 定义 OperatorRegistry 结构体
 pub struct OperatorRegistry {
     operators: HashMap<String, OperatorFn>,
+    setf_functions: HashMap<String, SetfFn>,
+    setf_forms: HashMap<String, SetfFormFn>,
 }
 
 // 使用 lazy_static 定义单例 OperatorRegistry
@@ -32,6 +57,8 @@ impl OperatorRegistry {
     pub fn new() -> Self {
         OperatorRegistry {
             operators: HashMap::new(),
+            setf_functions: HashMap::new(),
+            setf_forms: HashMap::new(),
         }
     }
 
@@ -46,21 +73,94 @@ impl OperatorRegistry {
         let registry = OPERATOR_REGISTRY.lock().unwrap();
         registry.operators.get(name).copied()
     }
+
+    // 全部已注册操作符的名字
+    pub fn names() -> Vec<String> {
+        let registry = OPERATOR_REGISTRY.lock().unwrap();
+        registry.operators.keys().cloned().collect()
+    }
+
+    // 注册一个可以用 setf 赋值的位置
+    pub fn register_setf(name: &str, func: SetfFn) {
+        let mut registry = OPERATOR_REGISTRY.lock().unwrap();
+        registry.setf_functions.insert(name.to_string(), func);
+    }
+
+    // 获取一个位置的 setf 函数
+    pub fn get_setf(name: &str) -> Option<SetfFn> {
+        let registry = OPERATOR_REGISTRY.lock().unwrap();
+        registry.setf_functions.get(name).copied()
+    }
+
+    // 注册一个接收未求值位置参数的 setf 位置
+    pub fn register_setf_form(name: &str, func: SetfFormFn) {
+        let mut registry = OPERATOR_REGISTRY.lock().unwrap();
+        registry.setf_forms.insert(name.to_string(), func);
+    }
+
+    pub fn get_setf_form(name: &str) -> Option<SetfFormFn> {
+        let registry = OPERATOR_REGISTRY.lock().unwrap();
+        registry.setf_forms.get(name).copied()
+    }
+}
+
+// 检查实参个数在 min 和 max 之间，max 为 usize::MAX 表示没有上限
+pub fn expect_args(name: &str, args: &[Expr], min: usize, max: usize) -> Result<(), LispError> {
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            min.to_string()
+        } else if max == usize::MAX {
+            format!("at least {}", min)
+        } else {
+            format!("{} to {}", min, max)
+        };
+        let noun = if min == 1 && max == 1 { "argument" } else { "arguments" };
+        return Err(LispError::new(&format!("{} expects {} {}", name, expected, noun)));
+    }
+    Ok(())
 }
 
+use alist::register_alist_operators;
 use arithmetic::register_arithmetic_operators;
+use array::register_array_operators;
+use bitwise::register_bitwise_operators;
+use character::register_character_operators;
+use clos::register_clos_operators;
 use comparison::register_comparison_operators;
 use control::register_control_operators;
+use format::register_format_operators;
+use hash_table::register_hash_table_operators;
 use lambda::register_lambda_operators;
 use list::register_list_operators;
+use math::register_math_operators;
+use random::register_random_operators;
 use set::register_set_operators;
+use string::register_string_operators;
+use structure::register_structure_operators;
+use package::register_package_operators;
+use symbol::register_symbol_operators;
+use types::register_type_operators;
 
 // 初始化操作符注册表
 pub fn initialize() {
+    register_alist_operators();
     register_arithmetic_operators();
+    register_array_operators();
+    register_bitwise_operators();
+    register_character_operators();
+    register_clos_operators();
     register_comparison_operators();
     register_control_operators();
+    register_format_operators();
+    register_hash_table_operators();
     register_lambda_operators();
     register_list_operators();
+    register_math_operators();
+    register_random_operators();
     register_set_operators();
+    register_string_operators();
+    register_structure_operators();
+    register_package_operators();
+    register_symbol_operators();
+    register_type_operators();
 }
\ No newline at end of file
@@ -0,0 +1,295 @@
+// operator/package.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::package::{self, COMMON_LISP};
+
+// 包以名字表示：defpackage 和 in-package 返回包名字符串
+pub struct PackageOps;
+
+impl PackageOps {
+    // 名字标识符：字符串，或者符号的名字（关键字、#:name 和 pkg::name 都只取名字部分）
+    fn name_designator(caller: &str, value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Str(name) => Ok(name.clone()),
+            Expr::Symbol(name) => {
+                let name = package::uninterned_name(name).or_else(|| name.strip_prefix(':')).unwrap_or(name);
+                Ok(package::symbol_name(name).to_string())
+            }
+            other => Err(LispError::new(&format!("{}: {} is not a name", caller, other))),
+        }
+    }
+
+    // 包名到正式名字；没有给出时是当前包
+    fn package_argument(caller: &str, value: Option<&Expr>, env: &Environment) -> Result<String, LispError> {
+        match value {
+            Some(value) => {
+                let name = PackageOps::name_designator(caller, value)?;
+                env.packages().resolve(&name).map_err(|message| LispError::new(&format!("{}: {}", caller, message)))
+            }
+            None => Ok(env.packages().current().to_string()),
+        }
+    }
+
+    // 单个符号或者符号列表
+    fn symbols_argument(caller: &str, value: &Expr) -> Result<Vec<String>, LispError> {
+        let symbol = |value: &Expr| match value {
+            Expr::Symbol(symbol) => Ok(symbol.clone()),
+            other => Err(LispError::new(&format!("{}: {} is not a symbol", caller, other))),
+        };
+        match value {
+            Expr::List(items) => items.iter().map(symbol).collect(),
+            other => Ok(vec![symbol(other)?]),
+        }
+    }
+
+    fn package_error(caller: &str) -> impl Fn(String) -> LispError + '_ {
+        move |message| LispError::new(&format!("{}: {}", caller, message))
+    }
+
+    // (defpackage name option...)，选项有 (:use pkg...)、(:export name...)、(:import-from pkg name...)、
+    // (:nicknames name...) 和 (:documentation "...")；没有 :use 选项时使用 common-lisp
+    pub fn eval_defpackage(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let name = match args.first() {
+            Some(name) => PackageOps::name_designator("defpackage", name)?,
+            None => return Err(LispError::new("defpackage requires a package name")),
+        };
+        let mut nicknames = Vec::new();
+        let mut uses = None;
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+        let mut documentation = None;
+        for option in &args[1..] {
+            let (keyword, values) = match option {
+                Expr::List(parts) if parts.first().is_some_and(Expr::is_keyword) => match &parts[0] {
+                    Expr::Symbol(keyword) => (keyword.as_str(), &parts[1..]),
+                    _ => unreachable!(),
+                },
+                other => return Err(LispError::new(&format!("defpackage: invalid option {}", other))),
+            };
+            let names = || values.iter().map(|value| PackageOps::name_designator("defpackage", value)).collect::<Result<Vec<_>, _>>();
+            match keyword {
+                ":nicknames" => nicknames.extend(names()?),
+                ":use" => uses.get_or_insert_with(Vec::new).extend(names()?),
+                ":export" => exports.extend(names()?),
+                ":import-from" => {
+                    let mut names = names()?;
+                    if names.is_empty() {
+                        return Err(LispError::new("defpackage: :import-from requires a package name"));
+                    }
+                    let from = names.remove(0);
+                    imports.push((from, names));
+                }
+                ":documentation" => match values {
+                    [Expr::Str(doc)] => documentation = Some(doc.clone()),
+                    _ => return Err(LispError::new("defpackage: :documentation requires a string")),
+                },
+                other => return Err(LispError::new(&format!("defpackage: unsupported option {}", other))),
+            }
+        }
+
+        let error = PackageOps::package_error("defpackage");
+        let mut packages = env.packages();
+        let package = packages.define(&name, &nicknames).map_err(&error)?;
+        for used in uses.unwrap_or_else(|| vec![COMMON_LISP.to_string()]) {
+            let used = packages.resolve(&used).map_err(&error)?;
+            packages.use_package(&package, &used).map_err(&error)?;
+        }
+        for (from, names) in imports {
+            let from = packages.resolve(&from).map_err(&error)?;
+            for name in names {
+                let symbol = packages
+                    .find_symbol(&from, &name)
+                    .ok_or_else(|| error(format!("symbol {} not found in package {}", name, from)))?;
+                packages.import(&package, &symbol).map_err(&error)?;
+            }
+        }
+        for name in exports {
+            let symbol = packages.intern(&package, &name);
+            packages.export(&package, &symbol).map_err(&error)?;
+        }
+        drop(packages);
+        if let Some(doc) = documentation {
+            env.set_documentation(&package, "package", doc);
+        }
+        Ok(Expr::Str(package))
+    }
+
+    // (in-package name)：之后读入的符号在这个包中解析
+    pub fn eval_in_package(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("in-package", args, 1, 1)?;
+        let name = PackageOps::name_designator("in-package", &args[0])?;
+        let package = env.packages().set_current(&name).map_err(PackageOps::package_error("in-package"))?;
+        Ok(Expr::Str(package))
+    }
+
+    // (export symbols [package])
+    pub fn eval_export(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("export", args, 1, 2)?;
+        let symbols = PackageOps::symbols_argument("export", &Evaluator::eval(&args[0], env)?)?;
+        let package = match args.get(1) {
+            Some(arg) => PackageOps::package_argument("export", Some(&Evaluator::eval(arg, env)?), env)?,
+            None => PackageOps::package_argument("export", None, env)?,
+        };
+        for symbol in symbols {
+            env.packages().export(&package, &symbol).map_err(PackageOps::package_error("export"))?;
+        }
+        Ok(Expr::t())
+    }
+
+    // (import symbols [package])
+    pub fn eval_import(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("import", args, 1, 2)?;
+        let symbols = PackageOps::symbols_argument("import", &Evaluator::eval(&args[0], env)?)?;
+        let package = match args.get(1) {
+            Some(arg) => PackageOps::package_argument("import", Some(&Evaluator::eval(arg, env)?), env)?,
+            None => PackageOps::package_argument("import", None, env)?,
+        };
+        for symbol in symbols {
+            env.packages().import(&package, &symbol).map_err(PackageOps::package_error("import"))?;
+        }
+        Ok(Expr::t())
+    }
+
+    // (use-package packages [package])
+    pub fn eval_use_package(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("use-package", args, 1, 2)?;
+        let used = match Evaluator::eval(&args[0], env)? {
+            Expr::List(items) => items,
+            other => vec![other],
+        };
+        let package = match args.get(1) {
+            Some(arg) => PackageOps::package_argument("use-package", Some(&Evaluator::eval(arg, env)?), env)?,
+            None => PackageOps::package_argument("use-package", None, env)?,
+        };
+        for used in &used {
+            let used = PackageOps::package_argument("use-package", Some(used), env)?;
+            env.packages().use_package(&package, &used).map_err(PackageOps::package_error("use-package"))?;
+        }
+        Ok(Expr::t())
+    }
+}
+
+pub fn register_package_operators() {
+    OperatorRegistry::register("defpackage", PackageOps::eval_defpackage);
+    OperatorRegistry::register("in-package", PackageOps::eval_in_package);
+    OperatorRegistry::register("export", PackageOps::eval_export);
+    OperatorRegistry::register("import", PackageOps::eval_import);
+    OperatorRegistry::register("use-package", PackageOps::eval_use_package);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    fn define_libraries(env: &mut Environment) {
+        for form in [
+            "(defpackage :json (:use :cl) (:export #:parse))",
+            "(in-package :json)",
+            "(defun parse (s) (cond (s \"json\") (t nil)))",
+            "(defpackage \"CSV\" (:nicknames :comma) (:export :parse))",
+            "(in-package :csv)",
+            "(defun parse (s) \"csv\")",
+            "(defun helper () 'help)",
+            "(in-package :cl-user)",
+        ] {
+            eval_str(form, env).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_packages_keep_definitions_apart() {
+        let mut env = Environment::initialize();
+        define_libraries(&mut env);
+        assert_eq!(eval_str("(json:parse \"x\")", &mut env), Ok(Expr::Str("json".to_string())));
+        assert_eq!(eval_str("(csv:parse \"x\")", &mut env), Ok(Expr::Str("csv".to_string())));
+        assert_eq!(eval_str("(comma:parse \"x\")", &mut env), Ok(Expr::Str("csv".to_string())));
+        assert_eq!(eval_str("(parse \"x\")", &mut env), Err(LispError::new("Undefined function: parse")));
+        assert_eq!(eval_to_string("(csv::helper)", &mut env), "csv::help");
+        assert_eq!(
+            eval_str("(csv:helper)", &mut env),
+            Err(LispError::new("symbol helper is not external in package csv"))
+        );
+        assert_eq!(eval_str("(missing:parse)", &mut env), Err(LispError::new("package missing does not exist")));
+    }
+
+    #[test]
+    fn test_use_package_import_and_export() {
+        let mut env = Environment::initialize();
+        define_libraries(&mut env);
+        assert_eq!(eval_str("(use-package :json)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(parse \"x\")", &mut env), Ok(Expr::Str("json".to_string())));
+        assert_eq!(
+            eval_str("(use-package :csv)", &mut env),
+            Err(LispError::new("use-package: using package csv makes csv::parse conflict with json::parse in package common-lisp-user"))
+        );
+        assert_eq!(
+            eval_str("(import 'csv:parse)", &mut env),
+            Err(LispError::new("import: importing csv::parse conflicts with json::parse in package common-lisp-user"))
+        );
+
+        eval_str("(import 'csv::helper)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(helper)", &mut env), "csv::help");
+        eval_str("(export 'csv::helper :csv)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(csv:helper)", &mut env), "csv::help");
+    }
+
+    #[test]
+    fn test_symbols_print_relative_to_the_current_package() {
+        let mut env = Environment::initialize();
+        eval_str("(defpackage :other (:export #:shown))", &mut env).unwrap();
+        eval_str("(in-package :other)", &mut env).unwrap();
+        assert_eq!(eval_to_string("'foo", &mut env), "foo");
+        assert_eq!(eval_str("(format nil \"~a\" 'foo)", &mut env), Ok(Expr::Str("foo".to_string())));
+        assert_eq!(eval_to_string("'(car shown)", &mut env), "(car shown)");
+        eval_str("(setf other-symbols '(foo shown))", &mut env).unwrap();
+
+        // 回到 cl-user 后，导出的符号写作 pkg:name，其余写作 pkg::name
+        eval_str("(cl:in-package :cl-user)", &mut env).unwrap();
+        assert_eq!(eval_to_string("other::other-symbols", &mut env), "(other::foo other:shown)");
+        assert_eq!(eval_str("(format nil \"~a\" 'other::foo)", &mut env), Ok(Expr::Str("other::foo".to_string())));
+        assert_eq!(eval_to_string("'(car foo :key)", &mut env), "(car foo :key)");
+
+        // 使用该包后，导出的符号可以直接访问
+        eval_str("(use-package :other)", &mut env).unwrap();
+        assert_eq!(eval_to_string("'other:shown", &mut env), "shown");
+        eval_str("(in-package :other)", &mut env).unwrap();
+        assert_eq!(eval_to_string("'cl-user::foo", &mut env), "common-lisp-user::foo");
+    }
+
+    #[test]
+    fn test_extensions_live_in_their_own_package() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(join '(\"a\" \"b\") \",\")", &mut env), Ok(Expr::Str("a,b".to_string())));
+        assert_eq!(eval_to_string("'(car join)", &mut env), "(car join)");
+
+        // 只使用 common-lisp 的包看不到扩展，需要写出 rust-lisp 包名
+        eval_str("(defpackage :plain (:use :cl))", &mut env).unwrap();
+        eval_str("(in-package :plain)", &mut env).unwrap();
+        assert_eq!(eval_to_string("'(car join split-string)", &mut env), "(car join split-string)");
+        assert_eq!(eval_to_string("'rust-lisp:split-string", &mut env), "rust-lisp:split-string");
+        assert_eq!(eval_to_string("'cl-user::join", &mut env), "rust-lisp:join");
+        assert_eq!(eval_str("(rust-lisp:join '(\"a\" \"b\") \"-\")", &mut env), Ok(Expr::Str("a-b".to_string())));
+        assert!(eval_str("(join '(\"a\") \"-\")", &mut env).is_err());
+        assert_eq!(eval_str("(cl:car cl:nil)", &mut env), Ok(Expr::nil()));
+        assert!(eval_str("'cl:join", &mut env).is_err());
+        assert!(eval_str("'cl:seed-random-state", &mut env).is_err());
+    }
+
+    #[test]
+    fn test_structures_in_packages() {
+        let mut env = Environment::initialize();
+        eval_str("(defpackage :geo (:export :make-point :point-x))", &mut env).unwrap();
+        eval_str("(in-package :geo)", &mut env).unwrap();
+        eval_str("(defstruct point x y)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(make-point :x 1 :y 2)", &mut env), "#S(point :x 1 :y 2)");
+        eval_str("(in-package :cl-user)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(geo:make-point :x 1 :y 2)", &mut env), "#S(geo::point :x 1 :y 2)");
+        assert_eq!(eval_str("(geo:point-x (geo:make-point :x 3))", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_str("(in-package :nowhere)", &mut env), Err(LispError::new("in-package: package nowhere does not exist")));
+    }
+}
@@ -0,0 +1,185 @@
+// operator/random.rs
+
+use crate::operator::OperatorRegistry;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::bignum::BigInt;
+use crate::random::RandomState;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct Random;
+
+impl Random {
+    pub fn make_state(state: RandomState) -> Expr {
+        Expr::RandomState(Rc::new(RefCell::new(state)))
+    }
+
+    // 省略状态参数时使用 *random-state* 的当前值
+    fn current_state(name: &str, env: &Environment) -> Result<Rc<RefCell<RandomState>>, LispError> {
+        match env.get_symbol("*random-state*") {
+            Some(Expr::RandomState(state)) => Ok(state.clone()),
+            Some(other) => Err(LispError::new(&format!("{}: *random-state* is not a random state, got {}", name, other))),
+            None => Err(LispError::new(&format!("{}: *random-state* is unbound", name))),
+        }
+    }
+
+    // (random limit [state])：limit 为正整数时返回 [0, limit) 内的整数，为正浮点数时返回同范围的浮点数
+    pub fn eval_random(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(LispError::new("random expects one or two arguments"));
+        }
+        let limit = Evaluator::eval(&args[0], env)?;
+        let state = match args.get(1) {
+            Some(arg) => match Evaluator::eval(arg, env)? {
+                Expr::RandomState(state) => state,
+                other => return Err(LispError::new(&format!("random: state must be a random state, got {}", other))),
+            },
+            None => Random::current_state("random", env)?,
+        };
+        let mut state = state.borrow_mut();
+        match limit {
+            Expr::Number(n) if n > 0 => Ok(Expr::Number(state.below_u64(n as u64) as i64)),
+            Expr::BigInt(ref n) if !n.is_negative() => Ok(Expr::from_bigint(state.below_bigint(n))),
+            Expr::Float(x) if x > 0.0 && x.is_finite() => loop {
+                // 乘法舍入可能恰好得到 limit 本身，这时重新取一个
+                let value = state.unit_f64() * x;
+                if value < x {
+                    break Ok(Expr::Float(value));
+                }
+            },
+            other => Err(LispError::new(&format!("random: limit must be a positive integer or float, got {}", other))),
+        }
+    }
+
+    // (make-random-state [state])：省略或为 nil 时复制 *random-state*，为随机状态时复制它，为 t 时用当前时间做种子
+    pub fn eval_make_random_state(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() > 1 {
+            return Err(LispError::new("make-random-state expects at most one argument"));
+        }
+        let source = match args.first() {
+            Some(arg) => Evaluator::eval(arg, env)?,
+            None => Expr::nil(),
+        };
+        match source {
+            Expr::RandomState(state) => Ok(Random::make_state(state.borrow().clone())),
+            ref value if !value.is_truthy() => {
+                let state = Random::current_state("make-random-state", env)?;
+                let copy = state.borrow().clone();
+                Ok(Random::make_state(copy))
+            }
+            ref value if *value == Expr::t() => {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_nanos() as u64)
+                    .unwrap_or(0);
+                Ok(Random::make_state(RandomState::from_seed(seed)))
+            }
+            other => Err(LispError::new(&format!("make-random-state: argument must be a random state, t or nil, got {}", other))),
+        }
+    }
+
+    // (seed-random-state seed)：由整数种子得到新的随机状态，同一个种子总是给出同样的序列
+    pub fn eval_seed_random_state(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("seed-random-state expects exactly one argument"));
+        }
+        let seed = match Evaluator::eval(&args[0], env)? {
+            Expr::Number(n) => n as u64,
+            // 任意精度的种子只取低 64 位
+            Expr::BigInt(n) => {
+                let low = n.and(&BigInt::from_i128(u64::MAX as i128));
+                match low.to_i64() {
+                    Some(value) => value as u64,
+                    None => low.sub(&BigInt::from_i128(1i128 << 64)).to_i64().unwrap_or(0) as u64,
+                }
+            }
+            other => return Err(LispError::new(&format!("seed-random-state: seed must be an integer, got {}", other))),
+        };
+        Ok(Random::make_state(RandomState::from_seed(seed)))
+    }
+
+    pub fn eval_random_state_p(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new("random-state-p expects exactly one argument"));
+        }
+        let value = Evaluator::eval(&args[0], env)?;
+        Ok(Expr::from_bool(matches!(value, Expr::RandomState(_))))
+    }
+}
+
+pub fn register_random_operators() {
+    OperatorRegistry::register("random", Random::eval_random);
+    OperatorRegistry::register("make-random-state", Random::eval_make_random_state);
+    OperatorRegistry::register("seed-random-state", Random::eval_seed_random_state);
+    OperatorRegistry::register("random-state-p", Random::eval_random_state_p);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::eval_str;
+
+    #[test]
+    fn test_random_ranges() {
+        let mut env = Environment::initialize();
+        for _ in 0..200 {
+            match eval_str("(random 10)", &mut env).unwrap() {
+                Expr::Number(n) => assert!((0..10).contains(&n)),
+                other => panic!("expected an integer, got {}", other),
+            }
+            match eval_str("(random 1.5)", &mut env).unwrap() {
+                Expr::Float(x) => assert!((0.0..1.5).contains(&x)),
+                other => panic!("expected a float, got {}", other),
+            }
+        }
+        match eval_str("(random 100000000000000000000000)", &mut env).unwrap() {
+            Expr::Number(_) | Expr::BigInt(_) => {}
+            other => panic!("expected an integer, got {}", other),
+        }
+        assert_eq!(eval_str("(random 1)", &mut env), Ok(Expr::Number(0)));
+    }
+
+    #[test]
+    fn test_seeded_states_are_reproducible() {
+        let mut env = Environment::initialize();
+        let first = eval_str("(setf s (seed-random-state 42))", &mut env)
+            .and_then(|_| eval_str("(random 1000000 s)", &mut env))
+            .unwrap();
+        eval_str("(setf s (seed-random-state 42))", &mut env).unwrap();
+        assert_eq!(eval_str("(random 1000000 s)", &mut env), Ok(first));
+
+        // 复制出的状态与原状态各自独立地产生同样的序列
+        eval_str("(setf *random-state* (seed-random-state 7))", &mut env).unwrap();
+        eval_str("(setf copy (make-random-state))", &mut env).unwrap();
+        let a = eval_str("(random 1000000)", &mut env).unwrap();
+        let b = eval_str("(random 1000000 copy)", &mut env).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(eval_str("(random-state-p copy)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(random-state-p 1)", &mut env), Ok(Expr::nil()));
+    }
+
+    #[test]
+    fn test_random_errors() {
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(random 0)", &mut env),
+            Err(LispError::new("random: limit must be a positive integer or float, got 0"))
+        );
+        assert_eq!(
+            eval_str("(random -1.5)", &mut env),
+            Err(LispError::new("random: limit must be a positive integer or float, got -1.5"))
+        );
+        assert_eq!(
+            eval_str("(make-random-state 3)", &mut env),
+            Err(LispError::new("make-random-state: argument must be a random state, t or nil, got 3"))
+        );
+        assert_eq!(
+            eval_str("(seed-random-state \"x\")", &mut env),
+            Err(LispError::new("seed-random-state: seed must be an integer, got \"x\""))
+        );
+    }
+}
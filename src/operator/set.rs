@@ -4,6 +4,7 @@ use crate::environment::Environment;
 use crate::exception::LispError;
 use crate::expression::Expr;
 use crate::evaluator::Evaluator;
+use crate::operator::lambda::Lambda;
 
 pub struct SetOps;
 
@@ -14,7 +15,11 @@ impl SetOps {
         }
 
         let symbol = match &args[0] {
+            keyword if keyword.is_keyword() => {
+                return Err(LispError::new(&format!("setf: cannot assign to the keyword {}", keyword)));
+            }
             Expr::Symbol(s) => s.clone(),
+            Expr::List(place) => return SetOps::set_place(place, &args[1], env),
             _ => return Err(LispError::new("setf: first argument must be a symbol")),
         };
 
@@ -22,6 +27,56 @@ impl SetOps {
         env.set_symbol(symbol.clone(), value.clone());
         Ok(value)
     }
+
+    // 求值位置的子形式，返回以求值结果代替子形式的位置。读取和写回这个位置都不会再次产生子形式的副作用。
+    // getf 这类按形式注册的位置，第一个实参本身也是位置，同样只解析而不读取
+    pub fn resolve_place(place: &Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        let items = match place {
+            Expr::List(items) => items,
+            _ => return Ok(place.clone()),
+        };
+        let (name, place_args) = match items.split_first() {
+            Some((Expr::Symbol(name), rest)) => (name, rest),
+            _ => return Err(LispError::new(&format!("setf: invalid place {}", place))),
+        };
+        let nested = OperatorRegistry::get_setf_form(name).is_some();
+        let mut resolved = vec![items[0].clone()];
+        for (index, arg) in place_args.iter().enumerate() {
+            resolved.push(if nested && index == 0 {
+                SetOps::resolve_place(arg, env)?
+            } else {
+                Expr::List(vec![Expr::Symbol("quote".to_string()), Evaluator::eval(arg, env)?])
+            });
+        }
+        Ok(Expr::List(resolved))
+    }
+
+    // (setf (accessor args...) value)：先从左到右求值位置参数，再求值新值，然后交给该位置注册的 setf 函数
+    fn set_place(place: &[Expr], value: &Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        let (name, place_args) = match place.split_first() {
+            Some((Expr::Symbol(name), rest)) => (name, rest),
+            _ => return Err(LispError::new(&format!("setf: invalid place {}", Expr::List(place.to_vec())))),
+        };
+        if let Some(setter) = OperatorRegistry::get_setf_form(name) {
+            return setter(place_args, value, env);
+        }
+        // 内置位置之外，还可以是 defstruct 等定义的 (setf name) 函数，它以新值为第一个实参
+        let setf_function = format!("(setf {})", name);
+        let setter = OperatorRegistry::get_setf(name);
+        if setter.is_none() && env.get_function(&setf_function).is_none() {
+            return Err(LispError::new(&format!("setf: {} is not a settable place", name)));
+        }
+        let place_args = place_args.iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        let value = Evaluator::eval(value, env)?;
+        match setter {
+            Some(setter) => setter(&place_args, value, env),
+            None => {
+                let mut values = vec![value];
+                values.extend(place_args);
+                Lambda::funcall(&Expr::Symbol(setf_function), values, env)
+            }
+        }
+    }
 }
 
 pub fn register_set_operators() {
@@ -108,4 +163,15 @@ mod tests {
         assert_eq!(result.unwrap(), Expr::Number(42));
         assert_eq!(env.get_symbol("x"), Some(&Expr::Number(42)));
     }
+
+    #[test]
+    fn test_eval_setf_rejects_keywords() {
+        let mut env = Environment::initialize();
+
+        let args = vec![Expr::Symbol(":size".to_string()), Expr::Number(1)];
+        let result = SetOps::eval_setf(&args, &mut env);
+
+        assert_eq!(result, Err(LispError::new("setf: cannot assign to the keyword :size")));
+        assert_eq!(Evaluator::eval(&Expr::Symbol(":size".to_string()), &mut env), Ok(Expr::Symbol(":size".to_string())));
+    }
 }
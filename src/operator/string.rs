@@ -0,0 +1,548 @@
+// operator/string.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::arithmetic::Arithmetic;
+use crate::operator::comparison::Comparison;
+use crate::operator::lambda::eval_keyword_arguments;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::parser::Parser;
+use crate::printer::{PrintOptions, Printer};
+use crate::bignum::BigInt;
+use std::cmp::Ordering;
+
+// 所有下标都按字符（Unicode 标量值）计算，而不是按 UTF-8 字节
+pub struct StringOps;
+
+impl StringOps {
+    // 字符串标识符：字符串本身、符号的名字，或者单个字符
+    pub fn string_designator(name: &str, value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Str(s) => Ok(s.clone()),
+            Expr::Symbol(s) => Ok(s.clone()),
+            Expr::Char(ch) => Ok(ch.to_string()),
+            other => Err(LispError::new(&format!("{}: argument must be a string, got {}", name, other))),
+        }
+    }
+
+    fn string_argument(name: &str, arg: &Expr, env: &mut Environment) -> Result<String, LispError> {
+        let value = Evaluator::eval(arg, env)?;
+        StringOps::string_designator(name, &value)
+    }
+
+    fn index_value(name: &str, value: &Expr) -> Result<usize, LispError> {
+        match value {
+            Expr::Number(n) if *n >= 0 => Ok(*n as usize),
+            other => Err(LispError::new(&format!("{}: index must be a non-negative integer, got {}", name, other))),
+        }
+    }
+
+    // 省略或为 nil 的 end 表示序列末尾
+    fn bounds(name: &str, start: usize, end: Option<&Expr>, length: usize) -> Result<(usize, usize), LispError> {
+        let end = match end {
+            Some(value) if value.is_truthy() => StringOps::index_value(name, value)?,
+            _ => length,
+        };
+        if start > end || end > length {
+            return Err(LispError::new(&format!(
+                "{}: bounding indices {} and {} are out of range for length {}",
+                name, start, end, length
+            )));
+        }
+        Ok((start, end))
+    }
+
+    // 构成字符串的字符：字符串，或者元素都是字符的列表
+    fn sequence_chars(name: &str, value: &Expr) -> Result<Vec<char>, LispError> {
+        match value {
+            Expr::Str(s) => Ok(s.chars().collect()),
+            Expr::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Expr::Char(ch) => Ok(*ch),
+                    other => Err(LispError::new(&format!("{}: {} is not a character", name, other))),
+                })
+                .collect(),
+            other => Err(LispError::new(&format!("{}: argument must be a string or list of characters, got {}", name, other))),
+        }
+    }
+
+    // 序列的元素：字符串的元素是字符
+    fn sequence_elements(name: &str, value: &Expr) -> Result<Vec<Expr>, LispError> {
+        match value {
+            Expr::Str(s) => Ok(s.chars().map(Expr::Char).collect()),
+            Expr::List(items) => Ok(items.clone()),
+            other => Err(LispError::new(&format!("{}: argument must be a sequence, got {}", name, other))),
+        }
+    }
+
+    // (string x)：把字符串标识符转换成字符串
+    pub fn eval_string(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("string", args, 1, 1)?;
+        Ok(Expr::Str(StringOps::string_argument("string", &args[0], env)?))
+    }
+
+    // (concatenate 'string seq ...) 或 (concatenate 'list seq ...)
+    pub fn eval_concatenate(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() {
+            return Err(LispError::new("concatenate expects a result type"));
+        }
+        let result_type = Evaluator::eval(&args[0], env)?;
+        let sequences = args[1..].iter().map(|arg| Evaluator::eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+        match &result_type {
+            Expr::Symbol(s) if s == "string" => {
+                let mut result = String::new();
+                for sequence in &sequences {
+                    result.extend(StringOps::sequence_chars("concatenate", sequence)?);
+                }
+                Ok(Expr::Str(result))
+            }
+            Expr::Symbol(s) if s == "list" => {
+                let mut result = Vec::new();
+                for sequence in &sequences {
+                    result.extend(StringOps::sequence_elements("concatenate", sequence)?);
+                }
+                Ok(Expr::List(result))
+            }
+            other => Err(LispError::new(&format!("concatenate: unsupported result type {}", other))),
+        }
+    }
+
+    // (subseq sequence start [end])
+    pub fn eval_subseq(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("subseq", args, 2, 3)?;
+        let sequence = Evaluator::eval(&args[0], env)?;
+        let start = StringOps::index_value("subseq", &Evaluator::eval(&args[1], env)?)?;
+        let end = match args.get(2) {
+            Some(arg) => Some(Evaluator::eval(arg, env)?),
+            None => None,
+        };
+        match sequence {
+            Expr::Str(s) => {
+                let chars: Vec<char> = s.chars().collect();
+                let (start, end) = StringOps::bounds("subseq", start, end.as_ref(), chars.len())?;
+                Ok(Expr::Str(chars[start..end].iter().collect()))
+            }
+            Expr::List(items) => {
+                let (start, end) = StringOps::bounds("subseq", start, end.as_ref(), items.len())?;
+                Ok(Expr::List(items[start..end].to_vec()))
+            }
+            other => Err(LispError::new(&format!("subseq: argument must be a sequence, got {}", other))),
+        }
+    }
+
+    pub fn eval_string_upcase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("string-upcase", args, 1, 1)?;
+        Ok(Expr::Str(StringOps::string_argument("string-upcase", &args[0], env)?.to_uppercase()))
+    }
+
+    pub fn eval_string_downcase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("string-downcase", args, 1, 1)?;
+        Ok(Expr::Str(StringOps::string_argument("string-downcase", &args[0], env)?.to_lowercase()))
+    }
+
+    // (string-trim bag string)：去掉两端属于 bag 的字符，bag 是字符串或字符列表
+    fn trim(name: &str, args: &[Expr], env: &mut Environment, left: bool, right: bool) -> Result<Expr, LispError> {
+        expect_args(name, args, 2, 2)?;
+        let bag = StringOps::sequence_chars(name, &Evaluator::eval(&args[0], env)?)?;
+        let string = StringOps::string_argument(name, &args[1], env)?;
+        let mut trimmed = string.as_str();
+        if left {
+            trimmed = trimmed.trim_start_matches(|ch| bag.contains(&ch));
+        }
+        if right {
+            trimmed = trimmed.trim_end_matches(|ch| bag.contains(&ch));
+        }
+        Ok(Expr::Str(trimmed.to_string()))
+    }
+
+    pub fn eval_string_trim(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::trim("string-trim", args, env, true, true)
+    }
+
+    pub fn eval_string_left_trim(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::trim("string-left-trim", args, env, true, false)
+    }
+
+    pub fn eval_string_right_trim(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::trim("string-right-trim", args, env, false, true)
+    }
+
+    // (search needle haystack &key :start2 :from-end)：needle 第一次（或最后一次）出现的下标，元素用 eql 比较
+    pub fn eval_search(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 {
+            return Err(LispError::new("search expects at least two arguments"));
+        }
+        let needle = StringOps::sequence_elements("search", &Evaluator::eval(&args[0], env)?)?;
+        let haystack = StringOps::sequence_elements("search", &Evaluator::eval(&args[1], env)?)?;
+        let mut start = 0;
+        let mut from_end = false;
+        for (keyword, value) in eval_keyword_arguments("search", &args[2..], env, &["start2", "from-end"])? {
+            match keyword.as_str() {
+                "start2" => start = StringOps::index_value("search", &value)?,
+                _ => from_end = value.is_truthy(),
+            }
+        }
+        if start > haystack.len() {
+            return Err(LispError::new(&format!(
+                "search: start index {} is out of range for length {}",
+                start,
+                haystack.len()
+            )));
+        }
+        let matches_at = |i: &usize| {
+            needle.iter().zip(&haystack[*i..]).all(|(l, r)| Comparison::is_eql(l, r))
+        };
+        let last = haystack.len().checked_sub(needle.len());
+        let found = match last {
+            Some(last) if start <= last => {
+                if from_end {
+                    (start..=last).rev().find(matches_at)
+                } else {
+                    (start..=last).find(matches_at)
+                }
+            }
+            _ => None,
+        };
+        Ok(found.map_or_else(Expr::nil, |i| Expr::Number(i as i64)))
+    }
+
+    // (split-string string [separator])：省略分隔符时按空白切分并丢弃空段，否则按分隔符原样切分
+    pub fn eval_split_string(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("split-string", args, 1, 2)?;
+        let string = StringOps::string_argument("split-string", &args[0], env)?;
+        let separator = match args.get(1) {
+            Some(arg) => Some(StringOps::string_argument("split-string", arg, env)?),
+            None => None,
+        };
+        let parts: Vec<Expr> = match separator {
+            None => string.split_whitespace().map(|part| Expr::Str(part.to_string())).collect(),
+            Some(separator) if separator.is_empty() => {
+                return Err(LispError::new("split-string: separator must not be empty"));
+            }
+            Some(separator) => string.split(separator.as_str()).map(|part| Expr::Str(part.to_string())).collect(),
+        };
+        Ok(Expr::List(parts))
+    }
+
+    // (join strings [separator])
+    pub fn eval_join(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("join", args, 1, 2)?;
+        let parts = match Evaluator::eval(&args[0], env)? {
+            Expr::List(items) => items
+                .iter()
+                .map(|item| StringOps::string_designator("join", item))
+                .collect::<Result<Vec<_>, _>>()?,
+            other => return Err(LispError::new(&format!("join: argument must be a list of strings, got {}", other))),
+        };
+        let separator = match args.get(1) {
+            Some(arg) => StringOps::string_argument("join", arg, env)?,
+            None => String::new(),
+        };
+        Ok(Expr::Str(parts.join(&separator)))
+    }
+
+    // 逐字符比较两个字符串，返回第一个不同字符的下标（两者相同时为字符串的长度）和大小关系
+    fn compare_strings(name: &str, args: &[Expr], env: &mut Environment, ignore_case: bool) -> Result<(usize, Ordering), LispError> {
+        expect_args(name, args, 2, 2)?;
+        let mut left = StringOps::string_argument(name, &args[0], env)?;
+        let mut right = StringOps::string_argument(name, &args[1], env)?;
+        if ignore_case {
+            left = left.to_lowercase();
+            right = right.to_lowercase();
+        }
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+        let mismatch = (0..left.len().max(right.len())).find(|&i| left.get(i) != right.get(i));
+        Ok((mismatch.unwrap_or(left.len()), left.cmp(&right)))
+    }
+
+    pub fn eval_string_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (_, ordering) = StringOps::compare_strings("string=", args, env, false)?;
+        Ok(Expr::from_bool(ordering == Ordering::Equal))
+    }
+
+    pub fn eval_string_equal_ignore_case(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (_, ordering) = StringOps::compare_strings("string-equal", args, env, true)?;
+        Ok(Expr::from_bool(ordering == Ordering::Equal))
+    }
+
+    // 字符串的不等比较成立时返回第一个不同字符的下标，否则返回 nil
+    fn string_inequality(name: &str, args: &[Expr], env: &mut Environment, ignore_case: bool, holds: fn(Ordering) -> bool) -> Result<Expr, LispError> {
+        let (mismatch, ordering) = StringOps::compare_strings(name, args, env, ignore_case)?;
+        if !holds(ordering) {
+            return Ok(Expr::nil());
+        }
+        Ok(Expr::Number(mismatch as i64))
+    }
+
+    pub fn eval_string_not_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string/=", args, env, false, |ordering| ordering != Ordering::Equal)
+    }
+
+    pub fn eval_string_less(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string<", args, env, false, |ordering| ordering == Ordering::Less)
+    }
+
+    pub fn eval_string_greater(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string>", args, env, false, |ordering| ordering == Ordering::Greater)
+    }
+
+    pub fn eval_string_less_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string<=", args, env, false, |ordering| ordering != Ordering::Greater)
+    }
+
+    pub fn eval_string_greater_equal(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string>=", args, env, false, |ordering| ordering != Ordering::Less)
+    }
+
+    pub fn eval_string_lessp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::string_inequality("string-lessp", args, env, true, |ordering| ordering == Ordering::Less)
+    }
+
+    // (char string index)：第 index 个字符
+    fn char_at(name: &str, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args(name, args, 2, 2)?;
+        let string = match Evaluator::eval(&args[0], env)? {
+            Expr::Str(s) => s,
+            other => return Err(LispError::new(&format!("{}: argument must be a string, got {}", name, other))),
+        };
+        let index = StringOps::index_value(name, &Evaluator::eval(&args[1], env)?)?;
+        string.chars().nth(index).map(Expr::Char).ok_or_else(|| {
+            LispError::new(&format!(
+                "{}: index {} is out of range for string of length {}",
+                name,
+                index,
+                string.chars().count()
+            ))
+        })
+    }
+
+    pub fn eval_char(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::char_at("char", args, env)
+    }
+
+    pub fn eval_schar(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        StringOps::char_at("schar", args, env)
+    }
+
+    // (parse-integer string &key :start :end :radix :junk-allowed)
+    // 返回两个值：解析出的整数（junk-allowed 时可能为 nil）和停止解析处的下标
+    pub fn eval_parse_integer(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.is_empty() {
+            return Err(LispError::new("parse-integer expects a string"));
+        }
+        let string = match Evaluator::eval(&args[0], env)? {
+            Expr::Str(s) => s,
+            other => return Err(LispError::new(&format!("parse-integer: argument must be a string, got {}", other))),
+        };
+        let chars: Vec<char> = string.chars().collect();
+        let mut start = 0;
+        let mut end = None;
+        let mut radix = 10;
+        let mut junk_allowed = false;
+        for (keyword, value) in eval_keyword_arguments("parse-integer", &args[1..], env, &["start", "end", "radix", "junk-allowed"])? {
+            match keyword.as_str() {
+                "start" => start = StringOps::index_value("parse-integer", &value)?,
+                "end" => end = Some(value),
+                "radix" => {
+                    radix = match value {
+                        Expr::Number(n) if (2..=36).contains(&n) => n as u32,
+                        other => return Err(LispError::new(&format!("parse-integer: radix must be an integer between 2 and 36, got {}", other))),
+                    }
+                }
+                _ => junk_allowed = value.is_truthy(),
+            }
+        }
+        let (start, end) = StringOps::bounds("parse-integer", start, end.as_ref(), chars.len())?;
+
+        let mut i = start;
+        while i < end && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let negative = i < end && chars[i] == '-';
+        if i < end && (chars[i] == '-' || chars[i] == '+') {
+            i += 1;
+        }
+        let digits_start = i;
+        while i < end && chars[i].is_digit(radix) {
+            i += 1;
+        }
+        let digits: String = chars[digits_start..i].iter().collect();
+        let mut stop = i;
+        while stop < end && chars[stop].is_whitespace() {
+            stop += 1;
+        }
+
+        let value = if digits.is_empty() {
+            None
+        } else {
+            BigInt::parse(&digits, radix).map(|n| Expr::from_bigint(if negative { n.neg() } else { n }))
+        };
+        if junk_allowed {
+            return Ok(env.set_values(vec![value.unwrap_or_else(Expr::nil), Expr::Number(i as i64)]));
+        }
+        match value {
+            Some(value) if stop == end => Ok(env.set_values(vec![value, Expr::Number(end as i64)])),
+            Some(_) => Err(LispError::new(&format!("parse-integer: junk in string \"{}\"", string))),
+            None => Err(LispError::new(&format!("parse-integer: no digits in string \"{}\"", string))),
+        }
+    }
+
+    // (parse-float string)：按读取器的数值语法解析，实数结果总是转换成浮点数
+    pub fn eval_parse_float(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("parse-float", args, 1, 1)?;
+        let string = match Evaluator::eval(&args[0], env)? {
+            Expr::Str(s) => s,
+            other => return Err(LispError::new(&format!("parse-float: argument must be a string, got {}", other))),
+        };
+        let number = Parser::parse_numeric_token(string.trim()).ok().flatten();
+        match number.as_ref().and_then(Arithmetic::to_f64) {
+            Some(value) => Ok(Expr::Float(value)),
+            None => Err(LispError::new(&format!("parse-float: \"{}\" is not a number", string))),
+        }
+    }
+
+    // (number-to-string number [radix])：有理数按给定进制输出，不带进制前缀
+    pub fn eval_number_to_string(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("number-to-string", args, 1, 2)?;
+        let number = Evaluator::eval(&args[0], env)?;
+        if Arithmetic::complex_parts(&number).is_none() {
+            return Err(LispError::new(&format!("number-to-string: argument must be a number, got {}", number)));
+        }
+        let base = match args.get(1) {
+            Some(arg) => match Evaluator::eval(arg, env)? {
+                Expr::Number(n) if (2..=36).contains(&n) => n as u32,
+                other => return Err(LispError::new(&format!("number-to-string: radix must be an integer between 2 and 36, got {}", other))),
+            },
+            None => 10,
+        };
+        Ok(Expr::Str(Printer::print(&number, &PrintOptions { base, ..PrintOptions::default() })))
+    }
+
+    // (string-replace string old new)：把所有不重叠的 old 替换成 new
+    pub fn eval_string_replace(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("string-replace", args, 3, 3)?;
+        let string = StringOps::string_argument("string-replace", &args[0], env)?;
+        let old = StringOps::string_argument("string-replace", &args[1], env)?;
+        let new = StringOps::string_argument("string-replace", &args[2], env)?;
+        if old.is_empty() {
+            return Err(LispError::new("string-replace: the string to replace must not be empty"));
+        }
+        Ok(Expr::Str(string.replace(&old, &new)))
+    }
+}
+
+pub fn register_string_operators() {
+    OperatorRegistry::register("string", StringOps::eval_string);
+    OperatorRegistry::register("concatenate", StringOps::eval_concatenate);
+    OperatorRegistry::register("subseq", StringOps::eval_subseq);
+    OperatorRegistry::register("string-upcase", StringOps::eval_string_upcase);
+    OperatorRegistry::register("string-downcase", StringOps::eval_string_downcase);
+    OperatorRegistry::register("string-trim", StringOps::eval_string_trim);
+    OperatorRegistry::register("string-left-trim", StringOps::eval_string_left_trim);
+    OperatorRegistry::register("string-right-trim", StringOps::eval_string_right_trim);
+    OperatorRegistry::register("search", StringOps::eval_search);
+    OperatorRegistry::register("split-string", StringOps::eval_split_string);
+    OperatorRegistry::register("join", StringOps::eval_join);
+    OperatorRegistry::register("string=", StringOps::eval_string_equal);
+    OperatorRegistry::register("string-equal", StringOps::eval_string_equal_ignore_case);
+    OperatorRegistry::register("string/=", StringOps::eval_string_not_equal);
+    OperatorRegistry::register("string<", StringOps::eval_string_less);
+    OperatorRegistry::register("string>", StringOps::eval_string_greater);
+    OperatorRegistry::register("string<=", StringOps::eval_string_less_equal);
+    OperatorRegistry::register("string>=", StringOps::eval_string_greater_equal);
+    OperatorRegistry::register("string-lessp", StringOps::eval_string_lessp);
+    OperatorRegistry::register("char", StringOps::eval_char);
+    OperatorRegistry::register("schar", StringOps::eval_schar);
+    OperatorRegistry::register("parse-integer", StringOps::eval_parse_integer);
+    OperatorRegistry::register("parse-float", StringOps::eval_parse_float);
+    OperatorRegistry::register("number-to-string", StringOps::eval_number_to_string);
+    OperatorRegistry::register("string-replace", StringOps::eval_string_replace);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_in_new_env};
+
+    #[test]
+    fn test_length_and_indexing_count_characters() {
+        assert_eq!(eval_in_new_env("(length \"héllo\")"), "5");
+        assert_eq!(eval_in_new_env("(length \"日本語\")"), "3");
+        assert_eq!(eval_in_new_env("(char \"日本語\" 1)"), "#\\本");
+        assert_eq!(eval_in_new_env("(schar \"abc\" 0)"), "#\\a");
+        assert_eq!(eval_in_new_env("(subseq \"日本語テキスト\" 3)"), "\"テキスト\"");
+        assert_eq!(eval_in_new_env("(subseq \"héllo\" 1 3)"), "\"él\"");
+        assert_eq!(eval_in_new_env("(subseq '(1 2 3 4) 1 3)"), "(2 3)");
+        assert_eq!(eval_in_new_env("(search \"語\" \"日本語\")"), "2");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(char \"日本\" 2)", &mut env),
+            Err(LispError::new("char: index 2 is out of range for string of length 2"))
+        );
+        assert_eq!(
+            eval_str("(subseq \"abc\" 2 5)", &mut env),
+            Err(LispError::new("subseq: bounding indices 2 and 5 are out of range for length 3"))
+        );
+    }
+
+    #[test]
+    fn test_building_strings() {
+        assert_eq!(eval_in_new_env("(concatenate 'string \"ab\" \"cd\" '(#\\e))"), "\"abcde\"");
+        assert_eq!(eval_in_new_env("(concatenate 'list \"ab\" '(1))"), "(#\\a #\\b 1)");
+        assert_eq!(eval_in_new_env("(string-upcase \"straße\")"), "\"STRASSE\"");
+        assert_eq!(eval_in_new_env("(string-downcase \"ÄB\")"), "\"äb\"");
+        assert_eq!(eval_in_new_env("(string-trim \" \" \"  hi  \")"), "\"hi\"");
+        assert_eq!(eval_in_new_env("(string-left-trim '(#\\x) \"xxhix\")"), "\"hix\"");
+        assert_eq!(eval_in_new_env("(split-string \"  a b\tc \")"), "(\"a\" \"b\" \"c\")");
+        assert_eq!(eval_in_new_env("(split-string \"a,,b\" \",\")"), "(\"a\" \"\" \"b\")");
+        assert_eq!(eval_in_new_env("(join '(\"a\" \"b\" \"c\") \", \")"), "\"a, b, c\"");
+        assert_eq!(eval_in_new_env("(string-replace \"a-b-c\" \"-\" \"::\")"), "\"a::b::c\"");
+        assert_eq!(eval_in_new_env("(string 'abc)"), "\"abc\"");
+    }
+
+    #[test]
+    fn test_string_comparison() {
+        assert_eq!(eval_in_new_env("(string= \"abc\" \"abc\")"), "t");
+        assert_eq!(eval_in_new_env("(string= \"abc\" \"ABC\")"), "()");
+        assert_eq!(eval_in_new_env("(string-equal \"abc\" \"ABC\")"), "t");
+        assert_eq!(eval_in_new_env("(string< \"abc\" \"abd\")"), "2");
+        assert_eq!(eval_in_new_env("(string< \"ab\" \"abc\")"), "2");
+        assert_eq!(eval_in_new_env("(string< \"abd\" \"abc\")"), "()");
+        assert_eq!(eval_in_new_env("(string>= \"abc\" \"abc\")"), "3");
+        assert_eq!(eval_in_new_env("(search \"b\" \"abcb\" :from-end t)"), "3");
+        assert_eq!(eval_in_new_env("(search \"b\" \"abcb\" :start2 2)"), "3");
+        assert_eq!(eval_in_new_env("(search \"z\" \"abc\")"), "()");
+    }
+
+    #[test]
+    fn test_number_conversion() {
+        assert_eq!(eval_in_new_env("(parse-integer \" 42 \")"), "42");
+        assert_eq!(eval_in_new_env("(multiple-value-list (parse-integer \"-17\"))"), "(-17 3)");
+        assert_eq!(eval_in_new_env("(parse-integer \"ff\" :radix 16)"), "255");
+        assert_eq!(eval_in_new_env("(multiple-value-list (parse-integer \"12abc\" :junk-allowed t))"), "(12 2)");
+        assert_eq!(eval_in_new_env("(parse-integer \"abc\" :junk-allowed t)"), "()");
+        assert_eq!(eval_in_new_env("(parse-integer \"123456789012345678901234567890\")"), "123456789012345678901234567890");
+        assert_eq!(eval_in_new_env("(parse-float \"1.5e3\")"), "1500.0");
+        assert_eq!(eval_in_new_env("(parse-float \"3\")"), "3.0");
+        assert_eq!(eval_in_new_env("(parse-float \"1/4\")"), "0.25");
+        assert_eq!(eval_in_new_env("(number-to-string 255)"), "\"255\"");
+        assert_eq!(eval_in_new_env("(number-to-string 255 16)"), "\"FF\"");
+        assert_eq!(eval_in_new_env("(number-to-string 1/2)"), "\"1/2\"");
+        assert_eq!(eval_in_new_env("(number-to-string 2.5)"), "\"2.5\"");
+
+        let mut env = Environment::initialize();
+        assert_eq!(
+            eval_str("(parse-integer \"12abc\")", &mut env),
+            Err(LispError::new("parse-integer: junk in string \"12abc\""))
+        );
+        assert_eq!(
+            eval_str("(parse-float \"abc\")", &mut env),
+            Err(LispError::new("parse-float: \"abc\" is not a number"))
+        );
+    }
+}
@@ -0,0 +1,393 @@
+// operator/structure.rs
+
+use crate::operator::OperatorRegistry;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::package;
+use crate::structure::{Slot, StructInstance, StructType};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn symbol(name: &str) -> Expr {
+    Expr::Symbol(name.to_string())
+}
+
+fn quote(expr: Expr) -> Expr {
+    Expr::List(vec![symbol("quote"), expr])
+}
+
+fn lambda(params: Vec<Expr>, body: Expr) -> Expr {
+    Expr::List(vec![symbol("lambda"), Expr::List(params), body])
+}
+
+// defstruct 选项里的名字：符号，或者 nil 表示不生成
+fn option_name(option: &str, value: Option<&Expr>, default: String) -> Result<Option<String>, LispError> {
+    match value {
+        None => Ok(Some(default)),
+        Some(value) if !value.is_truthy() => Ok(None),
+        Some(Expr::Symbol(name)) => Ok(Some(name.clone())),
+        Some(other) => Err(LispError::new(&format!("defstruct: {} must be a symbol, got {}", option, other))),
+    }
+}
+
+pub struct Structure;
+
+impl Structure {
+    // 槽描述：name 或 (name default &key :read-only :type)
+    fn parse_slot(spec: &Expr) -> Result<Slot, LispError> {
+        let (name, rest) = match spec {
+            Expr::Symbol(name) if !spec.is_keyword() => (name.clone(), &[][..]),
+            Expr::List(parts) => match parts.split_first() {
+                Some((Expr::Symbol(name), rest)) if !parts[0].is_keyword() => (name.clone(), rest),
+                _ => return Err(LispError::new(&format!("defstruct: invalid slot description {}", spec))),
+            },
+            _ => return Err(LispError::new(&format!("defstruct: invalid slot description {}", spec))),
+        };
+        let mut slot = Slot { name, default: rest.first().cloned().unwrap_or_else(Expr::nil), read_only: false };
+        let options = rest.get(1..).unwrap_or(&[]);
+        if !options.len().is_multiple_of(2) {
+            return Err(LispError::new(&format!("defstruct: odd number of slot options in {}", spec)));
+        }
+        for pair in options.chunks(2) {
+            match &pair[0] {
+                Expr::Symbol(option) if option == ":read-only" => slot.read_only = pair[1].is_truthy(),
+                Expr::Symbol(option) if option == ":type" => {}
+                other => return Err(LispError::new(&format!("defstruct: unknown slot option {}", other))),
+            }
+        }
+        Ok(slot)
+    }
+
+    // (defstruct name-and-options [doc] slot...)
+    // name-and-options 是 name 或 (name option...)，选项有 :conc-name、:constructor、:predicate、:copier 和 :include
+    pub fn eval_defstruct(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let (name, options) = match args.first() {
+            Some(Expr::Symbol(name)) => (name.clone(), &[][..]),
+            Some(Expr::List(parts)) => match parts.split_first() {
+                Some((Expr::Symbol(name), options)) => (name.clone(), options),
+                _ => return Err(LispError::new("defstruct: name must be a symbol")),
+            },
+            _ => return Err(LispError::new("defstruct: name must be a symbol")),
+        };
+
+        // 生成的函数名在当前包中驻留，名字里不带结构名的包前缀
+        let base = package::symbol_name(&name).to_string();
+        let derive = |text: String| env.packages().intern_current(&text);
+        let (default_constructor, default_predicate, default_copier) =
+            (derive(format!("make-{}", base)), derive(format!("{}-p", base)), derive(format!("copy-{}", base)));
+        let mut conc_name = format!("{}-", base);
+        let mut constructor = Some(default_constructor.clone());
+        let mut predicate = Some(default_predicate.clone());
+        let mut copier = Some(default_copier.clone());
+        let mut slots = Vec::new();
+        let mut includes = vec![name.clone()];
+        for option in options {
+            let (keyword, values) = match option {
+                Expr::Symbol(keyword) if option.is_keyword() => (keyword.as_str(), &[][..]),
+                Expr::List(parts) if parts.first().is_some_and(Expr::is_keyword) => match &parts[0] {
+                    Expr::Symbol(keyword) => (keyword.as_str(), &parts[1..]),
+                    _ => unreachable!(),
+                },
+                _ => return Err(LispError::new(&format!("defstruct: invalid option {}", option))),
+            };
+            match keyword {
+                ":conc-name" => {
+                    conc_name = match values.first() {
+                        None => String::new(),
+                        Some(value) if !value.is_truthy() => String::new(),
+                        Some(Expr::Symbol(prefix)) => package::symbol_name(prefix).to_string(),
+                        Some(Expr::Str(prefix)) => prefix.clone(),
+                        Some(other) => return Err(LispError::new(&format!("defstruct: :conc-name must be a symbol, got {}", other))),
+                    }
+                }
+                ":constructor" => {
+                    if values.len() > 1 {
+                        return Err(LispError::new("defstruct: constructors with an explicit argument list are not supported"));
+                    }
+                    constructor = option_name(":constructor", values.first(), default_constructor.clone())?;
+                }
+                ":predicate" => predicate = option_name(":predicate", values.first(), default_predicate.clone())?,
+                ":copier" => copier = option_name(":copier", values.first(), default_copier.clone())?,
+                ":include" => {
+                    let parent = match values.first() {
+                        Some(Expr::Symbol(parent)) => env
+                            .get_structure(parent)
+                            .cloned()
+                            .ok_or_else(|| LispError::new(&format!("defstruct: cannot include unknown structure {}", parent)))?,
+                        _ => return Err(LispError::new("defstruct: :include requires a structure name")),
+                    };
+                    slots = parent.slots.clone();
+                    // (:include parent (slot new-default)...) 可以覆盖继承来的槽的默认值
+                    for spec in &values[1..] {
+                        let override_slot = Structure::parse_slot(spec)?;
+                        match slots.iter_mut().find(|slot| slot.name == override_slot.name) {
+                            Some(slot) => *slot = override_slot,
+                            None => {
+                                return Err(LispError::new(&format!("defstruct: {} has no slot named {}", parent.name, override_slot.name)));
+                            }
+                        }
+                    }
+                    includes.extend(parent.includes.iter().cloned());
+                }
+                other => return Err(LispError::new(&format!("defstruct: unknown option {}", other))),
+            }
+        }
+
+        let mut specs = &args[1..];
+        if let (Some(Expr::Str(doc)), true) = (specs.first(), specs.len() > 1) {
+            env.set_documentation(&name, "structure", doc.clone());
+            specs = &specs[1..];
+        }
+        for spec in specs {
+            let slot = Structure::parse_slot(spec)?;
+            if slots.iter().any(|existing| existing.name == slot.name) {
+                return Err(LispError::new(&format!("defstruct: duplicate slot {}", slot.name)));
+            }
+            slots.push(slot);
+        }
+
+        let structure = Rc::new(StructType { name: name.clone(), slots, includes, constructor: constructor.clone() });
+        env.set_structure(name.clone(), structure.clone());
+        Structure::define_functions(&structure, &conc_name, constructor, predicate, copier, env);
+        Ok(symbol(&name))
+    }
+
+    // 生成的函数都是普通的 lambda，关键字实参和默认值由 lambda 列表处理，结构本身由下面的内部操作符读写
+    fn define_functions(structure: &StructType, conc_name: &str, constructor: Option<String>, predicate: Option<String>, copier: Option<String>, env: &mut Environment) {
+        let type_name = quote(symbol(&structure.name));
+        if let Some(constructor) = constructor {
+            let params = std::iter::once(symbol("&key"))
+                .chain(structure.slots.iter().map(|slot| Expr::List(vec![symbol(&slot.name), slot.default.clone()])))
+                .collect();
+            let body = std::iter::once(symbol("%make-struct"))
+                .chain(std::iter::once(type_name.clone()))
+                .chain(structure.slots.iter().map(|slot| symbol(&slot.name)))
+                .collect();
+            env.set_function(constructor, lambda(params, Expr::List(body)));
+        }
+        for (index, slot) in structure.slots.iter().enumerate() {
+            let accessor = env.packages().intern_current(&format!("{}{}", conc_name, package::symbol_name(&slot.name)));
+            let index = Expr::Number(index as i64);
+            let body = vec![symbol("%struct-ref"), quote(symbol(&accessor)), symbol("object"), type_name.clone(), index.clone()];
+            env.set_function(accessor.clone(), lambda(vec![symbol("object")], Expr::List(body)));
+            if !slot.read_only {
+                let setter = format!("(setf {})", accessor);
+                let body = vec![symbol("%struct-set"), quote(symbol(&setter)), symbol("object"), type_name.clone(), index, symbol("value")];
+                env.set_function(setter, lambda(vec![symbol("value"), symbol("object")], Expr::List(body)));
+            }
+        }
+        if let Some(predicate) = predicate {
+            let body = vec![symbol("%struct-typep"), symbol("object"), type_name.clone()];
+            env.set_function(predicate, lambda(vec![symbol("object")], Expr::List(body)));
+        }
+        if let Some(copier) = copier {
+            let body = vec![symbol("%copy-struct"), quote(symbol(&copier)), symbol("object"), type_name];
+            env.set_function(copier, lambda(vec![symbol("object")], Expr::List(body)));
+        }
+    }
+
+    fn eval_all(args: &[Expr], env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        args.iter().map(|arg| Evaluator::eval(arg, env)).collect()
+    }
+
+    fn name_argument(value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Symbol(name) => Ok(name.clone()),
+            other => Err(LispError::new(&format!("structure name must be a symbol, got {}", other))),
+        }
+    }
+
+    // 检查 object 是 type_name（或它的子类型）的实例；caller 是出错时报告的函数名
+    fn instance_argument(caller: &Expr, object: &Expr, type_name: &str) -> Result<Rc<RefCell<StructInstance>>, LispError> {
+        match object {
+            Expr::Struct(instance) if instance.borrow().is_a(type_name) => Ok(instance.clone()),
+            other => Err(LispError::new(&format!("{}: {} is not a {}", caller, other, type_name))),
+        }
+    }
+
+    fn index_argument(value: &Expr) -> Result<usize, LispError> {
+        match value {
+            Expr::Number(n) if *n >= 0 => Ok(*n as usize),
+            other => Err(LispError::new(&format!("slot index must be a non-negative integer, got {}", other))),
+        }
+    }
+
+    // (%make-struct 'name slot-values...)
+    pub fn eval_make_struct(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Structure::eval_all(args, env)?;
+        let name = Structure::name_argument(values.first().unwrap_or(&Expr::nil()))?;
+        let structure = env
+            .get_structure(&name)
+            .cloned()
+            .ok_or_else(|| LispError::new(&format!("{} is not a defined structure", name)))?;
+        if values.len() - 1 != structure.slots.len() {
+            return Err(LispError::new(&format!("{} has {} slots, got {} values", name, structure.slots.len(), values.len() - 1)));
+        }
+        let instance = StructInstance::new(structure, values[1..].to_vec());
+        Ok(Expr::Struct(Rc::new(RefCell::new(instance))))
+    }
+
+    // (%struct-ref 'accessor object 'name index)
+    pub fn eval_struct_ref(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Structure::eval_all(args, env)?;
+        if values.len() != 4 {
+            return Err(LispError::new("%struct-ref expects 4 arguments"));
+        }
+        let instance = Structure::instance_argument(&values[0], &values[1], &Structure::name_argument(&values[2])?)?;
+        let index = Structure::index_argument(&values[3])?;
+        let value = instance.borrow().get(index).clone();
+        Ok(value)
+    }
+
+    // (%struct-set 'setter object 'name index value)
+    pub fn eval_struct_set(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Structure::eval_all(args, env)?;
+        if values.len() != 5 {
+            return Err(LispError::new("%struct-set expects 5 arguments"));
+        }
+        let instance = Structure::instance_argument(&values[0], &values[1], &Structure::name_argument(&values[2])?)?;
+        let index = Structure::index_argument(&values[3])?;
+        instance.borrow_mut().set(index, values[4].clone());
+        Ok(values[4].clone())
+    }
+
+    // (%struct-typep object 'name)：子类型的实例同样满足
+    pub fn eval_struct_typep(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Structure::eval_all(args, env)?;
+        if values.len() != 2 {
+            return Err(LispError::new("%struct-typep expects 2 arguments"));
+        }
+        let name = Structure::name_argument(&values[1])?;
+        Ok(Expr::from_bool(matches!(&values[0], Expr::Struct(instance) if instance.borrow().is_a(&name))))
+    }
+
+    // (%copy-struct 'copier object 'name)：浅拷贝，槽的值本身不复制
+    pub fn eval_copy_struct(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let values = Structure::eval_all(args, env)?;
+        if values.len() != 3 {
+            return Err(LispError::new("%copy-struct expects 3 arguments"));
+        }
+        let instance = Structure::instance_argument(&values[0], &values[1], &Structure::name_argument(&values[2])?)?;
+        let copy = instance.borrow().clone();
+        Ok(Expr::Struct(Rc::new(RefCell::new(copy))))
+    }
+}
+
+pub fn register_structure_operators() {
+    OperatorRegistry::register("defstruct", Structure::eval_defstruct);
+    OperatorRegistry::register("%make-struct", Structure::eval_make_struct);
+    OperatorRegistry::register("%struct-ref", Structure::eval_struct_ref);
+    OperatorRegistry::register("%struct-set", Structure::eval_struct_set);
+    OperatorRegistry::register("%struct-typep", Structure::eval_struct_typep);
+    OperatorRegistry::register("%copy-struct", Structure::eval_copy_struct);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_constructor_accessors_and_setf() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_to_string("(defstruct point (x 0) (y (* 2 x)) label)", &mut env), "point");
+        eval_str("(setf p (make-point :x 3))", &mut env).unwrap();
+        assert_eq!(eval_to_string("p", &mut env), "#S(point :x 3 :y 6 :label ())");
+        assert_eq!(eval_to_string("(make-point :label \"origin\" :y 1)", &mut env), "#S(point :x 0 :y 1 :label \"origin\")");
+        assert_eq!(eval_str("(point-x p)", &mut env), Ok(Expr::Number(3)));
+        assert_eq!(eval_str("(setf (point-y p) 10)", &mut env), Ok(Expr::Number(10)));
+        assert_eq!(eval_str("(point-y p)", &mut env), Ok(Expr::Number(10)));
+
+        // 实例是共享的：在函数里修改槽，调用者能看到
+        eval_str("(defun move (pt dx) (setf (point-x pt) (+ (point-x pt) dx)))", &mut env).unwrap();
+        eval_str("(move p 5)", &mut env).unwrap();
+        assert_eq!(eval_str("(point-x p)", &mut env), Ok(Expr::Number(8)));
+
+        assert_eq!(eval_str("(point-p p)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(point-p '(1 2))", &mut env), Ok(Expr::nil()));
+    }
+
+    #[test]
+    fn test_copier_and_equality() {
+        let mut env = Environment::initialize();
+        eval_str("(defstruct point x y)", &mut env).unwrap();
+        eval_str("(setf p (make-point :x 1 :y 2))", &mut env).unwrap();
+        eval_str("(setf q (copy-point p))", &mut env).unwrap();
+        eval_str("(setf (point-x q) 100)", &mut env).unwrap();
+        assert_eq!(eval_str("(point-x p)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(point-x q)", &mut env), Ok(Expr::Number(100)));
+        assert_eq!(eval_str("(eq p p)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equal p (copy-point p))", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(equalp p (copy-point p))", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(equalp p q)", &mut env), Ok(Expr::nil()));
+    }
+
+    #[test]
+    fn test_include_and_options() {
+        let mut env = Environment::initialize();
+        eval_str("(defstruct point (x 0) (y 0))", &mut env).unwrap();
+        eval_str("(defstruct (point3d (:include point (y 5))) (z 0))", &mut env).unwrap();
+        eval_str("(setf p (make-point3d :x 1 :z 3))", &mut env).unwrap();
+        assert_eq!(eval_to_string("p", &mut env), "#S(point3d :x 1 :y 5 :z 3)");
+        // 父类型的访问函数和谓词同样适用于子类型
+        assert_eq!(eval_str("(point-x p)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(point3d-x p)", &mut env), Ok(Expr::Number(1)));
+        assert_eq!(eval_str("(point-p p)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(point3d-p (make-point))", &mut env), Ok(Expr::nil()));
+        eval_str("(setf (point-y p) 7)", &mut env).unwrap();
+        assert_eq!(eval_str("(point3d-y p)", &mut env), Ok(Expr::Number(7)));
+
+        eval_str("(defstruct (account (:conc-name acct-) (:constructor open-account) (:predicate nil)) (balance 0 :read-only t) owner)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(acct-balance (open-account :balance 5))", &mut env), "5");
+        assert_eq!(
+            eval_str("(make-account)", &mut env),
+            Err(LispError::new("Undefined function: make-account"))
+        );
+        assert_eq!(
+            eval_str("(account-p 1)", &mut env),
+            Err(LispError::new("Undefined function: account-p"))
+        );
+        assert_eq!(
+            eval_str("(setf (acct-balance (open-account)) 1)", &mut env),
+            Err(LispError::new("setf: acct-balance is not a settable place"))
+        );
+    }
+
+    #[test]
+    fn test_readable_printed_form() {
+        let mut env = Environment::initialize();
+        eval_str("(defstruct point (x 0) (y 0))", &mut env).unwrap();
+        assert_eq!(eval_to_string("#S(point :x 1 :y 2)", &mut env), "#S(point :x 1 :y 2)");
+        assert_eq!(eval_to_string("(point-y #S(point :y (a b)))", &mut env), "(a b)");
+        assert_eq!(eval_to_string("#S(point :x 1)", &mut env), "#S(point :x 1 :y 0)");
+
+        // 打印出的形式可以读回，得到 equalp 的实例
+        let original = eval_str("(make-point :x \"a\" :y #(1 2))", &mut env).unwrap();
+        let reread = Parser::read(&original.to_string(), &mut env).unwrap();
+        let compare = Expr::List(vec![symbol("equalp"), quote(original), quote(reread)]);
+        assert_eq!(Evaluator::eval(&compare, &mut env), Ok(Expr::t()));
+    }
+
+    #[test]
+    fn test_defstruct_errors() {
+        let mut env = Environment::initialize();
+        eval_str("(defstruct point x)", &mut env).unwrap();
+        assert_eq!(eval_str("(point-x 5)", &mut env), Err(LispError::new("point-x: 5 is not a point")));
+        assert_eq!(
+            eval_str("(make-point :z 1)", &mut env),
+            Err(LispError::new("make-point: unknown keyword argument :z for lambda list (&key (x ()))"))
+        );
+        assert_eq!(
+            eval_str("(defstruct (circle (:include shape)) r)", &mut env),
+            Err(LispError::new("defstruct: cannot include unknown structure shape"))
+        );
+        assert_eq!(eval_str("(defstruct pair a a)", &mut env), Err(LispError::new("defstruct: duplicate slot a")));
+        assert_eq!(
+            eval_str("#S(shape :x 1)", &mut env),
+            Err(LispError::new("#S: shape is not a defined structure"))
+        );
+    }
+}
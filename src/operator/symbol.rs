@@ -0,0 +1,267 @@
+// operator/symbol.rs
+
+use crate::operator::{expect_args, OperatorRegistry};
+use crate::operator::comparison::Comparison;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use crate::package;
+
+// 符号的属性表和内省操作。这里的符号只由名字（以及包前缀）确定，同名符号总是 eq
+pub struct SymbolOps;
+
+impl SymbolOps {
+    fn eval_args(args: &[Expr], env: &mut Environment) -> Result<Vec<Expr>, LispError> {
+        args.iter().map(|arg| Evaluator::eval(arg, env)).collect()
+    }
+
+    // nil 既是空列表也是符号
+    fn symbol_argument(name: &str, value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Symbol(symbol) => Ok(symbol.clone()),
+            Expr::List(items) if items.is_empty() => Ok("nil".to_string()),
+            other => Err(LispError::new(&format!("{}: {} is not a symbol", name, other))),
+        }
+    }
+
+    fn string_argument(name: &str, value: &Expr) -> Result<String, LispError> {
+        match value {
+            Expr::Str(s) => Ok(s.clone()),
+            other => Err(LispError::new(&format!("{}: {} is not a string", name, other))),
+        }
+    }
+
+    // 属性表中指示符的下标，指示符用 eq 比较
+    fn find_property(plist: &[Expr], indicator: &Expr) -> Option<usize> {
+        plist.chunks(2).position(|pair| Comparison::is_eq(&pair[0], indicator)).map(|i| i * 2)
+    }
+
+    // (get symbol indicator [default])
+    pub fn eval_get(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("get", args, 2, 3)?;
+        let values = SymbolOps::eval_args(args, env)?;
+        let symbol = SymbolOps::symbol_argument("get", &values[0])?;
+        let plist = env.get_plist(&symbol);
+        match SymbolOps::find_property(&plist, &values[1]) {
+            Some(index) => Ok(plist[index + 1].clone()),
+            None => Ok(values.get(2).cloned().unwrap_or_else(Expr::nil)),
+        }
+    }
+
+    // (setf (get symbol indicator [default]) value)：已有的属性原地修改，新属性加在表头
+    pub fn setf_get(args: &[Expr], value: Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf get)", args, 2, 3)?;
+        let symbol = SymbolOps::symbol_argument("(setf get)", &args[0])?;
+        let mut plist = env.get_plist(&symbol);
+        match SymbolOps::find_property(&plist, &args[1]) {
+            Some(index) => plist[index + 1] = value.clone(),
+            None => {
+                plist.insert(0, value.clone());
+                plist.insert(0, args[1].clone());
+            }
+        }
+        env.set_plist(&symbol, plist);
+        Ok(value)
+    }
+
+    // (remprop symbol indicator)：删除了属性时返回 t
+    pub fn eval_remprop(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("remprop", args, 2, 2)?;
+        let values = SymbolOps::eval_args(args, env)?;
+        let symbol = SymbolOps::symbol_argument("remprop", &values[0])?;
+        let mut plist = env.get_plist(&symbol);
+        match SymbolOps::find_property(&plist, &values[1]) {
+            Some(index) => {
+                plist.drain(index..index + 2);
+                env.set_plist(&symbol, plist);
+                Ok(Expr::t())
+            }
+            None => Ok(Expr::nil()),
+        }
+    }
+
+    pub fn eval_symbol_plist(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("symbol-plist", args, 1, 1)?;
+        let symbol = SymbolOps::symbol_argument("symbol-plist", &Evaluator::eval(&args[0], env)?)?;
+        Ok(Expr::List(env.get_plist(&symbol)))
+    }
+
+    // (setf (symbol-plist symbol) list)：新的属性表必须有偶数个元素
+    pub fn setf_symbol_plist(args: &[Expr], value: Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf symbol-plist)", args, 1, 1)?;
+        let symbol = SymbolOps::symbol_argument("(setf symbol-plist)", &args[0])?;
+        match &value {
+            Expr::List(items) if items.len().is_multiple_of(2) => env.set_plist(&symbol, items.clone()),
+            other => return Err(LispError::new(&format!("(setf symbol-plist): {} is not a property list", other))),
+        }
+        Ok(value)
+    }
+
+    // 关键字的名字不含前缀冒号，其它包中的符号的名字不含包前缀
+    pub fn eval_symbol_name(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("symbol-name", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let symbol = SymbolOps::symbol_argument("symbol-name", &value)?;
+        let name = match package::uninterned_name(&symbol) {
+            Some(name) => name,
+            None if value.is_keyword() => &symbol[1..],
+            None => package::symbol_name(&symbol),
+        };
+        Ok(Expr::Str(name.to_string()))
+    }
+
+    pub fn eval_symbol_value(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("symbol-value", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let symbol = SymbolOps::symbol_argument("symbol-value", &value)?;
+        if value.is_keyword() {
+            return Ok(value);
+        }
+        env.get_symbol(&symbol)
+            .cloned()
+            .ok_or_else(|| LispError::new(&format!("symbol-value: the variable {} is unbound", symbol)))
+    }
+
+    pub fn setf_symbol_value(args: &[Expr], value: Expr, env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("(setf symbol-value)", args, 1, 1)?;
+        if args[0].is_keyword() {
+            return Err(LispError::new(&format!("(setf symbol-value): cannot assign to the keyword {}", args[0])));
+        }
+        let symbol = SymbolOps::symbol_argument("(setf symbol-value)", &args[0])?;
+        env.set_symbol(symbol, value.clone());
+        Ok(value)
+    }
+
+    pub fn eval_boundp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("boundp", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let symbol = SymbolOps::symbol_argument("boundp", &value)?;
+        Ok(Expr::from_bool(value.is_keyword() || env.get_symbol(&symbol).is_some()))
+    }
+
+    // (makunbound symbol)：返回该符号；t、nil 和关键字是常量，不能解除绑定
+    pub fn eval_makunbound(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("makunbound", args, 1, 1)?;
+        let value = Evaluator::eval(&args[0], env)?;
+        let symbol = SymbolOps::symbol_argument("makunbound", &value)?;
+        if value.is_keyword() || ["t", "T", "nil", "NIL"].contains(&symbol.as_str()) {
+            return Err(LispError::new(&format!("makunbound: cannot unbind the constant {}", value)));
+        }
+        env.remove_symbol(&symbol);
+        Ok(value)
+    }
+
+    // (intern name [package])：在指定的包（默认是当前包）中查找或创建符号
+    pub fn eval_intern(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("intern", args, 1, 2)?;
+        let name = SymbolOps::string_argument("intern", &Evaluator::eval(&args[0], env)?)?;
+        let package = match args.get(1) {
+            Some(arg) => {
+                let package = match Evaluator::eval(arg, env)? {
+                    Expr::Str(package) => package,
+                    other => package::symbol_name(&SymbolOps::symbol_argument("intern", &other)?).trim_start_matches(':').to_string(),
+                };
+                env.packages().resolve(&package).map_err(|message| LispError::new(&format!("intern: {}", message)))?
+            }
+            None => env.packages().current().to_string(),
+        };
+        Ok(Expr::Symbol(env.packages().intern(&package, &name)))
+    }
+
+    // (make-symbol name)：一个新的未驻留符号，与任何其它符号都不 eq，常用于宏展开中避免变量捕获
+    pub fn eval_make_symbol(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        expect_args("make-symbol", args, 1, 1)?;
+        let name = SymbolOps::string_argument("make-symbol", &Evaluator::eval(&args[0], env)?)?;
+        Ok(Expr::Symbol(package::make_uninterned(&name)))
+    }
+}
+
+pub fn register_symbol_operators() {
+    OperatorRegistry::register("get", SymbolOps::eval_get);
+    OperatorRegistry::register("remprop", SymbolOps::eval_remprop);
+    OperatorRegistry::register("symbol-plist", SymbolOps::eval_symbol_plist);
+    OperatorRegistry::register("symbol-name", SymbolOps::eval_symbol_name);
+    OperatorRegistry::register("symbol-value", SymbolOps::eval_symbol_value);
+    OperatorRegistry::register("boundp", SymbolOps::eval_boundp);
+    OperatorRegistry::register("makunbound", SymbolOps::eval_makunbound);
+    OperatorRegistry::register("intern", SymbolOps::eval_intern);
+    OperatorRegistry::register("make-symbol", SymbolOps::eval_make_symbol);
+    OperatorRegistry::register_setf("get", SymbolOps::setf_get);
+    OperatorRegistry::register_setf("symbol-plist", SymbolOps::setf_symbol_plist);
+    OperatorRegistry::register_setf("symbol-value", SymbolOps::setf_symbol_value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    #[test]
+    fn test_property_lists() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(get 'dog 'sound)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(get 'dog 'sound 'silent)", &mut env), Ok(Expr::Symbol("silent".to_string())));
+        eval_str("(setf (get 'dog 'sound) 'woof)", &mut env).unwrap();
+        eval_str("(setf (get 'dog 'legs) 4)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(get 'dog 'sound)", &mut env), "woof");
+        assert_eq!(eval_to_string("(symbol-plist 'dog)", &mut env), "(legs 4 sound woof)");
+        // 修改已有属性不改变顺序
+        eval_str("(setf (get 'dog 'sound) 'bark)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(symbol-plist 'dog)", &mut env), "(legs 4 sound bark)");
+        assert_eq!(eval_str("(remprop 'dog 'legs)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_str("(remprop 'dog 'legs)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("(symbol-plist 'dog)", &mut env), "(sound bark)");
+        eval_str("(setf (symbol-plist 'dog) '(color brown))", &mut env).unwrap();
+        assert_eq!(eval_to_string("(get 'dog 'color)", &mut env), "brown");
+        assert_eq!(eval_str("(get 1 'x)", &mut env), Err(LispError::new("get: 1 is not a symbol")));
+    }
+
+    #[test]
+    fn test_properties_are_shared_with_functions() {
+        let mut env = Environment::initialize();
+        eval_str("(defun tag (symbol value) (setf (get symbol 'tag) value))", &mut env).unwrap();
+        eval_str("(tag 'cat 'pet)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(get 'cat 'tag)", &mut env), "pet");
+    }
+
+    #[test]
+    fn test_symbol_introspection() {
+        let mut env = Environment::initialize();
+        assert_eq!(eval_str("(symbol-name 'apple)", &mut env), Ok(Expr::Str("apple".to_string())));
+        assert_eq!(eval_str("(symbol-name :key)", &mut env), Ok(Expr::Str("key".to_string())));
+        assert_eq!(eval_to_string("(intern \"pear\")", &mut env), "pear");
+        assert_eq!(eval_str("(eq (make-symbol \"pear\") 'pear)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_str("(eq (make-symbol \"A\") (make-symbol \"A\"))", &mut env), Ok(Expr::nil()));
+        eval_str("(setf fresh (make-symbol \"pear\"))", &mut env).unwrap();
+        assert_eq!(eval_str("(eq fresh fresh)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_to_string("fresh", &mut env), "#:pear");
+        assert_eq!(eval_str("(symbol-name fresh)", &mut env), Ok(Expr::Str("pear".to_string())));
+        // 读取器每次读到 #:name 都得到新的符号
+        assert_eq!(eval_str("(eq '#:pear '#:pear)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("'#:pear", &mut env), "#:pear");
+        assert_eq!(eval_str("(symbol-name '#:G7)", &mut env), Ok(Expr::Str("G7".to_string())));
+
+        assert_eq!(eval_str("(boundp 'x)", &mut env), Ok(Expr::nil()));
+        eval_str("(setf (symbol-value 'x) 10)", &mut env).unwrap();
+        assert_eq!(eval_str("x", &mut env), Ok(Expr::Number(10)));
+        assert_eq!(eval_str("(symbol-value (intern \"x\"))", &mut env), Ok(Expr::Number(10)));
+        assert_eq!(eval_str("(boundp 'x)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_to_string("(makunbound 'x)", &mut env), "x");
+        assert_eq!(eval_str("(boundp 'x)", &mut env), Ok(Expr::nil()));
+        assert_eq!(
+            eval_str("(symbol-value 'x)", &mut env),
+            Err(LispError::new("symbol-value: the variable x is unbound"))
+        );
+        assert_eq!(eval_str("(boundp :key)", &mut env), Ok(Expr::t()));
+        assert_eq!(eval_to_string("(symbol-value :key)", &mut env), ":key");
+        assert_eq!(
+            eval_str("(makunbound t)", &mut env),
+            Err(LispError::new("makunbound: cannot unbind the constant t"))
+        );
+        assert_eq!(eval_str("(intern 'x)", &mut env), Err(LispError::new("intern: x is not a string")));
+        eval_str("(defpackage :zoo)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(intern \"lion\" :zoo)", &mut env), "zoo::lion");
+        assert_eq!(eval_str("(symbol-name 'zoo::lion)", &mut env), Ok(Expr::Str("lion".to_string())));
+    }
+}
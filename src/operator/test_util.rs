@@ -0,0 +1,26 @@
+// operator/test_util.rs
+
+// 各操作符模块的测试共用的辅助函数
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::parser::Parser;
+use crate::printer::{PrintOptions, Printer};
+
+// 读取并求值一个形式
+pub fn eval_str(input: &str, env: &mut Environment) -> Result<Expr, LispError> {
+    let expr = Parser::read(input, env)?;
+    Evaluator::eval(&expr, env)
+}
+
+// 求值并返回结果在 REPL 中的打印形式
+pub fn eval_to_string(input: &str, env: &mut Environment) -> String {
+    let value = eval_str(input, env).unwrap();
+    Printer::print(&value, &PrintOptions::from_env(env))
+}
+
+// 在新环境中求值并返回结果的打印形式
+pub fn eval_in_new_env(input: &str) -> String {
+    eval_to_string(input, &mut Environment::initialize())
+}
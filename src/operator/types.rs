@@ -0,0 +1,403 @@
+// operator/types.rs
+
+use crate::operator::OperatorRegistry;
+use crate::operator::arithmetic::Arithmetic;
+use crate::operator::clos::Clos;
+use crate::operator::comparison::Comparison;
+use crate::operator::lambda::Lambda;
+use crate::clos;
+use crate::environment::Environment;
+use crate::exception::LispError;
+use crate::expression::Expr;
+use crate::evaluator::Evaluator;
+use std::cmp::Ordering;
+
+pub struct TypeOps;
+
+impl TypeOps {
+    fn single_argument(name: &str, args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 1 {
+            return Err(LispError::new(&format!("{} expects 1 argument", name)));
+        }
+        Evaluator::eval(&args[0], env)
+    }
+
+    // lambda 表达式就是函数对象
+    fn is_function(value: &Expr) -> bool {
+        matches!(value, Expr::List(items) if items.first() == Some(&Expr::Symbol("lambda".to_string())))
+    }
+
+    fn is_cons(value: &Expr) -> bool {
+        value.is_dotted_pair() || matches!(value, Expr::List(items) if !items.is_empty())
+    }
+
+    fn is_real(value: &Expr) -> bool {
+        matches!(value, Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_) | Expr::Float(_))
+    }
+
+    fn is_rational(value: &Expr) -> bool {
+        matches!(value, Expr::Number(_) | Expr::BigInt(_) | Expr::Ratio(_))
+    }
+
+    // (type-of object)：最具体的类型名，t 是 boolean，关键字是 keyword，实例和结构实例是它们的类型名
+    pub fn type_of(value: &Expr) -> String {
+        if value.is_keyword() {
+            "keyword".to_string()
+        } else if *value == Expr::t() {
+            "boolean".to_string()
+        } else {
+            clos::precedence_list(value).swap_remove(0)
+        }
+    }
+
+    // 数值范围的上下界：* 表示不限，(n) 表示不含 n
+    fn within_bound(value: &Expr, bound: Option<&Expr>, lower: bool) -> Result<bool, LispError> {
+        let (bound, exclusive) = match bound {
+            None => return Ok(true),
+            Some(Expr::Symbol(s)) if s == "*" => return Ok(true),
+            Some(Expr::List(items)) if items.len() == 1 => (&items[0], true),
+            Some(bound) => (bound, false),
+        };
+        let ordering = Arithmetic::compare(value, bound)
+            .ok_or_else(|| LispError::new(&format!("typep: invalid bound {} in type specifier", bound)))?;
+        Ok(match (lower, exclusive) {
+            (true, false) => ordering != Ordering::Less,
+            (true, true) => ordering == Ordering::Greater,
+            (false, false) => ordering != Ordering::Greater,
+            (false, true) => ordering == Ordering::Less,
+        })
+    }
+
+    fn in_range(value: &Expr, bounds: &[Expr]) -> Result<bool, LispError> {
+        if bounds.len() > 2 {
+            return Err(LispError::new("typep: a numeric type specifier takes at most two bounds"));
+        }
+        Ok(TypeOps::within_bound(value, bounds.first(), true)? && TypeOps::within_bound(value, bounds.get(1), false)?)
+    }
+
+    // 判断 value 是否属于类型说明符 spec。复合说明符支持 or、and、not、member、eql、satisfies、mod
+    // 以及带范围的 integer、rational、real 和 float
+    pub fn typep(value: &Expr, spec: &Expr, env: &mut Environment) -> Result<bool, LispError> {
+        let unknown = || LispError::new(&format!("typep: unknown type specifier {}", spec));
+        match spec {
+            Expr::List(parts) if parts.is_empty() => Ok(false),
+            Expr::Symbol(name) => match name.as_str() {
+                "t" | "T" => Ok(true),
+                "nil" | "NIL" => Ok(false),
+                "keyword" => Ok(value.is_keyword()),
+                "boolean" => Ok(value.is_null() || *value == Expr::t()),
+                "atom" => Ok(!TypeOps::is_cons(value)),
+                "fixnum" => Ok(matches!(value, Expr::Number(_))),
+                "bignum" => Ok(matches!(value, Expr::BigInt(_))),
+                "bit" => Ok(matches!(value, Expr::Number(0) | Expr::Number(1))),
+                "simple-string" => Ok(value.is_string()),
+                "simple-vector" => TypeOps::typep(value, &Expr::Symbol("vector".to_string()), env),
+                "function" => Ok(TypeOps::is_function(value)),
+                name if Clos::class_exists(name, env) => Ok(clos::precedence_list(value).iter().any(|class| class == name)),
+                _ => Err(unknown()),
+            },
+            Expr::List(parts) => {
+                let (head, rest) = match parts.split_first() {
+                    Some((Expr::Symbol(head), rest)) => (head.as_str(), rest),
+                    _ => return Err(unknown()),
+                };
+                match head {
+                    "or" => {
+                        for spec in rest {
+                            if TypeOps::typep(value, spec, env)? {
+                                return Ok(true);
+                            }
+                        }
+                        Ok(false)
+                    }
+                    "and" => {
+                        for spec in rest {
+                            if !TypeOps::typep(value, spec, env)? {
+                                return Ok(false);
+                            }
+                        }
+                        Ok(true)
+                    }
+                    "not" if rest.len() == 1 => Ok(!TypeOps::typep(value, &rest[0], env)?),
+                    "member" => Ok(rest.iter().any(|item| Comparison::is_eql(item, value))),
+                    "eql" if rest.len() == 1 => Ok(Comparison::is_eql(&rest[0], value)),
+                    "satisfies" if rest.len() == 1 => {
+                        Ok(Lambda::funcall(&rest[0], vec![value.clone()], env)?.is_truthy())
+                    }
+                    "mod" if rest.len() == 1 => {
+                        Ok(value.is_integer() && TypeOps::in_range(value, &[Expr::Number(0), Expr::List(vec![rest[0].clone()])])?)
+                    }
+                    "integer" => Ok(value.is_integer() && TypeOps::in_range(value, rest)?),
+                    "rational" => Ok(TypeOps::is_rational(value) && TypeOps::in_range(value, rest)?),
+                    "real" => Ok(TypeOps::is_real(value) && TypeOps::in_range(value, rest)?),
+                    "float" => Ok(value.is_float() && TypeOps::in_range(value, rest)?),
+                    _ => Err(unknown()),
+                }
+            }
+            _ => Err(unknown()),
+        }
+    }
+
+    pub fn eval_numberp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("numberp", args, env)?.is_number()))
+    }
+
+    pub fn eval_integerp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("integerp", args, env)?.is_integer()))
+    }
+
+    pub fn eval_floatp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("floatp", args, env)?.is_float()))
+    }
+
+    pub fn eval_stringp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("stringp", args, env)?.is_string()))
+    }
+
+    // nil 也是符号
+    pub fn eval_symbolp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = TypeOps::single_argument("symbolp", args, env)?;
+        Ok(Expr::from_bool(value.is_symbol() || value.is_null()))
+    }
+
+    pub fn eval_listp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = TypeOps::single_argument("listp", args, env)?;
+        Ok(Expr::from_bool(value.is_list() || value.is_dotted_pair() || value.is_null()))
+    }
+
+    pub fn eval_consp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::is_cons(&TypeOps::single_argument("consp", args, env)?)))
+    }
+
+    pub fn eval_atom(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(!TypeOps::is_cons(&TypeOps::single_argument("atom", args, env)?)))
+    }
+
+    pub fn eval_null(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("null", args, env)?.is_null()))
+    }
+
+    pub fn eval_functionp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::is_function(&TypeOps::single_argument("functionp", args, env)?)))
+    }
+
+    pub fn eval_keywordp(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(Expr::from_bool(TypeOps::single_argument("keywordp", args, env)?.is_keyword()))
+    }
+
+    pub fn eval_type_of(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let value = TypeOps::single_argument("type-of", args, env)?;
+        Ok(Expr::Symbol(TypeOps::type_of(&value)))
+    }
+
+    // (typep object type-specifier)
+    pub fn eval_typep(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() != 2 {
+            return Err(LispError::new("typep expects 2 arguments"));
+        }
+        let value = Evaluator::eval(&args[0], env)?;
+        let spec = Evaluator::eval(&args[1], env)?;
+        Ok(Expr::from_bool(TypeOps::typep(&value, &spec, env)?))
+    }
+
+    // typecase 和 etypecase 共用：返回第一个类型匹配的子句的结果；otherwise 或 t 子句匹配任何值
+    fn eval_type_case(name: &str, args: &[Expr], env: &mut Environment) -> Result<Option<Expr>, LispError> {
+        let (keyform, clauses) = args
+            .split_first()
+            .ok_or_else(|| LispError::new(&format!("{} requires a key form", name)))?;
+        let value = Evaluator::eval(keyform, env)?;
+        for clause in clauses {
+            let (spec, body) = match clause {
+                Expr::List(parts) if !parts.is_empty() => (&parts[0], &parts[1..]),
+                other => return Err(LispError::new(&format!("{}: invalid clause {}", name, other))),
+            };
+            let matches = match spec {
+                Expr::Symbol(s) if s == "otherwise" => true,
+                spec => TypeOps::typep(&value, spec, env)?,
+            };
+            // 键形式和 satisfies 谓词留下的多值不是 typecase 的值
+            env.clear_values();
+            if matches {
+                return Lambda::eval_progn(body, env).map(Some);
+            }
+        }
+        env.clear_values();
+        Ok(None)
+    }
+
+    pub fn eval_typecase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        Ok(TypeOps::eval_type_case("typecase", args, env)?.unwrap_or_else(Expr::nil))
+    }
+
+    // 没有子句匹配时报错，错误信息列出全部子句的类型
+    pub fn eval_etypecase(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if let Some(result) = TypeOps::eval_type_case("etypecase", args, env)? {
+            return Ok(result);
+        }
+        let value = Evaluator::eval(&args[0], env)?;
+        let specs: Vec<String> = args[1..]
+            .iter()
+            .filter_map(|clause| match clause {
+                Expr::List(parts) => parts.first().map(|spec| spec.to_string()),
+                _ => None,
+            })
+            .collect();
+        Err(LispError::new(&format!("etypecase: {} is not of type (or {})", value, specs.join(" "))))
+    }
+
+    // (check-type place type-specifier [description])：值不属于该类型时报错，否则返回 nil
+    pub fn eval_check_type(args: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        if args.len() < 2 || args.len() > 3 {
+            return Err(LispError::new("check-type expects 2 to 3 arguments"));
+        }
+        let value = Evaluator::eval(&args[0], env)?;
+        if TypeOps::typep(&value, &args[1], env)? {
+            env.clear_values();
+            return Ok(Expr::nil());
+        }
+        let expected = match args.get(2) {
+            Some(Expr::Str(description)) => description.clone(),
+            Some(other) => return Err(LispError::new(&format!("check-type: description must be a string, got {}", other))),
+            None => format!("of type {}", args[1]),
+        };
+        Err(LispError::new(&format!("check-type: the value of {} is {}, which is not {}", args[0], value, expected)))
+    }
+}
+
+pub fn register_type_operators() {
+    OperatorRegistry::register("numberp", TypeOps::eval_numberp);
+    OperatorRegistry::register("integerp", TypeOps::eval_integerp);
+    OperatorRegistry::register("floatp", TypeOps::eval_floatp);
+    OperatorRegistry::register("stringp", TypeOps::eval_stringp);
+    OperatorRegistry::register("symbolp", TypeOps::eval_symbolp);
+    OperatorRegistry::register("listp", TypeOps::eval_listp);
+    OperatorRegistry::register("consp", TypeOps::eval_consp);
+    OperatorRegistry::register("atom", TypeOps::eval_atom);
+    OperatorRegistry::register("null", TypeOps::eval_null);
+    OperatorRegistry::register("functionp", TypeOps::eval_functionp);
+    OperatorRegistry::register("keywordp", TypeOps::eval_keywordp);
+    OperatorRegistry::register("type-of", TypeOps::eval_type_of);
+    OperatorRegistry::register("typep", TypeOps::eval_typep);
+    OperatorRegistry::register("typecase", TypeOps::eval_typecase);
+    OperatorRegistry::register("etypecase", TypeOps::eval_etypecase);
+    OperatorRegistry::register("check-type", TypeOps::eval_check_type);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::test_util::{eval_str, eval_to_string};
+
+    fn assert_true(input: &str, env: &mut Environment) {
+        assert_eq!(eval_str(input, env), Ok(Expr::t()), "{}", input);
+    }
+
+    fn assert_false(input: &str, env: &mut Environment) {
+        assert_eq!(eval_str(input, env), Ok(Expr::nil()), "{}", input);
+    }
+
+    #[test]
+    fn test_type_predicates() {
+        let mut env = Environment::initialize();
+        for input in [
+            "(numberp 1)", "(numberp 1/2)", "(numberp 1.5)", "(numberp #C(1 2))", "(integerp 100000000000000000000)",
+            "(floatp 1.0)", "(stringp \"s\")", "(symbolp 'a)", "(symbolp nil)", "(symbolp :k)", "(listp nil)",
+            "(listp '(1))", "(consp '(1))", "(atom 1)", "(atom nil)", "(null nil)", "(null 'nil)",
+            "(functionp (lambda (x) x))", "(keywordp :k)",
+        ] {
+            assert_true(input, &mut env);
+        }
+        for input in [
+            "(numberp \"1\")", "(integerp 1.0)", "(floatp 1)", "(stringp 'a)", "(symbolp 1)", "(listp 1)",
+            "(consp nil)", "(atom '(1))", "(null 0)", "(functionp 'car)", "(keywordp 'k)",
+        ] {
+            assert_false(input, &mut env);
+        }
+    }
+
+    #[test]
+    fn test_type_of() {
+        let mut env = Environment::initialize();
+        for (input, expected) in [
+            ("(type-of 1)", "integer"),
+            ("(type-of 1/2)", "ratio"),
+            ("(type-of 1.5)", "float"),
+            ("(type-of \"s\")", "string"),
+            ("(type-of 'a)", "symbol"),
+            ("(type-of :a)", "keyword"),
+            ("(type-of t)", "boolean"),
+            ("(type-of nil)", "null"),
+            ("(type-of '(1 2))", "cons"),
+            ("(type-of #\\a)", "character"),
+            ("(type-of (make-hash-table))", "hash-table"),
+        ] {
+            assert_eq!(eval_to_string(input, &mut env), expected, "{}", input);
+        }
+        eval_str("(defstruct point x)", &mut env).unwrap();
+        assert_eq!(eval_to_string("(type-of (make-point))", &mut env), "point");
+    }
+
+    #[test]
+    fn test_typep_with_compound_specifiers() {
+        let mut env = Environment::initialize();
+        for input in [
+            "(typep 5 'integer)", "(typep 5 'number)", "(typep \"s\" '(or string null))", "(typep nil '(or string null))",
+            "(typep 5 '(integer 0 10))", "(typep 10 '(integer 0 10))", "(typep 5 '(integer 0 *))", "(typep 1/2 '(real 0 (1)))",
+            "(typep 3 '(and integer (not (eql 4))))", "(typep :b '(member :a :b))", "(typep 4 '(satisfies evenp))",
+            "(typep 9 '(mod 10))", "(typep '(1) 'list)", "(typep 'a 'atom)",
+        ] {
+            assert_true(input, &mut env);
+        }
+        for input in [
+            "(typep 5 '(or string null))", "(typep 11 '(integer 0 10))", "(typep 0 '(integer (0) 10))",
+            "(typep 5.0 '(integer 0 10))", "(typep 4 '(and integer (not (eql 4))))", "(typep 10 '(mod 10))",
+            "(typep 1 nil)",
+        ] {
+            assert_false(input, &mut env);
+        }
+        eval_str("(defstruct shape)", &mut env).unwrap();
+        eval_str("(defstruct (circle (:include shape)) radius)", &mut env).unwrap();
+        assert_true("(typep (make-circle) 'shape)", &mut env);
+        assert_eq!(
+            eval_str("(typep 1 'widget)", &mut env),
+            Err(LispError::new("typep: unknown type specifier widget"))
+        );
+    }
+
+    #[test]
+    fn test_typecase_and_check_type() {
+        let mut env = Environment::initialize();
+        eval_str(
+            "(defun classify (x) (typecase x ((integer 0 *) 'natural) (integer 'negative) ((or string symbol) 'name) (otherwise 'other)))",
+            &mut env,
+        )
+        .unwrap();
+        assert_eq!(eval_to_string("(classify 3)", &mut env), "natural");
+        assert_eq!(eval_to_string("(classify -3)", &mut env), "negative");
+        assert_eq!(eval_to_string("(classify \"x\")", &mut env), "name");
+        assert_eq!(eval_to_string("(classify 1.5)", &mut env), "other");
+        assert_eq!(eval_str("(typecase 1.5 (string 1))", &mut env), Ok(Expr::nil()));
+        // 选中子句的最后一个形式的多值原样传出，键形式的多值则不会
+        assert_eq!(eval_to_string("(multiple-value-list (typecase 1 (integer (values 1 2))))", &mut env), "(1 2)");
+        assert_eq!(eval_to_string("(multiple-value-list (etypecase 1 (integer (values 1 2))))", &mut env), "(1 2)");
+        assert_eq!(eval_to_string("(multiple-value-list (typecase (values 1 2) (string 's)))", &mut env), "(())");
+        assert_eq!(eval_to_string("(multiple-value-list (typecase (values 1 2) (integer)))", &mut env), "(())");
+        assert_eq!(eval_str("(etypecase 1 (string 's) (integer 'i))", &mut env), Ok(Expr::Symbol("i".to_string())));
+        assert_eq!(
+            eval_str("(etypecase 1.5 (string 's) (integer 'i))", &mut env),
+            Err(LispError::new("etypecase: 1.5 is not of type (or string integer)"))
+        );
+
+        eval_str("(setf x 5)", &mut env).unwrap();
+        assert_eq!(eval_str("(check-type x integer)", &mut env), Ok(Expr::nil()));
+        assert_eq!(eval_to_string("(multiple-value-list (check-type (values x 2) integer))", &mut env), "(())");
+        assert_eq!(
+            eval_str("(check-type x string)", &mut env),
+            Err(LispError::new("check-type: the value of x is 5, which is not of type string"))
+        );
+        assert_eq!(
+            eval_str("(check-type x (integer 0 3) \"a small number\")", &mut env),
+            Err(LispError::new("check-type: the value of x is 5, which is not a small number"))
+        );
+    }
+}
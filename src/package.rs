@@ -0,0 +1,337 @@
+// package.rs
+
+use crate::clos::BUILTIN_CLASSES;
+use crate::operator::OperatorRegistry;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// 内置的包：common-lisp 包含标准操作符和预定义符号，rust-lisp 包含本实现的扩展，
+// common-lisp-user 使用这两个包，是默认的当前包
+pub const COMMON_LISP: &str = "common-lisp";
+pub const COMMON_LISP_USER: &str = "common-lisp-user";
+pub const RUST_LISP: &str = "rust-lisp";
+
+// 不属于 Common Lisp 标准、由 rust-lisp 导出的操作符
+const EXTENSIONS: &[&str] = &[
+    "%call-generic", "%copy-struct", "%make-struct", "%struct-ref", "%struct-set", "%struct-typep", "gt", "gte", "lt",
+    "lte", "ne", "hash-table-keys", "hash-table-values", "join", "split-string", "string-replace", "number-to-string",
+    "parse-float", "whitespace-char-p", "seed-random-state", "quasiquote", "unquote",
+];
+
+// common-lisp 中不是操作符、内置类或者预定义变量的符号
+const COMMON_LISP_SYMBOLS: &[&str] = &[
+    "t", "T", "nil", "NIL", "&optional", "&rest", "&key", "&aux", "&body", "&allow-other-keys", "&whole", "&environment",
+    "declare", "ignore", "ignorable", "special", "optimize", "defmacro", "otherwise", "function", "and", "or", "not",
+    "member", "satisfies", "eql", "keyword", "atom", "boolean", "fixnum", "bignum", "bit", "simple-string",
+    "simple-vector",
+];
+
+// 符号仍然用字符串表示：家在 common-lisp、rust-lisp 或 common-lisp-user 中的符号就是它的名字，
+// 其它包中的符号带上包名，例如 mylib::parse。这样两个包中的同名定义不会互相覆盖
+#[derive(Debug, Default)]
+struct Package {
+    nicknames: Vec<String>,
+    // 以本包为家的符号名
+    present: HashSet<String>,
+    // 从其它包导入的符号，名字 → 符号
+    imports: HashMap<String, String>,
+    external: HashSet<String>,
+    uses: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Packages {
+    packages: HashMap<String, Package>,
+    current: String,
+}
+
+// 符号的包名部分和名字部分；名字中的 :: 之前是包名
+pub fn split_symbol(symbol: &str) -> (Option<&str>, &str) {
+    match symbol.find("::") {
+        Some(index) if index > 0 => (Some(&symbol[..index]), &symbol[index + 2..]),
+        _ => (None, symbol),
+    }
+}
+
+// 去掉包前缀之后的符号名
+pub fn symbol_name(symbol: &str) -> &str {
+    split_symbol(symbol).1
+}
+
+static UNINTERNED_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+// 未驻留的符号写作 #:name。make-symbol 和读取器读到的 #:name 在后面加上 #编号，
+// 所以每次得到的符号都和其它任何符号不同；打印和 symbol-name 都不显示编号
+pub fn make_uninterned(name: &str) -> String {
+    format!("#:{}#{}", name, UNINTERNED_COUNTER.fetch_add(1, Ordering::SeqCst))
+}
+
+// 未驻留符号的名字；gensym 生成的 #:G1 没有编号
+pub fn uninterned_name(symbol: &str) -> Option<&str> {
+    let name = symbol.strip_prefix("#:")?;
+    match name.rsplit_once('#') {
+        Some((name, id)) if !id.is_empty() && id.chars().all(|ch| ch.is_ascii_digit()) => Some(name),
+        _ => Some(name),
+    }
+}
+
+// 包名不区分大小写，统一用小写保存
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+}
+
+fn canonical(package: &str, name: &str) -> String {
+    if package == COMMON_LISP || package == COMMON_LISP_USER || package == RUST_LISP {
+        name.to_string()
+    } else {
+        format!("{}::{}", package, name)
+    }
+}
+
+impl Packages {
+    // extra_symbols 是 common-lisp 中预定义的变量名
+    pub fn new<'a>(extra_symbols: impl Iterator<Item = &'a String>) -> Self {
+        let mut packages = Packages { packages: HashMap::new(), current: COMMON_LISP_USER.to_string() };
+        packages.define(COMMON_LISP, &["cl".to_string()]).unwrap();
+        packages.define(RUST_LISP, &[]).unwrap();
+        let (extensions, operators): (Vec<String>, Vec<String>) =
+            OperatorRegistry::names().into_iter().partition(|name| EXTENSIONS.contains(&name.as_str()));
+        let builtins = COMMON_LISP_SYMBOLS.iter().chain(BUILTIN_CLASSES).map(|s| s.to_string());
+        for (package, names) in [(COMMON_LISP, builtins.chain(operators).chain(extra_symbols.cloned()).collect()), (RUST_LISP, extensions)] {
+            let entry = packages.packages.get_mut(package).unwrap();
+            for name in names {
+                entry.present.insert(name.clone());
+                entry.external.insert(name);
+            }
+        }
+        packages.use_package(RUST_LISP, COMMON_LISP).unwrap();
+        packages.define(COMMON_LISP_USER, &["cl-user".to_string(), "user".to_string()]).unwrap();
+        packages.use_package(COMMON_LISP_USER, COMMON_LISP).unwrap();
+        packages.use_package(COMMON_LISP_USER, RUST_LISP).unwrap();
+        packages
+    }
+
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    // 由名字或昵称找到包的正式名字
+    pub fn find(&self, name: &str) -> Option<String> {
+        let name = normalize(name);
+        if self.packages.contains_key(&name) {
+            return Some(name);
+        }
+        self.packages
+            .iter()
+            .find(|(_, package)| package.nicknames.contains(&name))
+            .map(|(package_name, _)| package_name.clone())
+    }
+
+    pub fn resolve(&self, name: &str) -> Result<String, String> {
+        self.find(name).ok_or_else(|| format!("package {} does not exist", name))
+    }
+
+    // 创建包（已存在时只补充昵称），返回正式名字
+    pub fn define(&mut self, name: &str, nicknames: &[String]) -> Result<String, String> {
+        let name = normalize(name);
+        for nickname in nicknames {
+            if let Some(owner) = self.find(nickname).filter(|owner| *owner != name) {
+                return Err(format!("the name {} is already used by package {}", nickname, owner));
+            }
+        }
+        let package = self.packages.entry(name.clone()).or_default();
+        for nickname in nicknames.iter().map(|nickname| normalize(nickname)) {
+            if !package.nicknames.contains(&nickname) {
+                package.nicknames.push(nickname);
+            }
+        }
+        Ok(name)
+    }
+
+    pub fn set_current(&mut self, name: &str) -> Result<String, String> {
+        self.current = self.resolve(name)?;
+        Ok(self.current.clone())
+    }
+
+    // 在包中可以访问的名为 name 的符号：导入的、以本包为家的，或者被使用的包导出的
+    pub fn find_symbol(&self, package: &str, name: &str) -> Option<String> {
+        let entry = self.packages.get(package)?;
+        if let Some(symbol) = entry.imports.get(name) {
+            return Some(symbol.clone());
+        }
+        if entry.present.contains(name) {
+            return Some(canonical(package, name));
+        }
+        entry.uses.iter().find_map(|used| self.find_external(used, name))
+    }
+
+    fn find_external(&self, package: &str, name: &str) -> Option<String> {
+        let entry = self.packages.get(package)?;
+        if entry.external.contains(name) {
+            self.find_symbol(package, name)
+        } else {
+            None
+        }
+    }
+
+    // 在当前包中可以直接访问的符号只打印名字，其它包导出的符号打印为 pkg:name，其余为 pkg::name
+    pub fn print_name(&self, symbol: &str) -> String {
+        if symbol.starts_with(':') {
+            return symbol.to_string();
+        }
+        let (package, name) = split_symbol(symbol);
+        let home = match package {
+            Some(package) => package,
+            None if self.packages[COMMON_LISP].present.contains(name) => COMMON_LISP,
+            None if self.packages[RUST_LISP].present.contains(name) => RUST_LISP,
+            None => COMMON_LISP_USER,
+        };
+        if home == self.current || self.find_symbol(&self.current, name).as_deref() == Some(symbol) {
+            return name.to_string();
+        }
+        let exported = self.find_external(home, name).as_deref() == Some(symbol);
+        format!("{}{}{}", home, if exported { ":" } else { "::" }, name)
+    }
+
+    pub fn intern(&mut self, package: &str, name: &str) -> String {
+        if let Some(symbol) = self.find_symbol(package, name) {
+            return symbol;
+        }
+        self.packages.get_mut(package).unwrap().present.insert(name.to_string());
+        canonical(package, name)
+    }
+
+    pub fn intern_current(&mut self, name: &str) -> String {
+        let current = self.current.clone();
+        self.intern(&current, name)
+    }
+
+    // 读取器读到的符号记号：pkg::name 可以访问任何符号，pkg:name 只能访问导出的符号，其余的在当前包中驻留
+    pub fn read_symbol(&mut self, token: &str) -> Result<String, String> {
+        let colon = match token.find(':') {
+            Some(index) if index > 0 => index,
+            _ => return Ok(if token.starts_with(':') { token.to_string() } else { self.intern_current(token) }),
+        };
+        let package = self.resolve(&token[..colon])?;
+        let (internal, name) = match token[colon + 1..].strip_prefix(':') {
+            Some(name) => (true, name),
+            None => (false, &token[colon + 1..]),
+        };
+        if name.is_empty() || name.contains(':') {
+            return Err(format!("invalid symbol {}", token));
+        }
+        if internal {
+            return Ok(self.intern(&package, name));
+        }
+        self.find_external(&package, name)
+            .ok_or_else(|| format!("symbol {} is not external in package {}", name, package))
+    }
+
+    // 导出包中可以访问的符号；从被使用的包继承来的符号先导入本包
+    pub fn export(&mut self, package: &str, symbol: &str) -> Result<(), String> {
+        let name = symbol_name(symbol);
+        let entry = &self.packages[package];
+        match self.find_symbol(package, name) {
+            Some(found) if found == symbol => {
+                let inherited = !entry.present.contains(name) && !entry.imports.contains_key(name);
+                let entry = self.packages.get_mut(package).unwrap();
+                if inherited {
+                    entry.imports.insert(name.to_string(), symbol.to_string());
+                }
+                entry.external.insert(name.to_string());
+                Ok(())
+            }
+            _ => Err(format!("symbol {} is not accessible in package {}", symbol, package)),
+        }
+    }
+
+    // 导入符号；包中已经可以访问另一个同名符号时报告冲突
+    pub fn import(&mut self, package: &str, symbol: &str) -> Result<(), String> {
+        let name = symbol_name(symbol);
+        match self.find_symbol(package, name) {
+            Some(existing) if existing != symbol => {
+                Err(format!("importing {} conflicts with {} in package {}", symbol, existing, package))
+            }
+            _ => {
+                let entry = self.packages.get_mut(package).unwrap();
+                entry.imports.insert(name.to_string(), symbol.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    // 使用另一个包：它导出的符号不能和本包中已经可以访问的同名符号冲突
+    pub fn use_package(&mut self, package: &str, used: &str) -> Result<(), String> {
+        if package == used || self.packages[package].uses.iter().any(|u| u == used) {
+            return Ok(());
+        }
+        for name in &self.packages[used].external {
+            if let (Some(existing), Some(inherited)) = (self.find_symbol(package, name), self.find_external(used, name)) {
+                if existing != inherited {
+                    return Err(format!("using package {} makes {} conflict with {} in package {}", used, inherited, existing, package));
+                }
+            }
+        }
+        self.packages.get_mut(package).unwrap().uses.push(used.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages() -> Packages {
+        let variables = ["*print-base*".to_string()];
+        Packages::new(variables.iter())
+    }
+
+    #[test]
+    fn test_reading_symbols() {
+        let mut packages = packages();
+        assert_eq!(packages.read_symbol("parse"), Ok("parse".to_string()));
+        assert_eq!(packages.read_symbol(":key"), Ok(":key".to_string()));
+        assert_eq!(packages.read_symbol("cl:*print-base*"), Ok("*print-base*".to_string()));
+        assert_eq!(packages.read_symbol("user::parse"), Ok("parse".to_string()));
+        assert_eq!(packages.read_symbol("missing:x"), Err("package missing does not exist".to_string()));
+
+        packages.define("mylib", &[]).unwrap();
+        packages.use_package("mylib", COMMON_LISP).unwrap();
+        packages.set_current("MyLib").unwrap();
+        assert_eq!(packages.read_symbol("parse"), Ok("mylib::parse".to_string()));
+        assert_eq!(packages.read_symbol("t"), Ok("t".to_string()));
+        assert_eq!(packages.read_symbol("&rest"), Ok("&rest".to_string()));
+
+        packages.set_current("cl-user").unwrap();
+        assert_eq!(packages.read_symbol("mylib::parse"), Ok("mylib::parse".to_string()));
+        assert_eq!(packages.read_symbol("mylib:parse"), Err("symbol parse is not external in package mylib".to_string()));
+        packages.export("mylib", "mylib::parse").unwrap();
+        assert_eq!(packages.read_symbol("mylib:parse"), Ok("mylib::parse".to_string()));
+    }
+
+    #[test]
+    fn test_use_and_import_conflicts() {
+        let mut packages = packages();
+        packages.define("mylib", &["lib".to_string()]).unwrap();
+        let parse = packages.intern("mylib", "parse");
+        packages.export("mylib", &parse).unwrap();
+
+        packages.define("app", &[]).unwrap();
+        packages.use_package("app", "mylib").unwrap();
+        assert_eq!(packages.find_symbol("app", "parse"), Some(parse.clone()));
+
+        // cl-user 中已经有自己的 parse
+        packages.intern(COMMON_LISP_USER, "parse");
+        assert!(packages.use_package(COMMON_LISP_USER, "mylib").is_err());
+        assert!(packages.import(COMMON_LISP_USER, &parse).is_err());
+
+        packages.import(COMMON_LISP_USER, "mylib::helper").unwrap();
+        assert_eq!(packages.find_symbol(COMMON_LISP_USER, "helper"), Some("mylib::helper".to_string()));
+        assert_eq!(symbol_name("mylib::helper"), "helper");
+        assert_eq!(uninterned_name("#:G1"), Some("G1"));
+        assert_eq!(uninterned_name(&make_uninterned("a#b")), Some("a#b"));
+        assert_ne!(make_uninterned("a"), make_uninterned("a"));
+        assert_eq!(uninterned_name("helper"), None);
+        assert_eq!(packages.find("LIB"), Some("mylib".to_string()));
+    }
+}
@@ -1,16 +1,83 @@
 // parser.rs
 
+use crate::array::Array;
+use crate::bignum::BigInt;
+use crate::ratio::Ratio;
+use crate::character;
+use crate::operator::arithmetic::Arithmetic;
+use crate::operator::array::ArrayOps;
+use crate::operator::lambda::Lambda;
 use crate::environment::Environment;
 use crate::exception::LispError;
 use crate::expression::Expr;
 use crate::macro_expander::MacroExpander;
+use crate::package;
+use crate::source::SourcePosition;
+use std::iter::Peekable;
 use std::str::Chars;
 
+// 带行列号跟踪的字符读取器，current_position() 返回下一个字符的位置
+pub struct SourceReader<'a> {
+    chars: Peekable<Chars<'a>>,
+    file: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> SourceReader<'a> {
+    pub fn new(input: &'a str, file: &'a str, first_line: usize) -> Self {
+        SourceReader {
+            chars: input.chars().peekable(),
+            file,
+            line: first_line,
+            column: 1,
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    // 下一个字符之后的那个字符
+    pub fn peek_second(&self) -> Option<char> {
+        self.chars.clone().nth(1)
+    }
+
+    pub fn current_position(&self) -> SourcePosition {
+        SourcePosition::new(self.file, self.line, self.column)
+    }
+
+    pub fn error(&self, message: &str) -> LispError {
+        LispError::new(message).with_position(self.current_position())
+    }
+}
+
+impl Iterator for SourceReader<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+}
+
 pub struct Parser;
 
 impl Parser {
+    #[allow(dead_code)]
     pub fn read(input: &str, env: &mut Environment) -> Result<Expr, LispError> {
-        let mut chars = input.chars().peekable();
+        Parser::read_from(input, "<input>", 1, env)
+    }
+
+    // 从指定文件的指定行开始读取一个形式，位置信息会出现在错误中
+    pub fn read_from(input: &str, file: &str, first_line: usize, env: &mut Environment) -> Result<Expr, LispError> {
+        let mut chars = SourceReader::new(input, file, first_line);
 
         // 如果输入是空的或者全是空白，返回空列表
         Parser::skip_whitespace_and_comments(&mut chars);
@@ -19,21 +86,24 @@ impl Parser {
         }
 
         // 解析表达式
+        let start = chars.current_position();
+        env.begin_read();
         let expr = Parser::parse_expr(&mut chars, env)?;
+        env.finish_read();
 
         // 确保没有在解析后的多余输入
         Parser::skip_whitespace_and_comments(&mut chars);
         if chars.peek().is_some() {
-            return Err(LispError::new("Unexpected input after list"));
+            return Err(chars.error("Unexpected input after list"));
         }
 
         // 在解析完成后展开宏
-        let expanded_expr = MacroExpander::expand_macro(&expr, env)?;
+        let expanded_expr = MacroExpander::expand_macro(&expr, env).map_err(|err| err.with_position(start))?;
 
         Ok(expanded_expr)
     }
 
-    pub fn parse_expr(chars: &mut std::iter::Peekable<Chars>, env: &mut Environment) -> Result<Expr, LispError> {
+    pub fn parse_expr(chars: &mut SourceReader, env: &mut Environment) -> Result<Expr, LispError> {
         Parser::skip_whitespace_and_comments(chars);
         if let Some(&ch) = chars.peek() {
             match ch {
@@ -54,41 +124,120 @@ impl Parser {
                     Ok(Expr::List(vec![Expr::Symbol("unquote".to_string()), unquoted_expr]))
                 }
                 '"' => Parser::parse_string(chars),
-                '-' => {
-                    chars.next(); // Consume the '-'
-                    if let Some(&next_ch) = chars.peek() {
-                        if next_ch.is_digit(10) || next_ch == '.' {
-                            Parser::parse_number_with_leading_sign(chars, true)
-                        } else if next_ch.is_whitespace() || next_ch == '(' || next_ch == ')' {
-                            // Treat as a subtraction operator if followed by space, open or close parenthesis
-                            Ok(Expr::Symbol("-".to_string()))
-                        } else {
-                            Parser::parse_symbol_with_leading_minus(chars)
-                        }
-                    } else {
-                        Err(LispError::new("Invalid number"))
-                    }
-                }
-                '0'..='9' => Parser::parse_number(chars),
-                _ => Parser::parse_symbol(chars),
+                '#' => Parser::parse_dispatch(chars, env),
+                _ => Parser::parse_atom(chars, env),
             }
         } else {
-            Err(LispError::new("Unexpected end of input"))
+            Err(chars.error("Unexpected end of input"))
         }
     }    
         
-    fn parse_symbol_with_leading_minus(chars: &mut std::iter::Peekable<Chars>) -> Result<Expr, LispError> {
-        let mut symbol = String::from("-");
-        while let Some(&ch) = chars.peek() {
-            if ch.is_whitespace() || ch == '(' || ch == ')' {
-                break;
+    // # 开头的读取宏：#C(re im) 复数，#(...) 向量，#nA(...) n 维数组，#S(name :slot value...) 结构实例，#:name 未驻留符号
+    fn parse_dispatch(chars: &mut SourceReader, env: &mut Environment) -> Result<Expr, LispError> {
+        let start = chars.current_position();
+        chars.next(); // Skip '#'
+        match chars.peek() {
+            Some('C') | Some('c') => {
+                chars.next();
+                if chars.peek() != Some(&'(') {
+                    return Err(chars.error("#C must be followed by a list"));
+                }
+                match Parser::parse_list(chars, env)? {
+                    Expr::List(parts) if parts.len() == 2 => {
+                        Arithmetic::make_complex(parts[0].clone(), parts[1].clone())
+                            .map_err(|_| LispError::new("Invalid complex number").with_position(start))
+                    }
+                    _ => Err(LispError::new("Invalid complex number").with_position(start)),
+                }
             }
-            symbol.push(chars.next().unwrap());
+            Some(':') => Parser::parse_uninterned_symbol(chars),
+            Some('S') | Some('s') => {
+                chars.next();
+                if chars.peek() != Some(&'(') {
+                    return Err(chars.error("#S must be followed by a list"));
+                }
+                let parts = match Parser::parse_list(chars, env)? {
+                    Expr::List(parts) => parts,
+                    _ => return Err(LispError::new("Invalid #S syntax").with_position(start)),
+                };
+                Parser::read_structure(&parts, env).map_err(|err| err.with_position(start))
+            }
+            Some('(') => match Parser::parse_list(chars, env)? {
+                Expr::List(items) => Ok(ArrayOps::make(Array::vector(items))),
+                _ => Err(LispError::new("Invalid vector syntax").with_position(start)),
+            },
+            Some('\\') => Parser::parse_character(chars, start),
+            Some('x') | Some('X') => Parser::parse_radix_number(chars, 16),
+            Some('b') | Some('B') => Parser::parse_radix_number(chars, 2),
+            Some('o') | Some('O') => Parser::parse_radix_number(chars, 8),
+            Some(ch) if ch.is_ascii_digit() => {
+                // #NrDIGITS
+                let mut digits = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !ch.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(ch);
+                    chars.next();
+                }
+                match (chars.peek(), digits.parse::<u32>()) {
+                    (Some('r') | Some('R'), Ok(radix)) if (2..=36).contains(&radix) => {
+                        Parser::parse_radix_number(chars, radix)
+                    }
+                    (Some('r') | Some('R'), _) => Err(chars.error(&format!("Invalid radix {} in #{}r", digits, digits))),
+                    (Some('a') | Some('A'), Ok(rank)) => {
+                        chars.next();
+                        let contents = Parser::parse_expr(chars, env)?;
+                        Array::from_nested(rank as usize, &contents)
+                            .map(ArrayOps::make)
+                            .map_err(|message| LispError::new(&format!("Invalid #{}A syntax: {}", rank, message)).with_position(start))
+                    }
+                    _ => Err(chars.error(&format!("Unknown reader macro #{}", digits))),
+                }
+            }
+            Some(&ch) => Err(chars.error(&format!("Unknown reader macro #{}", ch))),
+            None => Err(chars.error("Unexpected end of input")),
         }
-        Ok(Expr::Symbol(symbol))
     }
-    
-    fn parse_list(chars: &mut std::iter::Peekable<Chars>, env: &mut Environment) -> Result<Expr, LispError> {
+
+    // #S(name :slot value...) 用结构的标准构造函数创建实例；槽值不求值，槽名也可以不带冒号
+    fn read_structure(parts: &[Expr], env: &mut Environment) -> Result<Expr, LispError> {
+        let name = match parts.first() {
+            Some(Expr::Symbol(name)) => name,
+            _ => return Err(LispError::new("#S: structure name must be a symbol")),
+        };
+        let constructor = match env.get_structure(name) {
+            Some(structure) => structure.constructor.clone(),
+            None => return Err(LispError::new(&format!("#S: {} is not a defined structure", name))),
+        };
+        let constructor = constructor.ok_or_else(|| LispError::new(&format!("#S: {} has no standard constructor", name)))?;
+        let args = parts[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, part)| match part {
+                Expr::Symbol(slot) if i % 2 == 0 && !part.is_keyword() => Expr::Symbol(format!(":{}", package::symbol_name(slot))),
+                _ => part.clone(),
+            })
+            .collect();
+        Lambda::funcall(&Expr::Symbol(constructor), args, env)
+    }
+
+    // #\a 是单个字符；#\ 之后有多个字符时是字符名，例如 #\Space 或 #\U+3BB
+    fn parse_character(chars: &mut SourceReader, start: SourcePosition) -> Result<Expr, LispError> {
+        chars.next(); // Skip '\\'
+        let first = chars.next().ok_or_else(|| chars.error("Unexpected end of input"))?;
+        let rest = Parser::read_token(chars);
+        if rest.is_empty() {
+            return Ok(Expr::Char(first));
+        }
+        let name = format!("{}{}", first, rest);
+        character::from_name(&name)
+            .map(Expr::Char)
+            .ok_or_else(|| LispError::new(&format!("Unknown character name #\\{}", name)).with_position(start))
+    }
+
+    fn parse_list(chars: &mut SourceReader, env: &mut Environment) -> Result<Expr, LispError> {
+        let start = chars.current_position();
         chars.next(); // Skip '('
         let mut list = Vec::new();
         loop {
@@ -98,117 +247,177 @@ impl Parser {
                     chars.next(); // Skip ')'
                     break;
                 }
+                // 单独的 . 引出不正规列表的尾部，例如 (a . b) 和 (a b . c)
+                if ch == '.' && chars.peek_second().is_none_or(Parser::is_delimiter) {
+                    return Parser::parse_dotted_tail(chars, list, env);
+                }
                 list.push(Parser::parse_expr(chars, env)?);
             } else {
-                return Err(LispError::new("Parse Error: Unexpected end of list")); // 确保在缺少右括号时生成错误
+                // 确保在缺少右括号时生成错误，并指向未闭合的左括号
+                return Err(LispError::new("Parse Error: Unexpected end of list").with_position(start));
             }
         }
     
         // 检查第一个符号是否为 defmacro
         if let Some(Expr::Symbol(ref sym)) = list.first() {
             if sym == "defmacro" {
-                return MacroExpander::parse_defmacro(&list, env);
+                return MacroExpander::parse_defmacro(&list, env).map_err(|err| err.with_position(start));
             }
         }
-    
-        Ok(Expr::List(list))
+
+        let list = Expr::List(list);
+        env.record_source(&list, start);
+        Ok(list)
     }    
 
-    fn parse_symbol(chars: &mut std::iter::Peekable<Chars>) -> Result<Expr, LispError> {
-        let mut symbol = String::new();
-        while let Some(&ch) = chars.peek() {
-            if ch.is_whitespace() || ch == '(' || ch == ')' {
-                break;
-            }
-            symbol.push(chars.next().unwrap());
+    // 点之后恰好一个形式，然后是右括号；结果由尾部开始逐个 cons 起来，尾部是列表时得到普通列表
+    fn parse_dotted_tail(chars: &mut SourceReader, items: Vec<Expr>, env: &mut Environment) -> Result<Expr, LispError> {
+        if items.is_empty() {
+            return Err(chars.error("nothing before . in list"));
+        }
+        chars.next(); // Skip '.'
+        Parser::skip_whitespace_and_comments(chars);
+        if chars.peek().is_none_or(|&ch| ch == ')') {
+            return Err(chars.error("missing form after . in list"));
+        }
+        let tail = Parser::parse_expr(chars, env)?;
+        Parser::skip_whitespace_and_comments(chars);
+        match chars.next() {
+            Some(')') => Ok(items.into_iter().rev().fold(tail, |cdr, car| Expr::cons(car, cdr))),
+            _ => Err(chars.error("expected ) after the form following .")),
         }
-        Ok(Expr::Symbol(symbol))
     }
 
-    fn parse_number_with_leading_sign(chars: &mut std::iter::Peekable<Chars>, is_negative: bool) -> Result<Expr, LispError> {
-        let mut number = String::new();
-    
-        if is_negative {
-            number.push('-');
+    fn is_delimiter(ch: char) -> bool {
+        ch.is_whitespace() || ch == '(' || ch == ')' || ch == '"' || ch == ';'
+    }
+
+    // 读取到分隔符为止的一个记号
+    // #:name 每次读到都是一个新的未驻留符号，和任何包中的符号都不相同
+    fn parse_uninterned_symbol(chars: &mut SourceReader) -> Result<Expr, LispError> {
+        chars.next(); // Skip ':'
+        let name = Parser::read_token(chars);
+        if name.is_empty() || name.contains(':') {
+            return Err(chars.error(&format!("Invalid uninterned symbol #:{}", name)));
         }
-    
-        let mut is_float = false;
-    
+        Ok(Expr::Symbol(package::make_uninterned(&name)))
+    }
+
+    fn read_token(chars: &mut SourceReader) -> String {
+        let mut token = String::new();
         while let Some(&ch) = chars.peek() {
-            if ch.is_digit(10) || ch == '.' {
-                if ch == '.' {
-                    if is_float {
-                        return Err(LispError::new("Invalid float"));
-                    }
-                    is_float = true;
-                }
-                number.push(chars.next().unwrap());
-            } else {
+            if Parser::is_delimiter(ch) {
                 break;
             }
+            token.push(chars.next().unwrap());
         }
-    
-        // Ensure a valid number is read
-        if number.len() == 1 && is_negative {
-            return Ok(Expr::Symbol("-".to_string()));  // Treat it as a symbol if only "-"
-        }
-    
-        // Check if the number is a float or an integer
-        if is_float {
-            number.parse::<f64>()
-                .map(Expr::Float)
-                .map_err(|_| LispError::new("Invalid float"))
-        } else {
-            number.parse::<i64>()
-                .map(Expr::Number)
-                .map_err(|_| LispError::new("Invalid number"))
+        token
+    }
+
+    // 符号或数值：能按数值语法解析的记号是数值，其余的都是符号（例如 1+ 和 42abc）
+    fn parse_atom(chars: &mut SourceReader, env: &mut Environment) -> Result<Expr, LispError> {
+        let start = chars.current_position();
+        let token = Parser::read_token(chars);
+        match Parser::parse_numeric_token(&token) {
+            Ok(Some(number)) => Ok(number),
+            // 符号按当前包解析，pkg:name 和 pkg::name 指定其它包中的符号
+            Ok(None) => env.packages().read_symbol(&token).map(Expr::Symbol).map_err(|message| LispError::new(&message).with_position(start)),
+            Err(message) => Err(LispError::new(&message).with_position(start)),
         }
     }
-    
-    fn parse_number(chars: &mut std::iter::Peekable<Chars>) -> Result<Expr, LispError> {
-        let mut number = String::new();
-        let mut is_float = false;
 
-        // Read all continuous digits and periods
-        while let Some(&ch) = chars.peek() {
-            if ch.is_digit(10) || ch == '.' {
-                if ch == '.' {
-                    if is_float {
-                        return Err(LispError::new("Invalid float"));
-                    }
-                    is_float = true;
-                }
-                number.push(chars.next().unwrap());
-            } else {
-                break;
+    // 数值语法：
+    //   整数  [+-]digits[.]
+    //   分数  [+-]digits/digits
+    //   浮点数 [+-][digits][.digits][exponent]，exponent 为 [eEdDfFsSlL][+-]digits，
+    //         至少要有一个数字，并且有小数部分或者指数
+    pub fn parse_numeric_token(token: &str) -> Result<Option<Expr>, String> {
+        let is_digits = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+        let (sign, unsigned) = match token.strip_prefix(['+', '-']) {
+            Some(rest) => (&token[..1], rest),
+            None => ("", token),
+        };
+
+        let integer = unsigned.strip_suffix('.').unwrap_or(unsigned);
+        if is_digits(integer) {
+            return Ok(Parser::parse_integer(&format!("{}{}", sign, integer), 10));
+        }
+
+        if let Some((numerator, denominator)) = unsigned.split_once('/') {
+            if !is_digits(numerator) || !is_digits(denominator) {
+                return Ok(None);
             }
+            return Parser::make_ratio(&format!("{}{}", sign, numerator), denominator, 10).map(Some);
         }
 
-        // Ensure a valid number is read
-        if number.is_empty() || number == "." {
-            return Err(LispError::new("Invalid number"));
+        let (mantissa, exponent) = match unsigned.find(|ch: char| "eEdDfFsSlL".contains(ch)) {
+            Some(index) => (&unsigned[..index], Some(&unsigned[index + 1..])),
+            None => (unsigned, None),
+        };
+        let (whole, fraction) = match mantissa.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (mantissa, None),
+        };
+        let valid_mantissa = (whole.is_empty() || is_digits(whole))
+            && fraction.is_none_or(|fraction| fraction.is_empty() || is_digits(fraction))
+            && (is_digits(whole) || fraction.is_some_and(is_digits));
+        let valid_exponent = exponent.is_none_or(|exponent| is_digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent)));
+        if !valid_mantissa || !valid_exponent || (fraction.is_none() && exponent.is_none()) {
+            return Ok(None);
         }
 
-        // Check next character legality
-        if let Some(&ch) = chars.peek() {
-            if !ch.is_whitespace() && ch != '(' && ch != ')' && ch != ';' {
-                return Err(LispError::new("Invalid number"));
+        let text = format!(
+            "{}{}.{}e{}",
+            sign,
+            if whole.is_empty() { "0" } else { whole },
+            fraction.filter(|fraction| !fraction.is_empty()).unwrap_or("0"),
+            exponent.unwrap_or("0")
+        );
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Some(Expr::Float(value))),
+            _ => Err(format!("Invalid float: {} is out of range", token)),
+        }
+    }
+
+    // #x、#b、#o、#Nr 之后的有理数，dispatch 字符位于 chars.peek()
+    fn parse_radix_number(chars: &mut SourceReader, radix: u32) -> Result<Expr, LispError> {
+        chars.next(); // Skip the radix character
+        let token = Parser::read_token(chars);
+        let is_digits = |text: &str| !text.is_empty() && text.chars().all(|ch| ch.is_digit(radix));
+        let (sign, unsigned) = match token.strip_prefix(['+', '-']) {
+            Some(rest) => (&token[..1], rest),
+            None => ("", token.as_str()),
+        };
+        let result = match unsigned.split_once('/') {
+            Some((numerator, denominator)) if is_digits(numerator) && is_digits(denominator) => {
+                Parser::make_ratio(&format!("{}{}", sign, numerator), denominator, radix).map_err(|message| chars.error(&message))
             }
+            None if is_digits(unsigned) => Parser::parse_integer(&format!("{}{}", sign, unsigned), radix)
+                .ok_or_else(|| chars.error("Invalid number")),
+            _ => Err(chars.error(&format!("Invalid number {} in radix {}", token, radix))),
+        };
+        result
+    }
+
+    fn make_ratio(numerator: &str, denominator: &str, radix: u32) -> Result<Expr, String> {
+        match (BigInt::parse(numerator, radix), BigInt::parse(denominator, radix)) {
+            (Some(numerator), Some(denominator)) => Ratio::new(numerator, denominator)
+                .map(Expr::from_ratio)
+                .ok_or_else(|| "Invalid ratio: zero denominator".to_string()),
+            _ => Err("Invalid ratio".to_string()),
         }
+    }
 
-        // Parse as integer or float
-        if is_float {
-            number.parse::<f64>()
-                .map(Expr::Float)
-                .map_err(|_| LispError::new("Invalid float"))
-        } else {
-            number.parse::<i64>()
-                .map(Expr::Number)
-                .map_err(|_| LispError::new("Invalid number"))
+    // 超出 i64 范围的整数字面量读作任意精度整数
+    fn parse_integer(number: &str, radix: u32) -> Option<Expr> {
+        match i64::from_str_radix(number, radix) {
+            Ok(n) => Some(Expr::Number(n)),
+            Err(_) => BigInt::parse(number, radix).map(Expr::from_bigint),
         }
     }
-    
-    fn parse_string(chars: &mut std::iter::Peekable<Chars>) -> Result<Expr, LispError> {
+
+    fn parse_string(chars: &mut SourceReader) -> Result<Expr, LispError> {
+        let start = chars.current_position();
         chars.next(); // Skip '"'
         let mut string = String::new();
         while let Some(&ch) = chars.peek() {
@@ -220,11 +429,11 @@ impl Parser {
                 _ => string.push(chars.next().unwrap()),
             }
         }
-        Err(LispError::new("Unterminated string literal"))
+        Err(LispError::new("Unterminated string literal").with_position(start))
     }
 
     // Skip whitespace characters and comments
-    fn skip_whitespace_and_comments(chars: &mut std::iter::Peekable<Chars>) {
+    fn skip_whitespace_and_comments(chars: &mut SourceReader) {
         while let Some(&ch) = chars.peek() {
             if ch.is_whitespace() || ch == '\n' || ch == '\r' {
                 chars.next();
@@ -244,6 +453,7 @@ impl Parser {
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
     use crate::expression::Expr;
@@ -284,10 +494,11 @@ mod tests {
 
     #[test]
     fn test_parse_invalid_number() {
+        // 不能解析为数值的记号是符号
         let input = "42abc";
         let mut env = Environment::initialize();
         let result = Parser::read(input, &mut env);
-        assert_eq!(result, Err(LispError::new("Invalid number")));
+        assert_eq!(result, Ok(Expr::Symbol("42abc".to_string())));
     }
 
     #[test]
@@ -311,7 +522,7 @@ mod tests {
         let input = "-";
         let mut env = Environment::initialize();
         let result = Parser::read(input, &mut env);
-        assert_eq!(result, Err(LispError::new("Invalid number")));
+        assert_eq!(result, Ok(Expr::Symbol("-".to_string())));
     }
 
     #[test]
@@ -542,7 +753,10 @@ mod tests {
         let input = "3.14.159";
         let mut env = Environment::initialize();
         let result = Parser::read(input, &mut env);
-        assert_eq!(result, Err(LispError::new("Invalid float")));
+        assert_eq!(result, Ok(Expr::Symbol("3.14.159".to_string())));
+
+        let result = Parser::read("1e999", &mut env);
+        assert_eq!(result, Err(LispError::new("Invalid float: 1e999 is out of range")));
     }
 
     #[test]
@@ -706,5 +920,166 @@ mod tests {
             assert_eq!(eval_result, Ok(Expr::Number(15)));
         }
     }
-    
+
+    #[test]
+    fn test_parse_error_reports_position() {
+        let mut env = Environment::initialize();
+
+        // 未闭合的列表指向其左括号
+        let result = Parser::read_from("(+ 1\n  (* 2 3", "test.lisp", 10, &mut env);
+        let err = result.unwrap_err();
+        assert_eq!(err.position(), Some(&SourcePosition::new("test.lisp", 11, 3)));
+        assert_eq!(err.to_string(), "Parse Error: Unexpected end of list at test.lisp:11:3");
+
+        // 多余的输入指向出错的字符
+        let err = Parser::read("(+ 1 2))", &mut env).unwrap_err();
+        assert_eq!(err.position(), Some(&SourcePosition::new("<input>", 1, 8)));
+
+        let err = Parser::read("\n 1e999", &mut env).unwrap_err();
+        assert_eq!(err.position(), Some(&SourcePosition::new("<input>", 2, 2)));
+    }
+
+    #[test]
+    fn test_parse_ratio() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("1/3", &mut env).unwrap().to_string(), "1/3");
+        assert_eq!(Parser::read("-4/6", &mut env).unwrap().to_string(), "-2/3");
+        assert_eq!(Parser::read("6/3", &mut env).unwrap(), Expr::Number(2));
+        assert_eq!(
+            Parser::read("(+ 1/2 1/3)", &mut env).unwrap().to_string(),
+            "(+ 1/2 1/3)"
+        );
+
+        let err = Parser::read("1/0", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid ratio: zero denominator"));
+        assert_eq!(Parser::read("1/x", &mut env), Ok(Expr::Symbol("1/x".to_string())));
+    }
+
+    #[test]
+    fn test_parse_complex() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("#C(1 2)", &mut env).unwrap().to_string(), "#C(1 2)");
+        assert_eq!(Parser::read("#c(1/2 -1)", &mut env).unwrap().to_string(), "#C(1/2 -1)");
+        assert_eq!(Parser::read("#C(3 0)", &mut env).unwrap(), Expr::Number(3));
+        assert_eq!(
+            Parser::read("#C(1.5 0)", &mut env).unwrap(),
+            Expr::Complex(Box::new(Expr::Float(1.5)), Box::new(Expr::Float(0.0)))
+        );
+
+        let err = Parser::read("#C(1 2 3)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid complex number"));
+        let err = Parser::read("#C(a 2)", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid complex number"));
+        let err = Parser::read("#Q", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Unknown reader macro #Q"));
+    }
+
+    #[test]
+    fn test_parse_uninterned_symbol() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("#:G1", &mut env).unwrap().to_string(), "#:G1");
+        assert_ne!(Parser::read("#:G1", &mut env).unwrap(), Parser::read("#:G1", &mut env).unwrap());
+        let err = Parser::read("#:", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid uninterned symbol #:"));
+        let err = Parser::read("#:a:b", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid uninterned symbol #:a:b"));
+    }
+
+    #[test]
+    fn test_parse_increment_symbols() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("1+", &mut env), Ok(Expr::Symbol("1+".to_string())));
+        assert_eq!(
+            Parser::read("(1- x)", &mut env),
+            Ok(Expr::List(vec![Expr::Symbol("1-".to_string()), Expr::Symbol("x".to_string())]))
+        );
+    }
+
+    #[test]
+    fn test_parse_extended_number_syntax() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("+5", &mut env), Ok(Expr::Number(5)));
+        assert_eq!(Parser::read("42.", &mut env), Ok(Expr::Number(42)));
+        assert_eq!(Parser::read("1e10", &mut env), Ok(Expr::Float(1e10)));
+        assert_eq!(Parser::read("-2.5E3", &mut env), Ok(Expr::Float(-2500.0)));
+        assert_eq!(Parser::read(".5e-3", &mut env), Ok(Expr::Float(0.0005)));
+        assert_eq!(Parser::read(".5", &mut env), Ok(Expr::Float(0.5)));
+        assert_eq!(Parser::read("-.5", &mut env), Ok(Expr::Float(-0.5)));
+        assert_eq!(Parser::read("1.5d0", &mut env), Ok(Expr::Float(1.5)));
+        assert_eq!(Parser::read("+3/6", &mut env).unwrap().to_string(), "1/2");
+
+        for symbol in ["+", "-", ".", "1e", "e5", "1.5e+", "+.", "1+", "1-", "-abc", "a1", "1..2"] {
+            assert_eq!(Parser::read(symbol, &mut env), Ok(Expr::Symbol(symbol.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_parse_radix_literals() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("#xFF", &mut env), Ok(Expr::Number(255)));
+        assert_eq!(Parser::read("#x-ff", &mut env), Ok(Expr::Number(-255)));
+        assert_eq!(Parser::read("#b1010", &mut env), Ok(Expr::Number(10)));
+        assert_eq!(Parser::read("#o777", &mut env), Ok(Expr::Number(511)));
+        assert_eq!(Parser::read("#3r120", &mut env), Ok(Expr::Number(15)));
+        assert_eq!(Parser::read("#36rZZ", &mut env), Ok(Expr::Number(1295)));
+        assert_eq!(Parser::read("#x1/F", &mut env).unwrap().to_string(), "1/15");
+        assert_eq!(
+            Parser::read("#x10000000000000000", &mut env).unwrap().to_string(),
+            "18446744073709551616"
+        );
+        assert_eq!(
+            Parser::read("(#b11 #o11)", &mut env),
+            Ok(Expr::List(vec![Expr::Number(3), Expr::Number(9)]))
+        );
+
+        let err = Parser::read("#b102", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid number 102 in radix 2"));
+        let err = Parser::read("#37r1", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Invalid radix 37 in #37r"));
+        let err = Parser::read("#12", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Unknown reader macro #12"));
+    }
+
+    #[test]
+    fn test_parse_characters() {
+        let mut env = Environment::initialize();
+        assert_eq!(Parser::read("#\\a", &mut env), Ok(Expr::Char('a')));
+        assert_eq!(Parser::read("#\\A", &mut env), Ok(Expr::Char('A')));
+        assert_eq!(Parser::read("#\\Space", &mut env), Ok(Expr::Char(' ')));
+        assert_eq!(Parser::read("#\\newline", &mut env), Ok(Expr::Char('\n')));
+        assert_eq!(Parser::read("#\\λ", &mut env), Ok(Expr::Char('λ')));
+        assert_eq!(Parser::read("#\\U+41", &mut env), Ok(Expr::Char('A')));
+        assert_eq!(
+            Parser::read("(#\\( #\\) #\\ )", &mut env),
+            Ok(Expr::List(vec![Expr::Char('('), Expr::Char(')'), Expr::Char(' ')]))
+        );
+        assert_eq!(Expr::Char(' ').to_string(), "#\\Space");
+        assert_eq!(Expr::Char('x').to_string(), "#\\x");
+
+        let err = Parser::read("#\\Bogus", &mut env).unwrap_err();
+        assert_eq!(err, LispError::new("Unknown character name #\\Bogus"));
+    }
+
+    #[test]
+    fn test_parse_dotted_lists() {
+        let mut env = Environment::initialize();
+        let pair = |car: Expr, cdr: Expr| Expr::DottedPair(Box::new(car), Box::new(cdr));
+        let symbol = |name: &str| Expr::Symbol(name.to_string());
+        assert_eq!(Parser::read("(a . b)", &mut env), Ok(pair(symbol("a"), symbol("b"))));
+        assert_eq!(
+            Parser::read("(1 2 . 3)", &mut env),
+            Ok(pair(Expr::Number(1), pair(Expr::Number(2), Expr::Number(3))))
+        );
+        // 尾部是列表时就是普通列表
+        assert_eq!(Parser::read("(1 . (2 3))", &mut env), Ok(Expr::List(vec![Expr::Number(1), Expr::Number(2), Expr::Number(3)])));
+        assert_eq!(Parser::read("(1 . nil)", &mut env), Ok(Expr::List(vec![Expr::Number(1)])));
+        // 点后面紧跟其它字符时仍然是符号或数值的一部分
+        assert_eq!(Parser::read("(a .b .5)", &mut env), Ok(Expr::List(vec![symbol("a"), symbol(".b"), Expr::Float(0.5)])));
+        assert_eq!(Parser::read("(1 2 . 3)", &mut env).unwrap().to_string(), "(1 2 . 3)");
+        assert_eq!(Parser::read("((a . 1) (b . 2))", &mut env).unwrap().to_string(), "((a . 1) (b . 2))");
+
+        assert_eq!(Parser::read("( . a)", &mut env), Err(LispError::new("nothing before . in list")));
+        assert_eq!(Parser::read("(a . )", &mut env), Err(LispError::new("missing form after . in list")));
+        assert_eq!(Parser::read("(a . b c)", &mut env), Err(LispError::new("expected ) after the form following .")));
+    }
 }
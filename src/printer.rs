@@ -0,0 +1,177 @@
+// printer.rs
+
+use crate::bignum::BigInt;
+use crate::environment::Environment;
+use crate::expression::Expr;
+use crate::operator::arithmetic::Arithmetic;
+use crate::package::{self, Packages};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// 打印选项，对应特殊变量 *print-base* 和 *print-radix*，以及决定符号怎样打印的包系统
+#[derive(Clone, Debug)]
+pub struct PrintOptions {
+    // 有理数的输出进制，2 到 36
+    pub base: u32,
+    // 是否输出进制前缀（#x、#b、#o、#Nr，十进制整数以 . 结尾），使输出在任意进制下都能读回
+    pub radix: bool,
+    // 符号相对于其中的当前包打印；没有包系统时按内部表示打印
+    pub packages: Option<Rc<RefCell<Packages>>>,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions { base: 10, radix: false, packages: None }
+    }
+}
+
+impl PrintOptions {
+    // *print-base* 不是 2 到 36 之间的整数时按 10 处理
+    pub fn from_env(env: &Environment) -> Self {
+        let base = match env.get_symbol("*print-base*") {
+            Some(Expr::Number(n)) if (2..=36).contains(n) => *n as u32,
+            _ => 10,
+        };
+        let radix = env.get_symbol("*print-radix*").is_some_and(Expr::is_truthy);
+        PrintOptions { base, radix, packages: Some(env.shared_packages()) }
+    }
+
+    fn symbol(&self, symbol: &str) -> String {
+        match &self.packages {
+            Some(packages) if package::uninterned_name(symbol).is_none() => packages.borrow().print_name(symbol),
+            _ => Expr::Symbol(symbol.to_string()).to_string(),
+        }
+    }
+}
+
+pub struct Printer;
+
+impl Printer {
+    pub fn print(expr: &Expr, options: &PrintOptions) -> String {
+        match expr {
+            Expr::Number(_) | Expr::BigInt(_) => {
+                let digits = Printer::digits(expr, options.base);
+                match (options.radix, options.base) {
+                    (false, _) => digits,
+                    (true, 10) => format!("{}.", digits),
+                    (true, base) => format!("{}{}", Printer::radix_prefix(base), digits),
+                }
+            }
+            Expr::Ratio(r) => {
+                let numerator = Expr::from_bigint(r.numerator().clone());
+                let denominator = Expr::from_bigint(r.denominator().clone());
+                let text = format!(
+                    "{}/{}",
+                    Printer::digits(&numerator, options.base),
+                    Printer::digits(&denominator, options.base)
+                );
+                if options.radix {
+                    format!("{}{}", Printer::radix_prefix(options.base), text)
+                } else {
+                    text
+                }
+            }
+            Expr::Complex(re, im) => {
+                format!("#C({} {})", Printer::print(re, options), Printer::print(im, options))
+            }
+            Expr::List(list) => {
+                let items: Vec<String> = list.iter().map(|item| Printer::print(item, options)).collect();
+                format!("({})", items.join(" "))
+            }
+            Expr::DottedPair(car, cdr) => Expr::render_dotted(car, cdr, &|item| Printer::print(item, options)),
+            Expr::Array(array) => array.borrow().render(&|item| Printer::print(item, options)),
+            Expr::Struct(instance) => instance.borrow().render(&|item| Printer::print(item, options)),
+            Expr::Symbol(symbol) => options.symbol(symbol),
+            Expr::Instance(instance) => format!("#<{}>", options.symbol(&instance.borrow().class().name)),
+            _ => expr.to_string(),
+        }
+    }
+
+    // princ 形式：字符串不带引号，字符不带 #\ 前缀，其余与 print 相同
+    pub fn princ(expr: &Expr, options: &PrintOptions) -> String {
+        match expr {
+            Expr::Str(s) => s.clone(),
+            Expr::Char(ch) => ch.to_string(),
+            Expr::List(list) => {
+                let items: Vec<String> = list.iter().map(|item| Printer::princ(item, options)).collect();
+                format!("({})", items.join(" "))
+            }
+            Expr::DottedPair(car, cdr) => Expr::render_dotted(car, cdr, &|item| Printer::princ(item, options)),
+            Expr::Array(array) => array.borrow().render(&|item| Printer::princ(item, options)),
+            Expr::Struct(instance) => instance.borrow().render(&|item| Printer::princ(item, options)),
+            _ => Printer::print(expr, options),
+        }
+    }
+
+    fn digits(integer: &Expr, base: u32) -> String {
+        Arithmetic::to_bigint(integer)
+            .unwrap_or_else(|| BigInt::from_i64(0))
+            .to_string_radix(base)
+    }
+
+    fn radix_prefix(base: u32) -> String {
+        match base {
+            2 => "#b".to_string(),
+            8 => "#o".to_string(),
+            16 => "#x".to_string(),
+            _ => format!("#{}r", base),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::Evaluator;
+    use crate::parser::Parser;
+
+    fn print_with(input: &str, base: u32, radix: bool) -> String {
+        let mut env = Environment::initialize();
+        let value = Evaluator::eval(&Parser::read(input, &mut env).unwrap(), &mut env).unwrap();
+        Printer::print(&value, &PrintOptions { base, radix, packages: None })
+    }
+
+    #[test]
+    fn test_print_base() {
+        assert_eq!(print_with("255", 10, false), "255");
+        assert_eq!(print_with("255", 16, false), "FF");
+        assert_eq!(print_with("-255", 2, false), "-11111111");
+        assert_eq!(print_with("18446744073709551616", 16, false), "10000000000000000");
+        assert_eq!(print_with("(/ 1 10)", 16, false), "1/A");
+        assert_eq!(print_with("'(10 (11 . 12) 1.5)", 16, false), "(A (B . C) 1.5)");
+        assert_eq!(print_with("#C(10 11)", 8, false), "#C(12 13)");
+    }
+
+    #[test]
+    fn test_print_radix_round_trips() {
+        assert_eq!(print_with("255", 16, true), "#xFF");
+        assert_eq!(print_with("5", 2, true), "#b101");
+        assert_eq!(print_with("8", 8, true), "#o10");
+        assert_eq!(print_with("15", 3, true), "#3r120");
+        assert_eq!(print_with("42", 10, true), "42.");
+        assert_eq!(print_with("(/ 1 3)", 10, true), "#10r1/3");
+
+        let mut env = Environment::initialize();
+        for (base, text) in [(16, "-123456789012345678901234567890"), (7, "(/ -22 7)"), (10, "-17")] {
+            let value = Evaluator::eval(&Parser::read(text, &mut env).unwrap(), &mut env).unwrap();
+            let printed = Printer::print(&value, &PrintOptions { base, radix: true, packages: None });
+            assert_eq!(Parser::read(&printed, &mut env), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_options_from_env() {
+        let mut env = Environment::initialize();
+        let options = PrintOptions::from_env(&env);
+        assert_eq!((options.base, options.radix), (10, false));
+        assert!(options.packages.is_some());
+
+        env.set_symbol("*print-base*".to_string(), Expr::Number(16));
+        env.set_symbol("*print-radix*".to_string(), Expr::t());
+        let options = PrintOptions::from_env(&env);
+        assert_eq!((options.base, options.radix), (16, true));
+
+        env.set_symbol("*print-base*".to_string(), Expr::Number(99));
+        assert_eq!(PrintOptions::from_env(&env).base, 10);
+    }
+}
@@ -0,0 +1,92 @@
+// random.rs
+
+use crate::bignum::BigInt;
+
+// 伪随机数发生器：SplitMix64（Steele、Lea、Flood，2014）。
+// 状态只有一个 u64，每次加上固定的黄金分割增量后做两轮乘法混合再输出。
+// 算法完全由整数的回绕运算定义，同一个种子在任何平台上都产生同样的序列。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomState {
+    state: u64,
+}
+
+impl RandomState {
+    pub fn from_seed(seed: u64) -> Self {
+        RandomState { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [0, limit) 内均匀分布的整数，limit 必须为正。
+    // 丢弃落在最后一个不完整区间里的输出，避免取模带来的偏差。
+    pub fn below_u64(&mut self, limit: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % limit;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % limit;
+            }
+        }
+    }
+
+    // [0, limit) 内的任意精度整数，limit 必须为正。
+    // 多取 64 位随机数再取模，偏差小于 2^-64。
+    pub fn below_bigint(&mut self, limit: &BigInt) -> BigInt {
+        let words = limit.integer_length() / 64 + 2;
+        let mut value = BigInt::from_i64(0);
+        for _ in 0..words {
+            value = value.shift_left(64).or(&BigInt::from_i128(self.next_u64() as i128));
+        }
+        value.div_rem(limit).map(|(_, rem)| rem).unwrap_or(value)
+    }
+
+    // [0, 1) 内的浮点数，取 53 位随机数作为尾数
+    pub fn unit_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splitmix64_reference_sequence() {
+        // 种子 0 的前几个输出，与参考实现一致
+        let mut state = RandomState::from_seed(0);
+        assert_eq!(state.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(state.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(state.next_u64(), 0x06C4_5D18_8009_454F);
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = RandomState::from_seed(42);
+        let mut b = RandomState::from_seed(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(RandomState::from_seed(1).next_u64(), RandomState::from_seed(2).next_u64());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut state = RandomState::from_seed(7);
+        for _ in 0..1000 {
+            assert!(state.below_u64(6) < 6);
+            let x = state.unit_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+        let limit = BigInt::parse("100000000000000000000000000000", 10).unwrap();
+        for _ in 0..100 {
+            let value = state.below_bigint(&limit);
+            assert!(!value.is_negative() && value < limit);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// ratio.rs
+
+use crate::bignum::BigInt;
+use std::cmp::Ordering;
+use std::fmt;
+
+// 精确有理数：总是约分到最简，分母为正。
+// 分母为 1 的值在 Expr 层面会被降级为整数，见 Expr::from_ratio。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl Ratio {
+    // 分母为零时返回 None
+    pub fn new(numerator: BigInt, denominator: BigInt) -> Option<Ratio> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let divisor = numerator.gcd(&denominator);
+        let (mut numerator, _) = numerator.div_rem(&divisor)?;
+        let (mut denominator, _) = denominator.div_rem(&divisor)?;
+        if denominator.is_negative() {
+            numerator = numerator.neg();
+            denominator = denominator.neg();
+        }
+        Some(Ratio { numerator, denominator })
+    }
+
+    pub fn from_integer(value: BigInt) -> Ratio {
+        Ratio { numerator: value, denominator: BigInt::from_i64(1) }
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator.to_f64() / self.denominator.to_f64()
+    }
+
+    pub fn add(&self, other: &Ratio) -> Ratio {
+        let numerator = self.numerator.mul(&other.denominator).add(&other.numerator.mul(&self.denominator));
+        Ratio::normalized(numerator, self.denominator.mul(&other.denominator))
+    }
+
+    pub fn sub(&self, other: &Ratio) -> Ratio {
+        let numerator = self.numerator.mul(&other.denominator).sub(&other.numerator.mul(&self.denominator));
+        Ratio::normalized(numerator, self.denominator.mul(&other.denominator))
+    }
+
+    pub fn mul(&self, other: &Ratio) -> Ratio {
+        Ratio::normalized(self.numerator.mul(&other.numerator), self.denominator.mul(&other.denominator))
+    }
+
+    // 除数为零时返回 None
+    pub fn div(&self, other: &Ratio) -> Option<Ratio> {
+        Ratio::new(self.numerator.mul(&other.denominator), self.denominator.mul(&other.numerator))
+    }
+
+    // 两个分母都非零，所以乘积也非零
+    fn normalized(numerator: BigInt, denominator: BigInt) -> Ratio {
+        Ratio::new(numerator, denominator).expect("denominator of a product of ratios is never zero")
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.numerator
+            .mul(&other.denominator)
+            .cmp(&other.numerator.mul(&self.denominator))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: i64, denominator: i64) -> Ratio {
+        Ratio::new(BigInt::from_i64(numerator), BigInt::from_i64(denominator)).unwrap()
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(ratio(2, 4).to_string(), "1/2");
+        assert_eq!(ratio(3, -6).to_string(), "-1/2");
+        assert_eq!(ratio(-3, -6).to_string(), "1/2");
+        assert_eq!(ratio(0, 5).to_string(), "0/1");
+        assert!(ratio(6, 3).is_integer());
+        assert_eq!(Ratio::new(BigInt::from_i64(1), BigInt::from_i64(0)), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(ratio(1, 3).add(&ratio(1, 6)), ratio(1, 2));
+        assert_eq!(ratio(1, 3).sub(&ratio(1, 2)), ratio(-1, 6));
+        assert_eq!(ratio(2, 3).mul(&ratio(3, 4)), ratio(1, 2));
+        assert_eq!(ratio(1, 3).div(&ratio(2, 3)), Some(ratio(1, 2)));
+        assert_eq!(ratio(1, 3).div(&ratio(0, 1)), None);
+    }
+
+    #[test]
+    fn test_ordering_and_float() {
+        assert!(ratio(1, 3) < ratio(1, 2));
+        assert!(ratio(-1, 2) < ratio(-1, 3));
+        assert_eq!(ratio(1, 4).to_f64(), 0.25);
+    }
+}
@@ -0,0 +1,203 @@
+// source.rs
+
+use crate::expression::Expr;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+// 源码位置：文件名、行号和列号（均从 1 开始）
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourcePosition {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourcePosition {
+    pub fn new(file: &str, line: usize, column: usize) -> Self {
+        SourcePosition {
+            file: file.to_string(),
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+// 一次读取得到的顶层形式中，每个列表形式所在的位置。
+// Expr 是按值复制的（函数体会被克隆进环境），没有可以比较的同一性，所以以形式的结构为键；
+// 同一个顶层形式里结构相同的形式取最先读到的位置。
+#[derive(Debug, Default)]
+pub struct SourceUnit {
+    forms: HashMap<u64, SourcePosition>,
+}
+
+impl SourceUnit {
+    pub fn record(&mut self, form: &Expr, position: SourcePosition) {
+        self.forms.entry(SourceUnit::key(form)).or_insert(position);
+    }
+
+    pub fn lookup(&self, form: &Expr) -> Option<&SourcePosition> {
+        self.forms.get(&SourceUnit::key(form))
+    }
+
+    // 键由整个形式计算，浮点数按位参与，所以只在深处或浮点字面量上不同的两个形式也不会共用位置
+    fn key(form: &Expr) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        SourceUnit::feed(form, &mut hasher);
+        hasher.finish()
+    }
+
+    fn feed(form: &Expr, hasher: &mut DefaultHasher) {
+        std::mem::discriminant(form).hash(hasher);
+        match form {
+            Expr::Symbol(s) | Expr::Str(s) => s.hash(hasher),
+            Expr::Number(n) => n.hash(hasher),
+            Expr::BigInt(n) => n.to_string().hash(hasher),
+            Expr::Ratio(r) => r.to_string().hash(hasher),
+            Expr::Float(x) => x.to_bits().hash(hasher),
+            Expr::Char(c) => c.hash(hasher),
+            Expr::List(items) => {
+                items.len().hash(hasher);
+                items.iter().for_each(|item| SourceUnit::feed(item, hasher));
+            }
+            Expr::Complex(left, right) | Expr::DottedPair(left, right) => {
+                SourceUnit::feed(left, hasher);
+                SourceUnit::feed(right, hasher);
+            }
+            _ => {}
+        }
+    }
+}
+
+// 源码位置表：正在读取的形式、最近读完的顶层形式，以及每个具名函数的函数体来自哪个顶层形式。
+// 函数调用期间在它自己的单元里查找位置，因此不同函数中相同的形式不会互相覆盖；
+// 单元不再被任何函数引用、也不是最近的顶层形式时随 Rc 一起释放。
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    reading: SourceUnit,
+    top_level: Rc<SourceUnit>,
+    functions: HashMap<String, Rc<SourceUnit>>,
+    active: Vec<Rc<SourceUnit>>,
+}
+
+impl SourceMap {
+    pub fn begin_read(&mut self) {
+        self.reading = SourceUnit::default();
+    }
+
+    pub fn record(&mut self, form: &Expr, position: SourcePosition) {
+        self.reading.record(form, position);
+    }
+
+    pub fn finish_read(&mut self) {
+        self.top_level = Rc::new(std::mem::take(&mut self.reading));
+    }
+
+    fn current(&self) -> &Rc<SourceUnit> {
+        self.active.last().unwrap_or(&self.top_level)
+    }
+
+    // 定义函数时记下它来自哪个单元
+    pub fn define_function(&mut self, name: &str) {
+        let unit = self.current().clone();
+        self.functions.insert(name.to_string(), unit);
+    }
+
+    // 进入具名函数的函数体；返回值表示之后是否需要 leave_function
+    pub fn enter_function(&mut self, name: &str) -> bool {
+        match self.functions.get(name) {
+            Some(unit) => {
+                self.active.push(unit.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn leave_function(&mut self) {
+        self.active.pop();
+    }
+
+    pub fn lookup(&self, form: &Expr) -> Option<&SourcePosition> {
+        self.current().lookup(form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arg: Expr) -> Expr {
+        Expr::List(vec![Expr::Symbol(name.to_string()), arg])
+    }
+
+    #[test]
+    fn test_display_position() {
+        let position = SourcePosition::new("test.lisp", 3, 7);
+        assert_eq!(position.to_string(), "test.lisp:3:7");
+    }
+
+    #[test]
+    fn test_record_and_lookup() {
+        let mut map = SourceMap::default();
+        let form = call("car", Expr::Number(5));
+        assert_eq!(map.lookup(&form), None);
+
+        map.begin_read();
+        map.record(&form, SourcePosition::new("<input>", 2, 4));
+        map.finish_read();
+        // 克隆后的形式依然能找到位置
+        assert_eq!(map.lookup(&form.clone()), Some(&SourcePosition::new("<input>", 2, 4)));
+        assert_eq!(map.lookup(&call("car", Expr::Number(6))), None);
+        assert_eq!(map.lookup(&Expr::List(vec![])), None);
+    }
+
+    #[test]
+    fn test_forms_differing_deep_inside_keep_their_positions() {
+        let mut map = SourceMap::default();
+        let nested = |n| call("f", call("g", call("h", Expr::Number(n))));
+        let floats = |x| call("car", Expr::Float(x));
+        map.begin_read();
+        map.record(&nested(1), SourcePosition::new("<input>", 1, 1));
+        map.record(&nested(2), SourcePosition::new("<input>", 2, 1));
+        map.record(&floats(1.5), SourcePosition::new("<input>", 3, 1));
+        map.record(&floats(2.5), SourcePosition::new("<input>", 4, 1));
+        map.finish_read();
+        assert_eq!(map.lookup(&nested(2)), Some(&SourcePosition::new("<input>", 2, 1)));
+        assert_eq!(map.lookup(&floats(2.5)), Some(&SourcePosition::new("<input>", 4, 1)));
+        assert_eq!(map.lookup(&nested(3)), None);
+    }
+
+    #[test]
+    fn test_functions_keep_their_own_positions() {
+        let mut map = SourceMap::default();
+        let body = call("car", Expr::Symbol("x".to_string()));
+        for (line, name) in [(1, "f1"), (2, "f2")] {
+            map.begin_read();
+            map.record(&body, SourcePosition::new("<repl>", line, 15));
+            map.finish_read();
+            map.define_function(name);
+        }
+
+        assert!(map.enter_function("f1"));
+        assert_eq!(map.lookup(&body), Some(&SourcePosition::new("<repl>", 1, 15)));
+        map.leave_function();
+        assert!(!map.enter_function("car"));
+
+        // 重新读取后，旧的顶层单元只由 f1 和 f2 引用，重新定义 f1 时释放它的单元
+        map.begin_read();
+        map.finish_read();
+        let old = Rc::downgrade(&map.functions["f1"]);
+        map.define_function("f1");
+        assert!(old.upgrade().is_none());
+        assert_eq!(map.lookup(&body), None);
+    }
+}
@@ -0,0 +1,79 @@
+// stream.rs
+
+use std::io::{self, Write};
+
+// 输出流。记录当前是否位于行首，供 fresh-line 和 format 的 ~& 使用。
+#[derive(Debug)]
+pub struct OutputStream {
+    sink: Sink,
+    at_line_start: bool,
+}
+
+#[derive(Debug)]
+enum Sink {
+    Stdout,
+    // 字符串输出流：内容累积在缓冲区里，由 get-output-stream-string 取走
+    String(String),
+}
+
+impl OutputStream {
+    pub fn stdout() -> Self {
+        OutputStream { sink: Sink::Stdout, at_line_start: true }
+    }
+
+    pub fn string() -> Self {
+        OutputStream { sink: Sink::String(String::new()), at_line_start: true }
+    }
+
+    pub fn is_string_stream(&self) -> bool {
+        matches!(self.sink, Sink::String(_))
+    }
+
+    pub fn at_line_start(&self) -> bool {
+        self.at_line_start
+    }
+
+    pub fn write_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.at_line_start = text.ends_with('\n');
+        match &mut self.sink {
+            Sink::Stdout => {
+                print!("{}", text);
+                // 标准输出写失败（例如管道已关闭）时没有更合适的去处，忽略即可
+                let _ = io::stdout().flush();
+            }
+            Sink::String(buffer) => buffer.push_str(text),
+        }
+    }
+
+    // 取走字符串输出流中已经写入的内容并清空缓冲区；标准输出流返回 None
+    pub fn take_string(&mut self) -> Option<String> {
+        match &mut self.sink {
+            Sink::String(buffer) => {
+                self.at_line_start = true;
+                Some(std::mem::take(buffer))
+            }
+            Sink::Stdout => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_stream_tracks_line_start() {
+        let mut stream = OutputStream::string();
+        assert!(stream.at_line_start());
+        stream.write_str("abc");
+        assert!(!stream.at_line_start());
+        stream.write_str("\n");
+        assert!(stream.at_line_start());
+        assert_eq!(stream.take_string(), Some("abc\n".to_string()));
+        assert_eq!(stream.take_string(), Some(String::new()));
+        assert_eq!(OutputStream::stdout().take_string(), None);
+    }
+}
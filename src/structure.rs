@@ -0,0 +1,89 @@
+// structure.rs
+
+use crate::expression::Expr;
+use crate::package;
+use std::rc::Rc;
+
+// defstruct 定义的结构类型。用 :include 继承时，父类型的槽排在前面，
+// 所以同一个槽在父类型和子类型的实例中下标相同，父类型的访问函数可以直接用于子类型。
+#[derive(Debug)]
+pub struct StructType {
+    pub name: String,
+    pub slots: Vec<Slot>,
+    // 自身以及全部祖先类型的名字，由近及远
+    pub includes: Vec<String>,
+    // 标准的关键字构造函数名，#S(...) 读入时使用；(:constructor nil) 时没有
+    pub constructor: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Slot {
+    pub name: String,
+    // 默认值形式，在构造实例时求值
+    pub default: Expr,
+    pub read_only: bool,
+}
+
+// 结构实例：共享定义，槽的值按定义中的顺序存放
+#[derive(Clone, Debug)]
+pub struct StructInstance {
+    structure: Rc<StructType>,
+    values: Vec<Expr>,
+}
+
+impl StructInstance {
+    pub fn new(structure: Rc<StructType>, values: Vec<Expr>) -> Self {
+        StructInstance { structure, values }
+    }
+
+    pub fn structure(&self) -> &StructType {
+        &self.structure
+    }
+
+    pub fn is_a(&self, name: &str) -> bool {
+        self.structure.includes.iter().any(|include| include == name)
+    }
+
+    pub fn values(&self) -> &[Expr] {
+        &self.values
+    }
+
+    pub fn get(&self, index: usize) -> &Expr {
+        &self.values[index]
+    }
+
+    pub fn set(&mut self, index: usize, value: Expr) {
+        self.values[index] = value;
+    }
+
+    // 可读的打印形式 #S(point :x 1 :y 2)，槽值的打印方式由 item 决定
+    pub fn render(&self, item: &dyn Fn(&Expr) -> String) -> String {
+        let mut parts = vec![item(&Expr::Symbol(self.structure.name.clone()))];
+        for (slot, value) in self.structure.slots.iter().zip(&self.values) {
+            parts.push(format!(":{}", package::symbol_name(&slot.name)));
+            parts.push(item(value));
+        }
+        format!("#S({})", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instances_know_their_ancestors() {
+        let slot = |name: &str| Slot { name: name.to_string(), default: Expr::nil(), read_only: false };
+        let structure = Rc::new(StructType {
+            name: "point3d".to_string(),
+            slots: vec![slot("x"), slot("y"), slot("z")],
+            includes: vec!["point3d".to_string(), "point".to_string()],
+            constructor: Some("make-point3d".to_string()),
+        });
+        let mut instance = StructInstance::new(structure, vec![Expr::Number(1), Expr::Number(2), Expr::Number(3)]);
+        assert!(instance.is_a("point"));
+        assert!(!instance.is_a("circle"));
+        instance.set(2, Expr::Str("z".to_string()));
+        assert_eq!(instance.render(&|e| e.to_string()), "#S(point3d :x 1 :y 2 :z \"z\")");
+    }
+}
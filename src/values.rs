@@ -0,0 +1,98 @@
+// values.rs
+
+use crate::expression::Expr;
+
+// 多值的旁路通道。
+// 形式的主值照常作为返回值传递；返回多个值的操作符额外把全部值登记在这里，
+// 并记下登记时所在的形式深度。只有多值的“拥有者”形式，以及把最后一个子形式的值
+// 原样传出的形式（progn、cond、函数调用等），才能把这些值交给 multiple-value-list 这样的消费者。
+#[derive(Debug, Default)]
+pub struct MultipleValues {
+    depth: usize,
+    pending: Option<(usize, Vec<Expr>)>,
+}
+
+impl MultipleValues {
+    // 开始求值任意形式：此前兄弟形式留下的多值已经失效
+    pub fn clear(&mut self) {
+        self.pending = None;
+    }
+
+    // 进入一个复合形式，返回它的深度
+    pub fn enter(&mut self) -> usize {
+        self.pending = None;
+        self.depth += 1;
+        self.depth
+    }
+
+    // 离开深度为 depth 的形式；passes_through 表示该形式的值就是它最后一个子形式的值
+    pub fn leave(&mut self, depth: usize, passes_through: bool) {
+        self.depth = depth - 1;
+        self.pending = match self.pending.take() {
+            Some((owner, values)) if owner == depth => Some((owner, values)),
+            Some((owner, values)) if owner == depth + 1 && passes_through => Some((depth, values)),
+            _ => None,
+        };
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    // 由当前正在执行的操作符登记全部返回值
+    pub fn set(&mut self, values: Vec<Expr>) {
+        self.pending = Some((self.depth, values));
+    }
+
+    // 取出深度为 depth 的形式产生的多值
+    pub fn take(&mut self, depth: usize) -> Option<Vec<Expr>> {
+        match self.pending.take() {
+            Some((owner, values)) if owner == depth => Some(values),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_reach_the_owner_form() {
+        let mut values = MultipleValues::default();
+        let depth = values.enter();
+        values.set(vec![Expr::Number(3), Expr::Number(1)]);
+        values.leave(depth, false);
+        assert_eq!(values.take(depth), Some(vec![Expr::Number(3), Expr::Number(1)]));
+    }
+
+    #[test]
+    fn test_values_pass_through_only_when_allowed() {
+        let mut values = MultipleValues::default();
+        let outer = values.enter();
+        let inner = values.enter();
+        values.set(vec![Expr::Number(3), Expr::Number(1)]);
+        values.leave(inner, false);
+        values.leave(outer, true);
+        assert_eq!(values.take(outer), Some(vec![Expr::Number(3), Expr::Number(1)]));
+
+        let outer = values.enter();
+        let inner = values.enter();
+        values.set(vec![Expr::Number(3), Expr::Number(1)]);
+        values.leave(inner, false);
+        values.leave(outer, false);
+        assert_eq!(values.take(outer), None);
+    }
+
+    #[test]
+    fn test_later_sibling_discards_values() {
+        let mut values = MultipleValues::default();
+        let outer = values.enter();
+        let inner = values.enter();
+        values.set(vec![Expr::Number(3), Expr::Number(1)]);
+        values.leave(inner, false);
+        values.clear();
+        values.leave(outer, true);
+        assert_eq!(values.take(outer), None);
+    }
+}